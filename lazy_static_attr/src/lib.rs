@@ -0,0 +1,81 @@
+// Copyright 2016 lazy-static.rs Developers
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+/*!
+An attribute form of the `lazy_static!` macro.
+
+A `static` item marked with `#[lazy_static]` is expanded to the
+corresponding `static ref` declaration of `lazy_static!`, so it behaves
+exactly like one. Unlike the macro block, the item is regular Rust syntax,
+which keeps it formatted by rustfmt and understood by IDEs.
+
+The crate using the attribute has to depend on `lazy_static` as well.
+
+```rust
+use std::collections::HashMap;
+use lazy_static_attr::lazy_static;
+
+#[lazy_static]
+static HASHMAP: HashMap<u32, &'static str> = {
+    let mut m = HashMap::new();
+    m.insert(0, "foo");
+    m
+};
+
+fn main() {
+    assert_eq!(HASHMAP.get(&0), Some(&"foo"));
+}
+```
+
+A `static mut` item is expanded to a `static ref mut` declaration, which is
+wrapped in a mutex.
+
+```rust
+use lazy_static_attr::lazy_static;
+
+#[lazy_static]
+static mut NAMES: Vec<&'static str> = Vec::new();
+
+fn main() {
+    NAMES.lock().push("foo");
+    assert_eq!(NAMES.lock().len(), 1);
+}
+```
+*/
+
+extern crate proc_macro;
+
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{parse_macro_input, Error, ItemStatic, StaticMutability};
+
+/// Turns a `static` item into a lazily initialized static.
+///
+/// See the [crate documentation](index.html) for details.
+#[proc_macro_attribute]
+pub fn lazy_static(args: TokenStream, input: TokenStream) -> TokenStream {
+    if !args.is_empty() {
+        let args = proc_macro2::TokenStream::from(args);
+        return Error::new_spanned(args, "`#[lazy_static]` does not take arguments")
+            .to_compile_error()
+            .into();
+    }
+
+    let ItemStatic { attrs, vis, mutability, ident, ty, expr, .. } =
+        parse_macro_input!(input as ItemStatic);
+    let mutability = match mutability {
+        StaticMutability::Mut(token) => Some(token),
+        _ => None,
+    };
+
+    TokenStream::from(quote! {
+        ::lazy_static::lazy_static! {
+            #(#attrs)*
+            #vis static ref #mutability #ident: #ty = #expr;
+        }
+    })
+}
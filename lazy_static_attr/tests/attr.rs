@@ -0,0 +1,42 @@
+use std::collections::HashMap;
+use lazy_static_attr::lazy_static;
+
+#[lazy_static]
+static NUMBER: u32 = times_two(3);
+
+/// Documentation!
+#[lazy_static]
+pub static HASHMAP: HashMap<u32, &'static str> = {
+    let mut m = HashMap::new();
+    m.insert(0, "foo");
+    m.insert(1, "bar");
+    m
+};
+
+#[lazy_static]
+static mut COUNTER: u32 = 0;
+
+fn times_two(n: u32) -> u32 {
+    n * 2
+}
+
+mod inner {
+    use lazy_static_attr::lazy_static;
+
+    #[lazy_static]
+    pub(crate) static RESTRICTED: &'static str = "restricted";
+}
+
+#[test]
+fn attr() {
+    assert_eq!(*NUMBER, 6);
+    assert_eq!(HASHMAP.get(&1), Some(&"bar"));
+    assert_eq!(*inner::RESTRICTED, "restricted");
+    assert!(lazy_static::is_initialized(&NUMBER));
+}
+
+#[test]
+fn attr_mut() {
+    *COUNTER.lock() += 1;
+    assert_eq!(*COUNTER.lock(), 1);
+}
@@ -0,0 +1,99 @@
+// Copyright 2016 lazy-static.rs Developers
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+extern crate std;
+
+use self::std::prelude::v1::*;
+use self::std::sync::{Mutex, Once};
+use core::sync::atomic::{AtomicBool, Ordering};
+
+static DTORS: Mutex<Vec<Box<dyn FnOnce() + Send>>> = Mutex::new(Vec::new());
+static REGISTER: Once = Once::new();
+
+// Miri cannot call `atexit`, so the values are leaked when running under it
+#[cfg(all(any(unix, windows), not(miri)))]
+extern "C" {
+    fn atexit(cb: extern "C" fn()) -> i32;
+}
+
+#[cfg_attr(any(not(any(unix, windows)), miri), allow(dead_code))]
+extern "C" fn run_dtors() {
+    // a destructor may initialize and register another static, so the list
+    // is drained one entry at a time
+    loop {
+        let dtor = match DTORS.lock() {
+            Ok(mut dtors) => dtors.pop(),
+            Err(_) => return,
+        };
+        match dtor {
+            Some(dtor) => dtor(),
+            None => return,
+        }
+    }
+}
+
+/// Registers `dtor` to be called when the process exits, before the
+/// destructors registered earlier.
+pub fn __register(dtor: fn()) {
+    __register_with(dtor)
+}
+
+/// Like `__register`, but for a closure.
+pub fn __register_with<F: FnOnce() + Send + 'static>(dtor: F) {
+    REGISTER.call_once(|| {
+        #[cfg(all(any(unix, windows), not(miri)))]
+        unsafe {
+            atexit(run_dtors);
+        }
+    });
+    DTORS.lock().unwrap_or_else(|e| e.into_inner()).push(Box::new(dtor));
+}
+
+static SHUTDOWN: Mutex<Vec<&'static Shutdown>> = Mutex::new(Vec::new());
+
+/// How `lazy_static::shutdown` resets a static, and whether it is waiting
+/// to do so.
+pub struct Shutdown {
+    reset: fn(),
+    registered: AtomicBool,
+}
+
+impl Shutdown {
+    pub const fn new(reset: fn()) -> Self {
+        Shutdown {
+            reset,
+            registered: AtomicBool::new(false),
+        }
+    }
+}
+
+/// Registers `shutdown` to be reset by `lazy_static::shutdown`, before the
+/// statics registered earlier, unless it is registered already.
+///
+/// A static that is initialized again before the next shutdown keeps its
+/// place, so the list does not grow with every initialization.
+pub fn __register_shutdown(shutdown: &'static Shutdown) {
+    if shutdown.registered.load(Ordering::Acquire) || shutdown.registered.swap(true, Ordering::AcqRel) {
+        return;
+    }
+    SHUTDOWN.lock().unwrap_or_else(|e| e.into_inner()).push(shutdown);
+}
+
+pub(crate) fn shutdown() {
+    // like at exit, a destructor may initialize another static, which is
+    // then dropped before the ones initialized before it
+    loop {
+        let shutdown = SHUTDOWN.lock().unwrap_or_else(|e| e.into_inner()).pop();
+        match shutdown {
+            Some(shutdown) => {
+                shutdown.registered.store(false, Ordering::Release);
+                (shutdown.reset)()
+            }
+            None => return,
+        }
+    }
+}
@@ -0,0 +1,372 @@
+// Copyright 2016 lazy-static.rs Developers
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+/*!
+Synchronization backends guarding the initialization of lazy statics.
+
+By default, lazy statics are guarded by `std::sync::Once`, by
+`parking_lot::Once` if the `parking_lot` feature is enabled, or by
+`spin::Once` if the `spin_no_std` feature is enabled. Other primitives,
+like the ones of an RTOS or a custom operating system, can be used by
+implementing [`OnceBackend`](trait.OnceBackend.html) for them and naming
+the implementing type in a `#[lazy(backend = ...)]` attribute:
+
+```rust
+#[macro_use]
+extern crate lazy_static;
+
+use std::sync::Once;
+use lazy_static::backend::OnceBackend;
+
+struct MyOnce(Once);
+
+unsafe impl OnceBackend for MyOnce {
+    const INIT: Self = MyOnce(Once::new());
+
+    fn call_once<F: FnOnce()>(&self, f: F) {
+        self.0.call_once(f)
+    }
+
+    fn is_completed(&self) -> bool {
+        self.0.is_completed()
+    }
+}
+
+lazy_static! {
+    #[lazy(backend = MyOnce)]
+    static ref NUMBER: u32 = 42;
+}
+
+fn main() {
+#     lazy_static::runtime_ready();
+    assert_eq!(*NUMBER, 42);
+}
+```
+*/
+
+use core::marker::PhantomData;
+#[cfg(not(panic = "abort"))]
+use core::mem;
+use core::sync::atomic::{AtomicU8, Ordering};
+
+/// A synchronization primitive that runs a closure exactly once.
+///
+/// # Safety
+///
+/// Implementations have to guarantee that, out of all calls to `call_once`
+/// on the same value, only a single closure is ever run, and that no call
+/// returns before that closure has completed. The completion of the closure
+/// has to synchronize with every call to `call_once` returning afterwards and
+/// with every call to `is_completed` returning `true`.
+pub unsafe trait OnceBackend: Sync {
+    /// A value on which `call_once` has not been called yet.
+    #[allow(clippy::declare_interior_mutable_const)]
+    const INIT: Self;
+
+    /// Runs `f` if no closure has been run on this value yet, and waits for
+    /// the closure to complete if another thread is running it.
+    fn call_once<F: FnOnce()>(&self, f: F);
+
+    /// Returns whether a closure passed to `call_once` has completed.
+    ///
+    /// This must not block or allocate, since `lazy_static::get` relies on
+    /// it to be callable from signal handlers.
+    fn is_completed(&self) -> bool;
+}
+
+/// A way of running code with interrupts (and other cores) locked out.
+///
+/// This is used by [`CriticalSectionOnce`](struct.CriticalSectionOnce.html),
+/// and implemented by [`GlobalCriticalSection`](struct.GlobalCriticalSection.html)
+/// with the `critical-section` feature. Platforms with a primitive of their
+/// own can implement it as well.
+///
+/// # Safety
+///
+/// `with` must not run `f` while anything else is running another closure
+/// passed to `with`, including interrupt handlers and other cores.
+pub unsafe trait CriticalSection {
+    /// Runs `f` in a critical section.
+    fn with<R, F: FnOnce() -> R>(f: F) -> R;
+}
+
+const INCOMPLETE: u8 = 0;
+const RUNNING: u8 = 1;
+const COMPLETE: u8 = 2;
+// a closure that panics aborts the process instead of poisoning the guard
+// when panics abort, so the guards do not keep track of it then
+#[cfg(not(panic = "abort"))]
+const POISONED: u8 = 3;
+
+/// A once guard that runs the initializer in a critical section.
+///
+/// This makes statics safe to access from both the main code and interrupt
+/// handlers of microcontrollers, on which neither `std` nor spinning work,
+/// since an interrupt handler spinning on a static that the code it
+/// interrupted is initializing never returns. Only loads and stores of an
+/// `AtomicU8` are used, so it also works on targets without compare-and-swap,
+/// like `thumbv6m`.
+///
+/// ```rust
+/// #[macro_use]
+/// extern crate lazy_static;
+///
+/// # #[cfg(feature="critical-section")]
+/// use lazy_static::backend::{CriticalSectionOnce, GlobalCriticalSection};
+///
+/// # #[cfg(feature="critical-section")]
+/// lazy_static! {
+///     #[lazy(backend = CriticalSectionOnce<GlobalCriticalSection>)]
+///     static ref TABLE: [u8; 4] = [1, 2, 4, 8];
+/// }
+///
+/// # #[cfg(feature="critical-section")]
+/// fn main() {
+///     assert_eq!(TABLE[3], 8);
+/// }
+/// # #[cfg(not(feature="critical-section"))]
+/// # fn main() {}
+/// ```
+pub struct CriticalSectionOnce<C> {
+    state: AtomicU8,
+    _section: PhantomData<fn() -> C>,
+}
+
+impl<C: CriticalSection> CriticalSectionOnce<C> {
+    #[cold]
+    fn call_once_slow<F: FnOnce()>(&self, f: F) {
+        C::with(|| {
+            match self.state.load(Ordering::Relaxed) {
+                INCOMPLETE => {}
+                RUNNING => panic!("one-time initialization may not be performed recursively"),
+                #[cfg(not(panic = "abort"))]
+                POISONED => panic!("Once instance has previously been poisoned"),
+                _ => return,
+            }
+            self.state.store(RUNNING, Ordering::Relaxed);
+            #[cfg(not(panic = "abort"))]
+            let poison = Poison(&self.state);
+            f();
+            #[cfg(not(panic = "abort"))]
+            mem::forget(poison);
+            self.state.store(COMPLETE, Ordering::Release);
+        })
+    }
+}
+
+unsafe impl<C: CriticalSection> OnceBackend for CriticalSectionOnce<C> {
+    #[allow(clippy::declare_interior_mutable_const)]
+    const INIT: Self = CriticalSectionOnce {
+        state: AtomicU8::new(INCOMPLETE),
+        _section: PhantomData,
+    };
+
+    #[inline(always)]
+    fn call_once<F: FnOnce()>(&self, f: F) {
+        if self.state.load(Ordering::Acquire) != COMPLETE {
+            self.call_once_slow(f);
+        }
+    }
+
+    #[inline(always)]
+    fn is_completed(&self) -> bool {
+        self.state.load(Ordering::Acquire) == COMPLETE
+    }
+}
+
+/// The critical section provided by the `critical-section` crate, which is
+/// implemented for most microcontrollers by their support crates.
+///
+/// This is only available with the `critical-section` feature.
+#[cfg(feature="critical-section")]
+pub struct GlobalCriticalSection;
+
+#[cfg(feature="critical-section")]
+mod global_critical_section {
+    extern crate critical_section;
+
+    use super::{CriticalSection, GlobalCriticalSection};
+
+    unsafe impl CriticalSection for GlobalCriticalSection {
+        #[inline(always)]
+        fn with<R, F: FnOnce() -> R>(f: F) -> R {
+            critical_section::with(|_| f())
+        }
+    }
+}
+
+// Poisons a once guard if the closure panics.
+#[cfg(not(panic = "abort"))]
+struct Poison<'a>(&'a AtomicU8);
+
+#[cfg(not(panic = "abort"))]
+impl<'a> Drop for Poison<'a> {
+    fn drop(&mut self) {
+        self.0.store(POISONED, Ordering::Release);
+    }
+}
+
+#[cfg(not(feature="spin_no_std"))]
+pub use self::std_once::StdOnce;
+
+#[cfg(not(feature="spin_no_std"))]
+mod std_once {
+    extern crate std;
+
+    use self::std::sync::Once;
+    use super::OnceBackend;
+
+    /// The once guard of the standard library, chosen with
+    /// `#[lazy(backend = "std")]`.
+    pub type StdOnce = Once;
+
+    unsafe impl OnceBackend for Once {
+        #[allow(clippy::declare_interior_mutable_const)]
+        const INIT: Self = Once::new();
+
+        #[inline(always)]
+        fn call_once<F: FnOnce()>(&self, f: F) {
+            Once::call_once(self, f)
+        }
+
+        #[inline(always)]
+        fn is_completed(&self) -> bool {
+            Once::is_completed(self)
+        }
+    }
+}
+
+#[cfg(feature="parking_lot")]
+pub use self::parking_lot_once::ParkingLotOnce;
+
+#[cfg(feature="parking_lot")]
+mod parking_lot_once {
+    extern crate parking_lot;
+
+    use self::parking_lot::Once;
+    use super::OnceBackend;
+
+    /// The once guard of `parking_lot`, chosen with
+    /// `#[lazy(backend = "parking_lot")]`.
+    ///
+    /// This is only available with the `parking_lot` feature.
+    pub type ParkingLotOnce = Once;
+
+    unsafe impl OnceBackend for Once {
+        #[allow(clippy::declare_interior_mutable_const)]
+        const INIT: Self = Once::new();
+
+        #[inline(always)]
+        fn call_once<F: FnOnce()>(&self, f: F) {
+            Once::call_once(self, f)
+        }
+
+        #[inline(always)]
+        fn is_completed(&self) -> bool {
+            self.state().done()
+        }
+    }
+}
+
+#[cfg(feature="spin")]
+pub use self::spin_once::SpinOnce;
+
+#[cfg(feature="spin")]
+mod spin_once {
+    extern crate spin;
+
+    use self::spin::Once;
+    use super::OnceBackend;
+
+    /// The spinning once guard of `spin`, chosen with
+    /// `#[lazy(backend = "spin")]`.
+    ///
+    /// This is only available with the `spin` feature, which `spin_no_std`
+    /// enables.
+    pub type SpinOnce = Once;
+
+    unsafe impl OnceBackend for Once {
+        #[allow(clippy::declare_interior_mutable_const)]
+        const INIT: Self = Once::new();
+
+        #[inline(always)]
+        fn call_once<F: FnOnce()>(&self, f: F) {
+            Once::call_once(self, f);
+        }
+
+        #[inline(always)]
+        fn is_completed(&self) -> bool {
+            Once::is_completed(self)
+        }
+    }
+}
+
+// `wasm32` without the `atomics` target feature cannot spawn threads, so the
+// once guard does not need any synchronization there
+#[cfg(all(target_arch = "wasm32", not(target_feature = "atomics")))]
+#[doc(hidden)]
+pub mod single_thread {
+    use core::cell::Cell;
+    #[cfg(not(panic = "abort"))]
+    use core::mem;
+    use super::{OnceBackend, INCOMPLETE, RUNNING, COMPLETE};
+    #[cfg(not(panic = "abort"))]
+    use super::POISONED;
+
+    pub struct Once(Cell<u8>);
+
+    // there is no other thread to share it with
+    unsafe impl Sync for Once {}
+
+    // Poisons the guard if the closure panics.
+    #[cfg(not(panic = "abort"))]
+    struct Poison<'a>(&'a Cell<u8>);
+
+    #[cfg(not(panic = "abort"))]
+    impl<'a> Drop for Poison<'a> {
+        fn drop(&mut self) {
+            self.0.set(POISONED);
+        }
+    }
+
+    impl Once {
+        #[cold]
+        fn call_once_slow<F: FnOnce()>(&self, f: F) {
+            match self.0.get() {
+                INCOMPLETE => {}
+                RUNNING => panic!("one-time initialization may not be performed recursively"),
+                #[cfg(not(panic = "abort"))]
+                POISONED => panic!("Once instance has previously been poisoned"),
+                _ => return,
+            }
+            self.0.set(RUNNING);
+            #[cfg(not(panic = "abort"))]
+            let poison = Poison(&self.0);
+            f();
+            #[cfg(not(panic = "abort"))]
+            mem::forget(poison);
+            self.0.set(COMPLETE);
+        }
+    }
+
+    unsafe impl OnceBackend for Once {
+        #[allow(clippy::declare_interior_mutable_const)]
+        const INIT: Self = Once(Cell::new(INCOMPLETE));
+
+        #[inline(always)]
+        fn call_once<F: FnOnce()>(&self, f: F) {
+            if self.0.get() != COMPLETE {
+                self.call_once_slow(f);
+            }
+        }
+
+        #[inline(always)]
+        fn is_completed(&self) -> bool {
+            self.0.get() == COMPLETE
+        }
+    }
+}
@@ -0,0 +1,51 @@
+// Copyright 2016 lazy-static.rs Developers
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+extern crate std;
+
+use self::std::env::{self, VarError};
+use core::any::type_name;
+use core::fmt::Display;
+use core::str::FromStr;
+
+/// Parses the environment variable `name`, panicking if it is not set.
+pub fn required<T>(name: &'static str) -> T
+    where T: FromStr,
+          T::Err: Display
+{
+    match lookup(name) {
+        Some(value) => value,
+        None => panic!("environment variable `{}` is not set", name),
+    }
+}
+
+/// Parses the environment variable `name`, or returns `default()` if it is
+/// not set.
+pub fn or_default<T, D>(name: &'static str, default: D) -> T
+    where T: FromStr,
+          T::Err: Display,
+          D: FnOnce() -> T
+{
+    lookup(name).unwrap_or_else(default)
+}
+
+fn lookup<T>(name: &'static str) -> Option<T>
+    where T: FromStr,
+          T::Err: Display
+{
+    match env::var(name) {
+        Ok(value) => match value.parse() {
+            Ok(parsed) => Some(parsed),
+            Err(error) => panic!("environment variable `{}` has the value {:?}, which is not a valid `{}`: {}",
+                                 name, value, type_name::<T>(), error),
+        },
+        Err(VarError::NotPresent) => None,
+        Err(VarError::NotUnicode(value)) => {
+            panic!("environment variable `{}` has the value {:?}, which is not valid unicode", name, value)
+        }
+    }
+}
@@ -0,0 +1,169 @@
+// Copyright 2016 lazy-static.rs Developers
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! Epoch-based reclamation of replaced values.
+//!
+//! A thread pins the current epoch while it reads, which only writes to a
+//! slot owned by the thread, instead of touching a reference count that is
+//! shared with every other reader. A replaced value is retired with the
+//! epoch it was replaced in, and the epoch only advances once every pinned
+//! thread has seen it, so a value retired in epoch `e` can no longer be
+//! read by anyone once the epoch is `e + 2`.
+
+extern crate std;
+
+use self::std::boxed::Box;
+use self::std::sync::Mutex;
+use self::std::vec::Vec;
+use core::cell::Cell;
+use core::marker::PhantomData;
+use core::ptr;
+use core::sync::atomic::{fence, AtomicBool, AtomicPtr, AtomicUsize, Ordering};
+
+static EPOCH: AtomicUsize = AtomicUsize::new(0);
+static PARTICIPANTS: AtomicPtr<Participant> = AtomicPtr::new(ptr::null_mut());
+static GARBAGE: Mutex<Vec<Garbage>> = Mutex::new(Vec::new());
+
+// the participants are never freed, but are reused by new threads once the
+// thread that owned them has exited
+struct Participant {
+    // the pinned epoch shifted left by one, with the lowest bit set while
+    // the thread is pinned
+    state: AtomicUsize,
+    in_use: AtomicBool,
+    next: *mut Participant,
+}
+
+struct Garbage {
+    epoch: usize,
+    free: Box<dyn FnOnce() + Send>,
+}
+
+struct Local {
+    participant: &'static Participant,
+    pins: Cell<usize>,
+}
+
+impl Drop for Local {
+    fn drop(&mut self) {
+        self.participant.in_use.store(false, Ordering::Release);
+    }
+}
+
+self::std::thread_local! {
+    static LOCAL: Local = Local {
+        participant: participant(),
+        pins: Cell::new(0),
+    };
+}
+
+fn participant() -> &'static Participant {
+    let mut next = PARTICIPANTS.load(Ordering::Acquire);
+    while let Some(participant) = unsafe { next.as_ref() } {
+        if participant.in_use.compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed).is_ok() {
+            return participant;
+        }
+        next = participant.next;
+    }
+    let participant = Box::into_raw(Box::new(Participant {
+        state: AtomicUsize::new(0),
+        in_use: AtomicBool::new(true),
+        next: ptr::null_mut(),
+    }));
+    let mut head = PARTICIPANTS.load(Ordering::Relaxed);
+    loop {
+        unsafe { (*participant).next = head };
+        match PARTICIPANTS.compare_exchange_weak(head, participant, Ordering::AcqRel, Ordering::Relaxed) {
+            Ok(_) => return unsafe { &*participant },
+            Err(current) => head = current,
+        }
+    }
+}
+
+/// Keeps the values read while it is alive from being freed.
+pub struct Guard {
+    _not_send: PhantomData<*const ()>,
+}
+
+/// Pins the current thread, which can be done again while it is pinned.
+pub fn pin() -> Guard {
+    LOCAL.with(|local| {
+        let pins = local.pins.get();
+        local.pins.set(pins + 1);
+        if pins == 0 {
+            let epoch = EPOCH.load(Ordering::Relaxed);
+            local.participant.state.store(epoch << 1 | 1, Ordering::Relaxed);
+            // orders the pin before the loads of the values it protects
+            fence(Ordering::SeqCst);
+        }
+    });
+    Guard {
+        _not_send: PhantomData,
+    }
+}
+
+impl Drop for Guard {
+    fn drop(&mut self) {
+        LOCAL.with(|local| {
+            let pins = local.pins.get() - 1;
+            local.pins.set(pins);
+            if pins == 0 {
+                local.participant.state.store(0, Ordering::Release);
+            }
+        });
+    }
+}
+
+struct Retired<T>(*mut T);
+
+// the value is dropped on whichever thread collects it
+unsafe impl<T: Send> Send for Retired<T> {}
+
+/// Frees `value` once no pinned thread can read it anymore.
+///
+/// `value` has to come from `Box::into_raw`, and must have been replaced
+/// already, so that threads that pin after this cannot read it.
+pub unsafe fn retire<T: Send + 'static>(value: *mut T) {
+    let value = Retired(value);
+    let free = Box::new(move || {
+        let value = value;
+        drop(Box::from_raw(value.0))
+    });
+    // orders the replacement before the epoch it is retired in
+    fence(Ordering::SeqCst);
+    let mut garbage = GARBAGE.lock().unwrap_or_else(|e| e.into_inner());
+    garbage.push(Garbage {
+        epoch: EPOCH.load(Ordering::Relaxed),
+        free,
+    });
+    advance();
+    let epoch = EPOCH.load(Ordering::Relaxed);
+    let (collected, pending): (Vec<_>, Vec<_>) = garbage.drain(..).partition(|g| g.epoch + 2 <= epoch);
+    *garbage = pending;
+    // the values are dropped without the lock, as their destructors may
+    // retire other values
+    drop(garbage);
+    for garbage in collected {
+        (garbage.free)();
+    }
+}
+
+// advances the epoch if every pinned thread has seen the current one
+fn advance() {
+    let epoch = EPOCH.load(Ordering::Relaxed);
+    fence(Ordering::SeqCst);
+    let mut next = PARTICIPANTS.load(Ordering::Acquire);
+    while let Some(participant) = unsafe { next.as_ref() } {
+        let state = participant.state.load(Ordering::Relaxed);
+        if state & 1 == 1 && state >> 1 != epoch {
+            return;
+        }
+        next = participant.next;
+    }
+    fence(Ordering::Acquire);
+    let _ = EPOCH.compare_exchange(epoch, epoch + 1, Ordering::Release, Ordering::Relaxed);
+}
@@ -0,0 +1,92 @@
+// Copyright 2016 lazy-static.rs Developers
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+extern crate std;
+
+use self::std::sync::Once;
+use core::ptr;
+use core::sync::atomic::{AtomicBool, AtomicPtr, Ordering};
+use LazyStatic;
+
+/// The hook of a static with `#[lazy(on_fork_child = ...)]`, and whether it
+/// is registered already.
+pub struct Hook {
+    run: fn(),
+    next: AtomicPtr<Hook>,
+    registered: AtomicBool,
+}
+
+impl Hook {
+    pub const fn new(run: fn()) -> Self {
+        Hook {
+            run,
+            next: AtomicPtr::new(ptr::null_mut()),
+            registered: AtomicBool::new(false),
+        }
+    }
+}
+
+// the hooks are kept in a list that is never locked, as the child of a
+// `fork` only has the thread that called it, and a lock held by any other
+// thread would never be released
+static HOOKS: AtomicPtr<Hook> = AtomicPtr::new(ptr::null_mut());
+static REGISTER: Once = Once::new();
+
+#[cfg(all(unix, not(miri)))]
+extern "C" {
+    fn pthread_atfork(
+        prepare: Option<extern "C" fn()>,
+        parent: Option<extern "C" fn()>,
+        child: Option<extern "C" fn()>,
+    ) -> i32;
+}
+
+#[cfg_attr(any(not(unix), miri), allow(dead_code))]
+extern "C" fn run_hooks() {
+    // the hooks run in the order their statics were initialized in, so a
+    // hook can rely on the statics initialized before it
+    fn run(hook: *mut Hook) {
+        if let Some(hook) = unsafe { hook.as_ref() } {
+            run(hook.next.load(Ordering::Relaxed));
+            (hook.run)();
+        }
+    }
+    run(HOOKS.load(Ordering::Acquire));
+}
+
+/// Registers `hook` to be run in the child process after a `fork`, unless
+/// it is registered already.
+///
+/// A static that is initialized again, after it was reset, keeps its place,
+/// so its hook still runs once.
+pub fn __register(hook: &'static Hook) {
+    if hook.registered.load(Ordering::Acquire) || hook.registered.swap(true, Ordering::AcqRel) {
+        return;
+    }
+    REGISTER.call_once(|| {
+        #[cfg(all(unix, not(miri)))]
+        unsafe {
+            pthread_atfork(None, None, Some(run_hooks));
+        }
+    });
+    let node = hook as *const Hook as *mut Hook;
+    let mut head = HOOKS.load(Ordering::Relaxed);
+    loop {
+        hook.next.store(head, Ordering::Relaxed);
+        match HOOKS.compare_exchange_weak(head, node, Ordering::AcqRel, Ordering::Relaxed) {
+            Ok(_) => return,
+            Err(current) => head = current,
+        }
+    }
+}
+
+/// Calls `hook` with the value of `lazy`, if it is initialized.
+pub fn __run<L: LazyStatic, F: FnOnce(&L::Target)>(lazy: &L, hook: F) {
+    if let Some(value) = ::get(lazy) {
+        hook(value);
+    }
+}
@@ -0,0 +1,345 @@
+// Copyright 2016 lazy-static.rs Developers
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+/*!
+Lazily initialized values with asynchronous initializers.
+
+An [`AsyncLazy`](struct.AsyncLazy.html) is initialized by a future, which is
+run exactly once by whichever task first awaits the value. Tasks awaiting
+the value while the future is running are woken up once it completes.
+
+Awaiting the value is cancel-safe: the future is shared by every task that
+awaits it, and any of them continues it when it is woken, so dropping the
+task that started it does not leave the others waiting forever. If no task
+is awaiting the value anymore, the future is kept as it is, and continued by
+the next task that awaits the value. A task never blocks its thread while
+another one is polling the future, as it is woken up instead.
+
+Outside of async code, [`blocking_get`](struct.AsyncLazy.html#method.blocking_get)
+waits for the value by blocking the current thread.
+
+# Tokio
+
+With the `tokio` feature, `spawn()` starts the initialization on a task of
+the current tokio runtime, to have it done in the background, and
+`blocking_get()` runs the initializer inside the runtime entered on the
+current thread, such as from `spawn_blocking`, so that it can use the timers
+and I/O of tokio. Blocking a worker thread of the runtime is refused:
+`blocking_get()` panics when it is called from a task.
+
+The same does not hold for a plain `static ref`, whose initializer blocks the
+thread that runs it, and every thread that accesses the static in the
+meantime. A static that takes long to initialize and is used from tasks is
+better declared with `static ref async`, or initialized with
+`lazy_static::initialize` before the runtime is started.
+
+Statics of this kind are declared with `static ref async`, where the
+expression is a future instead of a value:
+
+```rust
+#[macro_use]
+extern crate lazy_static;
+
+lazy_static! {
+    static ref async ANSWER: u32 = std::future::ready(42);
+}
+
+fn main() {
+    // in an `async` context: `let answer = ANSWER.get().await;`
+    let answer = ANSWER.blocking_get();
+    assert_eq!(*answer, 42);
+}
+```
+
+The initialization only relies on the wakers of `core::task`, and not on
+any runtime, so it works the same under any executor, like tokio, async-std,
+smol, or the executors of embedded platforms. With the `spin_no_std`
+feature, it needs the `alloc` crate, to box the future and keep track of the
+waiting tasks, and `blocking_get()` spins instead of parking the thread.
+*/
+
+extern crate alloc;
+#[cfg(not(feature="spin_no_std"))]
+extern crate std;
+
+use self::alloc::boxed::Box;
+use self::alloc::sync::Arc;
+use self::alloc::task::Wake;
+use self::alloc::vec::Vec;
+use core::future::Future;
+use core::pin::Pin;
+use core::sync::atomic::{AtomicBool, Ordering};
+use core::task::{Context, Poll, Waker};
+#[cfg(not(feature="spin_no_std"))]
+use self::std::thread::{self, Thread};
+use lazy::{self, Mutex};
+use sync::OnceCell;
+
+/// A boxed future, as created by the initializers of `static ref async`.
+pub type BoxFuture<T> = Pin<Box<dyn Future<Output = T> + Send + 'static>>;
+
+#[doc(hidden)]
+pub fn __box_future<F>(future: F) -> BoxFuture<F::Output>
+    where F: Future + Send + 'static
+{
+    Box::pin(future)
+}
+
+enum State<T, F> {
+    Init(F),
+    Running(BoxFuture<T>),
+    Done,
+}
+
+// The tasks awaiting a running future, which is polled with a waker that
+// wakes all of them, so that whichever runs first continues it even if the
+// task that polled it last has been dropped since.
+//
+// A task that registers while the future is being polled may come after
+// the future has woken the waiters, so it sets `contended`, and the task
+// polling the future wakes the waiters again if they were woken during its
+// poll, instead of leaving them to a task that may never be polled again.
+struct Waiters {
+    wakers: Mutex<Vec<Waker>>,
+    woken: AtomicBool,
+    contended: AtomicBool,
+}
+
+impl Waiters {
+    fn new() -> Self {
+        Waiters {
+            wakers: Mutex::new(Vec::new()),
+            woken: AtomicBool::new(false),
+            contended: AtomicBool::new(false),
+        }
+    }
+
+    fn register(&self, waker: &Waker) {
+        let mut wakers = lazy::lock(&self.wakers);
+        if !wakers.iter().any(|w| w.will_wake(waker)) {
+            wakers.push(waker.clone());
+        }
+    }
+}
+
+impl Wake for Waiters {
+    fn wake(self: Arc<Self>) {
+        self.wake_by_ref();
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        self.woken.store(true, Ordering::SeqCst);
+        let wakers = core::mem::take(&mut *lazy::lock(&self.wakers));
+        for waker in wakers {
+            waker.wake();
+        }
+    }
+}
+
+/// A value that is initialized by a future on its first access.
+pub struct AsyncLazy<T, F = fn() -> BoxFuture<T>> {
+    value: OnceCell<T>,
+    state: Mutex<State<T, F>>,
+    waiters: OnceCell<Arc<Waiters>>,
+}
+
+impl<T, F> AsyncLazy<T, F> {
+    /// Creates a new lazy value that is initialized by awaiting the future
+    /// returned by `init`.
+    #[cfg(not(loom))]
+    pub const fn new(init: F) -> Self {
+        AsyncLazy {
+            value: OnceCell::new(),
+            state: Mutex::new(State::Init(init)),
+            waiters: OnceCell::new(),
+        }
+    }
+
+    /// Creates a new lazy value that is initialized by awaiting the future
+    /// returned by `init`.
+    #[cfg(loom)]
+    pub fn new(init: F) -> Self {
+        AsyncLazy {
+            value: OnceCell::new(),
+            state: Mutex::new(State::Init(init)),
+            waiters: OnceCell::new(),
+        }
+    }
+
+    /// Returns a reference to the value if it has already been initialized.
+    pub fn try_get(&self) -> Option<&T> {
+        self.value.get()
+    }
+}
+
+impl<T, F, Fut> AsyncLazy<T, F>
+    where F: FnOnce() -> Fut,
+          Fut: Future<Output = T> + Send + 'static
+{
+    /// Returns a future resolving to a reference to the value, which starts
+    /// the initialization if it has not been started yet.
+    pub fn get(&self) -> Get<'_, T, F> {
+        Get { lazy: self }
+    }
+
+    /// Blocks the current thread until the value has been initialized, and
+    /// returns a reference to it.
+    ///
+    /// If no task is running the initialization, the future is run on the
+    /// current thread. With the `spin_no_std` feature, the thread spins
+    /// while it waits. With the `tokio` feature, it runs inside the tokio
+    /// runtime entered on the current thread, if any, so that it can use
+    /// the timers and I/O of the runtime, and this panics if it is called
+    /// from a task of the runtime, as it would block one of its workers.
+    pub fn blocking_get(&self) -> &T {
+        #[cfg(feature="tokio")]
+        {
+            if let Ok(handle) = ::__tokio::runtime::Handle::try_current() {
+                return handle.block_on(self.get());
+            }
+        }
+        block_on(self.get())
+    }
+}
+
+#[cfg(feature="tokio")]
+impl<T, F, Fut> AsyncLazy<T, F>
+    where T: Send + Sync + 'static,
+          F: FnOnce() -> Fut + Send + 'static,
+          Fut: Future<Output = T> + Send + 'static
+{
+    /// Starts the initialization on a task of the current tokio runtime,
+    /// without waiting for it, and returns the handle of the task.
+    ///
+    /// Tasks awaiting the value in the meantime are woken up once the
+    /// spawned task has initialized it. Like `tokio::spawn`, this panics if
+    /// it is not called from within a tokio runtime.
+    pub fn spawn(&'static self) -> ::__tokio::task::JoinHandle<&'static T> {
+        ::__tokio::spawn(self.get())
+    }
+}
+
+#[cfg(not(feature="spin_no_std"))]
+struct ThreadWaker(Thread);
+
+#[cfg(not(feature="spin_no_std"))]
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+#[cfg(not(feature="spin_no_std"))]
+fn block_on<Fut: Future>(future: Fut) -> Fut::Output {
+    let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+    let mut cx = Context::from_waker(&waker);
+    let mut future = Box::pin(future);
+    loop {
+        match future.as_mut().poll(&mut cx) {
+            Poll::Ready(output) => return output,
+            Poll::Pending => thread::park(),
+        }
+    }
+}
+
+// without threads to park, the future is polled again until it is ready
+#[cfg(feature="spin_no_std")]
+struct SpinWaker;
+
+#[cfg(feature="spin_no_std")]
+impl Wake for SpinWaker {
+    fn wake(self: Arc<Self>) {}
+}
+
+#[cfg(feature="spin_no_std")]
+fn block_on<Fut: Future>(future: Fut) -> Fut::Output {
+    let waker = Waker::from(Arc::new(SpinWaker));
+    let mut cx = Context::from_waker(&waker);
+    let mut future = Box::pin(future);
+    loop {
+        match future.as_mut().poll(&mut cx) {
+            Poll::Ready(output) => return output,
+            Poll::Pending => core::hint::spin_loop(),
+        }
+    }
+}
+
+/// The future returned by [`AsyncLazy::get`](struct.AsyncLazy.html#method.get).
+pub struct Get<'a, T: 'a, F: 'a = fn() -> BoxFuture<T>> {
+    lazy: &'a AsyncLazy<T, F>,
+}
+
+impl<'a, T, F, Fut> Future for Get<'a, T, F>
+    where F: FnOnce() -> Fut,
+          Fut: Future<Output = T> + Send + 'static
+{
+    type Output = &'a T;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<&'a T> {
+        let lazy = self.lazy;
+        if let Some(value) = lazy.value.get() {
+            return Poll::Ready(value);
+        }
+
+        let waiters = lazy.waiters.get_or_init(|| Arc::new(Waiters::new()));
+        // registered before the state is looked at, so that a wake-up in
+        // the meantime is not missed
+        waiters.register(cx.waker());
+        let mut state = match lazy::try_lock(&lazy.state) {
+            Some(state) => state,
+            // Another task is polling the future, and wakes this one once it
+            // makes progress, so the thread is not blocked waiting for it.
+            // It is told that this task registered, in case the future woke
+            // the waiters before that, and the lock is tried again in case it
+            // was released before being told.
+            None => {
+                waiters.contended.store(true, Ordering::SeqCst);
+                if let Some(state) = lazy::try_lock(&lazy.state) {
+                    state
+                } else {
+                    return match lazy.value.get() {
+                        Some(value) => Poll::Ready(value),
+                        None => Poll::Pending,
+                    };
+                }
+            }
+        };
+        if let State::Init(_) = *state {
+            if let State::Init(init) = core::mem::replace(&mut *state, State::Done) {
+                *state = State::Running(Box::pin(init()));
+            }
+        }
+        let poll = match *state {
+            State::Running(ref mut future) => {
+                waiters.woken.store(false, Ordering::SeqCst);
+                let waker = Waker::from(waiters.clone());
+                future.as_mut().poll(&mut Context::from_waker(&waker))
+            }
+            // The value was set by a task that held the lock before us.
+            _ => return Poll::Ready(lazy.value.get().unwrap()),
+        };
+
+        match poll {
+            Poll::Ready(value) => {
+                *state = State::Done;
+                let _ = lazy.value.set(value);
+                waiters.wake_by_ref();
+                Poll::Ready(lazy.value.get().unwrap())
+            }
+            Poll::Pending => {
+                drop(state);
+                // a task that failed to lock the state in the meantime may
+                // have registered after the waiters were woken
+                if waiters.contended.swap(false, Ordering::SeqCst)
+                    && waiters.woken.load(Ordering::SeqCst) {
+                    waiters.wake_by_ref();
+                }
+                Poll::Pending
+            }
+        }
+    }
+}
@@ -0,0 +1,143 @@
+// Copyright 2016 lazy-static.rs Developers
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! The values of statics marked with `#[lazy(process_global = "...")]`.
+//!
+//! Every copy of this crate that is linked into a dynamic library carries
+//! its own table of values, so they are found through an exported function
+//! instead, of which the dynamic linker picks the first one that was loaded
+//! with global visibility. Only plain C types cross that boundary, as the
+//! copies may have been built by different compilers.
+
+extern crate std;
+
+use self::std::boxed::Box;
+use self::std::sync::Mutex;
+use self::std::vec::Vec;
+use core::ffi::c_void;
+use core::mem;
+use lazy::{self, Lazy};
+
+type Init = unsafe extern "C-unwind" fn(*mut c_void) -> *const c_void;
+type Lookup = unsafe extern "C-unwind" fn(*const u8, usize, Init, *mut c_void) -> *const c_void;
+
+struct Value(*const c_void);
+
+// the values are only created from `Sync` types
+unsafe impl Send for Value {}
+unsafe impl Sync for Value {}
+
+struct Entry {
+    key: &'static [u8],
+    value: &'static Lazy<Value>,
+    // set once the initializer of `value` panicked, which keeps the value in
+    // the table without handing it out again
+    poisoned: bool,
+}
+
+static VALUES: Mutex<Vec<Entry>> = Mutex::new(Vec::new());
+
+/// Returns the value stored under `key`, calling `init(ctx)` to create it
+/// if there is none yet.
+#[no_mangle]
+pub unsafe extern "C-unwind" fn lazy_static_process_global_v1(
+    key: *const u8,
+    len: usize,
+    init: Init,
+    ctx: *mut c_void,
+) -> *const c_void {
+    let key = core::slice::from_raw_parts(key, len);
+    let value = {
+        let mut values = lazy::lock(&VALUES);
+        match values.iter().find(|e| e.key == key && !e.poisoned) {
+            Some(entry) => entry.value,
+            None => {
+                let value: &'static Lazy<Value> = Box::leak(Box::new(Lazy::new()));
+                values.push(Entry { key: Box::leak(key.into()), value, poisoned: false });
+                value
+            }
+        }
+    };
+    // the table is not locked while the value is created, so that it can
+    // use other process-global statics
+    let poison = Poison(value);
+    let value = value.get(|| Value(init(ctx))).0;
+    mem::forget(poison);
+    value
+}
+
+// Marks a value whose initializer panicked as poisoned, so that the next
+// lookup of its key creates a new one.
+struct Poison(&'static Lazy<Value>);
+
+impl Drop for Poison {
+    fn drop(&mut self) {
+        let mut values = VALUES.lock().unwrap_or_else(|e| e.into_inner());
+        for entry in values.iter_mut().filter(|e| core::ptr::eq(e.value, self.0)) {
+            entry.poisoned = true;
+        }
+    }
+}
+
+#[cfg(all(unix, not(miri)))]
+extern "C" {
+    fn dlsym(handle: *mut c_void, symbol: *const u8) -> *mut c_void;
+}
+
+#[cfg(all(unix, not(miri), any(target_os = "macos", target_os = "ios")))]
+const RTLD_DEFAULT: *mut c_void = -2isize as *mut c_void;
+
+#[cfg(all(unix, not(miri), not(any(target_os = "macos", target_os = "ios"))))]
+const RTLD_DEFAULT: *mut c_void = core::ptr::null_mut();
+
+#[cfg(all(unix, not(miri)))]
+fn find_lookup() -> Lookup {
+    let found = unsafe { dlsym(RTLD_DEFAULT, b"lazy_static_process_global_v1\0".as_ptr()) };
+    if found.is_null() {
+        lazy_static_process_global_v1
+    } else {
+        unsafe { mem::transmute::<*mut c_void, Lookup>(found) }
+    }
+}
+
+// Miri cannot call `dlsym`, and other platforms have no global symbol
+// namespace, so the values are only shared within each copy there
+#[cfg(not(all(unix, not(miri))))]
+fn find_lookup() -> Lookup {
+    lazy_static_process_global_v1
+}
+
+struct Context<F> {
+    init: Option<F>,
+}
+
+unsafe extern "C-unwind" fn init<T, F: FnOnce() -> T>(ctx: *mut c_void) -> *const c_void {
+    let ctx = &mut *(ctx as *mut Context<F>);
+    let init = ctx.init.take().unwrap();
+    Box::into_raw(Box::new(init())) as *const c_void
+}
+
+/// Returns the value of the process-global static `key`, initializing it
+/// with `f` if no copy of this crate in the process has done so yet.
+///
+/// The value is never dropped, and every copy has to use the same type for
+/// the same key.
+pub fn get_or_init<T, F>(key: &'static str, f: F) -> &'static T
+    where T: Sync + 'static,
+          F: FnOnce() -> T
+{
+    static LOOKUP: Lazy<Lookup> = Lazy::new();
+
+    let lookup = *LOOKUP.get(find_lookup);
+    let mut ctx = Context { init: Some(f) };
+    let ctx_ptr = &mut ctx as *mut Context<F> as *mut c_void;
+    unsafe {
+        let value = lookup(key.as_ptr(), key.len(), init::<T, F>, ctx_ptr);
+        debug_assert!(!value.is_null());
+        &*(value as *const T)
+    }
+}
@@ -0,0 +1,462 @@
+// Copyright 2016 lazy-static.rs Developers
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+#[cfg(not(feature="spin_no_std"))]
+extern crate std;
+
+use core::panic::UnwindSafe;
+use core::sync::atomic::{AtomicBool, Ordering};
+
+/// Runs the initializer `f` of the static called `name`.
+///
+/// Every static goes through this, so that the instrumentation enabled by
+/// cargo features is applied in a single place. Only the call of `f` is
+/// expanded for each static, while the instrumentation is shared by all of
+/// them.
+#[inline(always)]
+pub fn run<T, F>(name: &'static str, f: F) -> T
+    where F: FnOnce() -> T
+{
+    let run = Run::start(name);
+    let value = f();
+    run.finish();
+    value
+}
+
+// The instrumentation of a running initializer.
+struct Run {
+    name: &'static str,
+    #[cfg(feature="tracing")]
+    _span: ::tracing::span::EnteredSpan,
+    #[cfg(feature="metrics")]
+    start: ::metrics::Instant,
+    #[cfg(all(feature="registry", not(feature="spin_no_std")))]
+    started: ::registry::Started,
+    #[cfg(all(feature="log", not(feature="spin_no_std")))]
+    slow: slow::Start,
+}
+
+impl Run {
+    #[inline(never)]
+    fn start(name: &'static str) -> Self {
+        #[cfg(not(feature="spin_no_std"))]
+        cycle::run();
+        Run {
+            name,
+            #[cfg(feature="tracing")]
+            _span: ::tracing::info_span!("lazy_static::initialize", name).entered(),
+            #[cfg(feature="metrics")]
+            start: ::metrics::Instant::now(),
+            #[cfg(all(feature="registry", not(feature="spin_no_std")))]
+            started: ::registry::Started::now(name),
+            #[cfg(all(feature="log", not(feature="spin_no_std")))]
+            slow: slow::Start::now(),
+        }
+    }
+
+    #[inline(never)]
+    fn finish(self) {
+        #[cfg(all(feature="log", not(feature="spin_no_std")))]
+        self.slow.check(self.name);
+        #[cfg(feature="metrics")]
+        ::metrics::record(self.name, self.start.elapsed());
+        #[cfg(all(feature="registry", not(feature="spin_no_std")))]
+        self.started.record(self.name);
+        let _ = self.name;
+    }
+}
+
+/// Calls the initializer of a static declared with `= fn path`.
+///
+/// This is never inlined, so that `f` is only known at runtime, and is only
+/// generated once for every type of value.
+#[inline(never)]
+pub fn call<T>(f: fn() -> T) -> T {
+    f()
+}
+
+/// Marks the static called `name`, stored in `lazy`, as being waited for by
+/// the current thread until the returned guard is dropped, and as being
+/// initialized by it once `run` is called.
+///
+/// Panics if the current thread is already initializing it, or if another
+/// thread initializing it is waiting for a static that the current thread
+/// is initializing, as waiting for the initialization to complete would
+/// never return.
+///
+/// With the `strict` feature, it also panics if `ready` has not been called.
+#[inline(always)]
+pub fn enter<L>(name: &'static str, lazy: &L) -> Guard {
+    enter_at(name, lazy as *const L as *const ())
+}
+
+/// Like `enter`, with `lazy` being the address of the storage of the static.
+#[inline(never)]
+pub fn enter_at(name: &'static str, lazy: *const ()) -> Guard {
+    #[cfg(feature="strict")]
+    {
+        if !READY.load(Ordering::Acquire) {
+            panic!("lazy static `{}` was accessed before `lazy_static::runtime_ready()` was called", name);
+        }
+    }
+    #[cfg(not(feature="spin_no_std"))]
+    cycle::enter(name, lazy);
+    let _ = (name, lazy);
+    Guard(())
+}
+
+/// Returns whether a thread is running the initializer of the static
+/// called `name`.
+#[cfg(not(feature="spin_no_std"))]
+pub fn is_running(name: &str) -> bool {
+    cycle::is_running(name)
+}
+
+/// Keeps the message of the panic that poisoned the static stored at
+/// `lazy`, so that later accesses can report it instead of the generic
+/// message of its once guard.
+#[cfg(not(feature="spin_no_std"))]
+pub mod poison {
+    extern crate std;
+
+    use core::any::Any;
+    use core::sync::atomic::{AtomicBool, Ordering};
+    use self::std::string::String;
+    use self::std::sync::Mutex;
+    use self::std::vec::Vec;
+
+    // the address of every poisoned static, with its name and the message of
+    // the panic, if the payload was a string
+    static POISONED: Mutex<Vec<(usize, &'static str, Option<String>)>> = Mutex::new(Vec::new());
+    // set once any static has been poisoned, so that the others never lock
+    static ANY: AtomicBool = AtomicBool::new(false);
+
+    pub fn record(name: &'static str, lazy: *const (), payload: &(dyn Any + Send)) {
+        let message = match payload.downcast_ref::<&'static str>() {
+            Some(message) => Some(String::from(*message)),
+            None => payload.downcast_ref::<String>().cloned(),
+        };
+        let mut poisoned = POISONED.lock().unwrap_or_else(|e| e.into_inner());
+        poisoned.retain(|&(l, _, _)| l != lazy as usize);
+        poisoned.push((lazy as usize, name, message));
+        ANY.store(true, Ordering::Release);
+    }
+
+    /// Panics with the message of the panic that poisoned the static at
+    /// `lazy`, if there was one.
+    pub fn check(lazy: *const ()) {
+        if !ANY.load(Ordering::Acquire) {
+            return;
+        }
+        let poisoned = POISONED.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(&(_, name, ref message)) = poisoned.iter().find(|&&(l, _, _)| l == lazy as usize) {
+            let message = message.clone();
+            drop(poisoned);
+            match message {
+                Some(message) => panic!("lazy static `{}` is poisoned, as its initializer panicked: {}", name, message),
+                None => panic!("lazy static `{}` is poisoned, as its initializer panicked", name),
+            }
+        }
+    }
+
+    /// Forgets the panic of the static at `lazy`, once its guard is re-armed.
+    pub fn clear(lazy: *const ()) {
+        if ANY.load(Ordering::Acquire) {
+            POISONED.lock().unwrap_or_else(|e| e.into_inner()).retain(|&(l, _, _)| l != lazy as usize);
+        }
+    }
+}
+
+#[cfg(feature="strict")]
+static READY: AtomicBool = AtomicBool::new(false);
+
+/// Allows statics to be initialized from now on, with the `strict` feature.
+pub fn ready() {
+    #[cfg(feature="strict")]
+    READY.store(true, Ordering::Release);
+}
+
+/// Runs `f`, which initializes the static called `name`, on a thread named
+/// after the static.
+///
+/// If the thread cannot be spawned, the static is initialized on its first
+/// access instead.
+#[cfg(not(feature="spin_no_std"))]
+pub fn background(name: &'static str, f: fn()) {
+    let _ = self::std::thread::Builder::new().name(name.into()).spawn(f);
+}
+
+#[cfg(not(feature="spin_no_std"))]
+pub use self::deadline::Deadline;
+
+#[cfg(not(feature="spin_no_std"))]
+mod deadline {
+    extern crate std;
+
+    use self::std::sync::{Condvar, Mutex};
+    use self::std::thread;
+    use self::std::time::{Duration, Instant};
+
+    /// Tracks the background initialization of a static declared with
+    /// `#[lazy(deadline = ..., fallback = ...)]`.
+    pub struct Deadline {
+        // when the initializer was started, and whether it has returned
+        started: Mutex<Option<(Instant, bool)>>,
+        done: Condvar,
+    }
+
+    impl Deadline {
+        #[allow(clippy::new_without_default)]
+        pub const fn new() -> Self {
+            Deadline {
+                started: Mutex::new(None),
+                done: Condvar::new(),
+            }
+        }
+
+        /// Starts `init` on a thread named after the static the first time
+        /// this is called, and waits for it to return until `deadline` has
+        /// passed since then.
+        ///
+        /// If the thread cannot be spawned, `init` runs on the current
+        /// thread instead.
+        pub fn wait(&'static self, name: &'static str, deadline: Duration, init: fn()) {
+            let mut started = self.started.lock().unwrap_or_else(|e| e.into_inner());
+            let start = match *started {
+                Some((start, _)) => start,
+                None => {
+                    let start = Instant::now();
+                    *started = Some((start, false));
+                    let spawned = thread::Builder::new().name(name.into()).spawn(move || {
+                        let _done = Done(self);
+                        init();
+                    });
+                    if spawned.is_err() {
+                        drop(started);
+                        let _done = Done(self);
+                        init();
+                        return;
+                    }
+                    start
+                }
+            };
+            loop {
+                if let Some((_, true)) = *started {
+                    return;
+                }
+                let left = match deadline.checked_sub(start.elapsed()) {
+                    Some(left) if left > Duration::ZERO => left,
+                    _ => return,
+                };
+                started = self.done.wait_timeout(started, left).unwrap_or_else(|e| e.into_inner()).0;
+            }
+        }
+
+        /// Returns whether the initializer has returned or panicked, or
+        /// `deadline` has passed since it was started.
+        pub fn expired(&self, deadline: Duration) -> bool {
+            match *self.started.lock().unwrap_or_else(|e| e.into_inner()) {
+                Some((start, done)) => done || start.elapsed() >= deadline,
+                None => false,
+            }
+        }
+    }
+
+    // marks the initializer as returned when it is dropped, even by a panic
+    struct Done(&'static Deadline);
+
+    impl Drop for Done {
+        fn drop(&mut self) {
+            if let Some((_, ref mut done)) = *self.0.started.lock().unwrap_or_else(|e| e.into_inner()) {
+                *done = true;
+            }
+            self.0.done.notify_all();
+        }
+    }
+}
+
+/// Records whether a static declared with `#[lazy(fallback = ...)]` holds
+/// its fallback value.
+pub struct Fallback(AtomicBool);
+
+impl Fallback {
+    #[allow(clippy::new_without_default)]
+    pub const fn new() -> Self {
+        Fallback(AtomicBool::new(false))
+    }
+
+    /// Runs `init`, or `fallback` if `init` returns an error or panics.
+    ///
+    /// Panics are only caught with the standard library, so with the
+    /// `spin_no_std` feature they propagate like they would without a
+    /// fallback.
+    pub fn run<T, E, I, F>(&self, init: I, fallback: F) -> T
+        where I: FnOnce() -> Result<T, E> + UnwindSafe,
+              F: FnOnce() -> T
+    {
+        #[cfg(not(feature="spin_no_std"))]
+        let value = match self::std::panic::catch_unwind(init) {
+            Ok(Ok(value)) => Some(value),
+            _ => None,
+        };
+        #[cfg(feature="spin_no_std")]
+        let value = init().ok();
+        match value {
+            Some(value) => value,
+            None => {
+                // the value is published through the once guard of the
+                // static, which orders this store before any load in `used`
+                self.0.store(true, Ordering::Relaxed);
+                fallback()
+            }
+        }
+    }
+
+    pub fn used(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// Sets how long an initializer may take before a warning is logged, with
+/// the `log` feature.
+#[cfg(all(feature="log", not(feature="spin_no_std")))]
+pub fn set_slow_threshold(threshold: self::std::time::Duration) {
+    slow::set_threshold(threshold);
+}
+
+#[cfg(all(feature="log", not(feature="spin_no_std")))]
+mod slow {
+    extern crate std;
+
+    use core::sync::atomic::{AtomicUsize, Ordering};
+    use self::std::time::{Duration, Instant};
+
+    // in microseconds, so that it fits in a `usize` on 32-bit targets
+    static THRESHOLD: AtomicUsize = AtomicUsize::new(100_000);
+
+    pub fn set_threshold(threshold: Duration) {
+        let micros = threshold.as_micros();
+        THRESHOLD.store(if micros > usize::MAX as u128 { usize::MAX } else { micros as usize }, Ordering::Relaxed);
+    }
+
+    pub struct Start(Instant);
+
+    impl Start {
+        pub fn now() -> Self {
+            Start(Instant::now())
+        }
+
+        pub fn check(self, name: &'static str) {
+            let elapsed = self.0.elapsed();
+            let threshold = Duration::from_micros(THRESHOLD.load(Ordering::Relaxed) as u64);
+            if elapsed > threshold {
+                ::log::warn!(target: "lazy_static",
+                             "initializer of lazy static `{}` took {:?}, longer than {:?}",
+                             name, elapsed, threshold);
+            }
+        }
+    }
+}
+
+pub struct Guard(());
+
+#[cfg(not(feature="spin_no_std"))]
+impl Drop for Guard {
+    fn drop(&mut self) {
+        cycle::exit();
+    }
+}
+
+#[cfg(not(feature="spin_no_std"))]
+mod cycle {
+    extern crate std;
+
+    use self::std::format;
+    use self::std::string::String;
+    use self::std::sync::{Mutex, MutexGuard};
+    use self::std::thread::{self, ThreadId};
+    use self::std::vec::Vec;
+
+    // A static that a thread is waiting for, or initializing if `running`.
+    struct Entry {
+        thread: ThreadId,
+        lazy: usize,
+        name: &'static str,
+        running: bool,
+    }
+
+    // the entries of every thread, in the order in which it entered them
+    static ENTRIES: Mutex<Vec<Entry>> = Mutex::new(Vec::new());
+
+    fn entries() -> MutexGuard<'static, Vec<Entry>> {
+        ENTRIES.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    pub fn enter(name: &'static str, lazy: *const ()) {
+        let lazy = lazy as usize;
+        let current = thread::current().id();
+        let mut entries = entries();
+        if let Some(message) = find_cycle(&entries, current, lazy, name) {
+            drop(entries);
+            panic!("{}", message);
+        }
+        entries.push(Entry { thread: current, lazy, name, running: false });
+    }
+
+    pub fn run() {
+        let current = thread::current().id();
+        if let Some(entry) = entries().iter_mut().rev().find(|e| e.thread == current) {
+            entry.running = true;
+        }
+    }
+
+    pub fn is_running(name: &str) -> bool {
+        entries().iter().any(|e| e.running && e.name == name)
+    }
+
+    pub fn exit() {
+        let current = thread::current().id();
+        let mut entries = entries();
+        if let Some(pos) = entries.iter().rposition(|e| e.thread == current) {
+            entries.remove(pos);
+        }
+    }
+
+    fn find_cycle(entries: &[Entry], current: ThreadId, lazy: usize, name: &'static str) -> Option<String> {
+        let own: Vec<&Entry> = entries.iter().filter(|e| e.thread == current).collect();
+        if let Some(pos) = own.iter().position(|e| e.lazy == lazy) {
+            let mut cycle: Vec<&str> = own[pos..].iter().map(|e| e.name).collect();
+            cycle.push(name);
+            return Some(format!("lazy static `{}` was dereferenced during its own initialization: {}",
+                                name, cycle.join(" -> ")));
+        }
+
+        // follow the thread initializing `lazy` to the static it waits for,
+        // and so on, to see if that ends up waiting for the current thread
+        let mut cycle: Vec<&str> = own.last().map(|e| e.name).into_iter().collect();
+        cycle.push(name);
+        let mut target = lazy;
+        let mut visited = Vec::new();
+        loop {
+            let owner = entries.iter().find(|e| e.lazy == target && e.running)?.thread;
+            if owner == current {
+                return Some(format!("lazy static `{}` is being initialized by another thread waiting for this one: {}",
+                                    name, cycle.join(" -> ")));
+            }
+            if visited.contains(&owner) {
+                return None;
+            }
+            visited.push(owner);
+            let waiting = entries.iter().rev().find(|e| e.thread == owner)?;
+            if waiting.running {
+                return None;
+            }
+            target = waiting.lazy;
+            cycle.push(waiting.name);
+        }
+    }
+}
@@ -7,34 +7,77 @@
 
 extern crate std;
 
-use self::std::prelude::v1::*;
-use self::std::sync::Once;
-
-pub struct Lazy<T: Sync>(pub *const T, pub Once);
-
-impl<T: Sync> Lazy<T> {
-    #[inline(always)]
-    pub fn get<F>(&'static mut self, f: F) -> &T
-        where F: FnOnce() -> T
-    {
-        unsafe {
-            let r = &mut self.0;
-            self.1.call_once(|| {
-                *r = Box::into_raw(Box::new(f()));
-            });
-
-            &*self.0
-        }
+use self::std::thread;
+use self::std::time::{Duration, Instant};
+
+pub use self::std::sync::{Mutex, MutexGuard};
+
+#[inline(always)]
+pub fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap()
+}
+
+/// Locks `mutex` unless it is locked already.
+#[inline(always)]
+pub fn try_lock<T>(mutex: &Mutex<T>) -> Option<MutexGuard<'_, T>> {
+    match mutex.try_lock() {
+        Ok(guard) => Some(guard),
+        Err(self::std::sync::TryLockError::WouldBlock) => None,
+        Err(self::std::sync::TryLockError::Poisoned(e)) => core::panic!("{}", e),
     }
 }
 
-unsafe impl<T: Sync> Sync for Lazy<T> {}
+pub use self::std::sync::{RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+#[inline(always)]
+pub fn read<T>(lock: &RwLock<T>) -> RwLockReadGuard<'_, T> {
+    lock.read().unwrap()
+}
+
+#[inline(always)]
+pub fn write<T>(lock: &RwLock<T>) -> RwLockWriteGuard<'_, T> {
+    lock.write().unwrap()
+}
+
+#[cfg(loom)]
+pub use loom_lazy::Lazy;
+
+#[cfg(not(loom))]
+pub use lazy_cell::Lazy;
+
+#[cfg(feature="test-support")]
+pub type OverrideLock = MutexGuard<'static, ()>;
+
+/// Locks the lock that serializes `lazy_static::override_scoped`.
+#[cfg(feature="test-support")]
+pub fn override_lock() -> OverrideLock {
+    static LOCK: Mutex<()> = Mutex::new(());
+    // a test that panicked while holding a guard still restored its value
+    LOCK.lock().unwrap_or_else(|error| error.into_inner())
+}
 
-#[macro_export]
-#[doc(hidden)]
-macro_rules! __lazy_static_create {
-    ($NAME:ident, $T:ty) => {
-        use std::sync::ONCE_INIT;
-        static mut $NAME: $crate::lazy::Lazy<$T> = $crate::lazy::Lazy(0 as *const $T, ONCE_INIT);
+/// Returns the value from `get` once it is available, or initializes it with
+/// `init` if no other thread is `running` its initialization.
+///
+/// Gives up after `timeout` if another thread is still running it.
+pub fn wait_timeout<'a, T: ?Sized, G, R, I>(timeout: Duration, get: G, running: R, init: I)
+    -> Result<&'a T, ::Timeout>
+    where G: Fn() -> Option<&'a T>, R: Fn() -> bool, I: FnOnce() -> &'a T
+{
+    let start = Instant::now();
+    loop {
+        if let Some(value) = get() {
+            return Ok(value);
+        }
+        if !running() {
+            return Ok(init());
+        }
+        let elapsed = start.elapsed();
+        if elapsed >= timeout {
+            return Err(::Timeout(()));
+        }
+        thread::sleep(::core::cmp::min(timeout - elapsed, Duration::from_micros(100)));
     }
 }
+
+impl self::std::error::Error for ::Timeout {}
@@ -0,0 +1,279 @@
+// Copyright 2016 lazy-static.rs Developers
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+// The storage of a lazy static, shared by the `std` and the `spin_no_std`
+// builds, which only differ in their default once guard and in whether a
+// panic of the initializer of a static is recorded for later accesses.
+
+#[cfg(not(feature="spin_no_std"))]
+extern crate std;
+#[cfg(all(feature="spin_no_std", not(all(target_arch = "wasm32", not(target_feature = "atomics")))))]
+extern crate spin;
+#[cfg(all(not(feature="spin_no_std"), feature = "parking_lot",
+          not(all(target_arch = "wasm32", not(target_feature = "atomics")))))]
+extern crate parking_lot;
+
+use core::cell::UnsafeCell;
+use core::mem::MaybeUninit;
+use core::sync::atomic::{AtomicBool, Ordering};
+#[cfg(not(feature="spin_no_std"))]
+use self::std::panic::{self, AssertUnwindSafe};
+#[cfg(all(not(feature="spin_no_std"), not(feature = "parking_lot"),
+          not(all(target_arch = "wasm32", not(target_feature = "atomics")))))]
+use self::std::sync::Once;
+#[cfg(all(not(feature="spin_no_std"), feature = "parking_lot",
+          not(all(target_arch = "wasm32", not(target_feature = "atomics")))))]
+use self::parking_lot::Once;
+#[cfg(all(feature="spin_no_std", not(all(target_arch = "wasm32", not(target_feature = "atomics")))))]
+use self::spin::Once;
+#[cfg(all(target_arch = "wasm32", not(target_feature = "atomics")))]
+use backend::single_thread::Once;
+use backend::OnceBackend;
+
+// The once guard is the only record of whether the value has been written.
+pub struct Lazy<T, B: OnceBackend = Once>(UnsafeCell<MaybeUninit<T>>, UnsafeCell<B>, AtomicBool);
+
+// Marks a `Lazy` as being initialized while it is alive.
+struct Running<'a>(&'a AtomicBool);
+
+impl<'a> Running<'a> {
+    fn new(running: &'a AtomicBool) -> Self {
+        running.store(true, Ordering::Release);
+        Running(running)
+    }
+}
+
+impl<'a> Drop for Running<'a> {
+    fn drop(&mut self) {
+        self.0.store(false, Ordering::Release);
+    }
+}
+
+// The initializer passed to `call_once`, which is erased to a pointer to it
+// and a pointer to `Init::write`, so that the once guard and the bookkeeping
+// around it are not generated again for every `Lazy` holding another type.
+struct Init<T, F> {
+    f: Option<F>,
+    slot: *mut MaybeUninit<T>,
+}
+
+impl<T, F: FnOnce() -> T> Init<T, F> {
+    unsafe fn write(init: *mut ()) {
+        let init = &mut *(init as *mut Self);
+        if let Some(f) = init.f.take() {
+            (*init.slot).write(f());
+        }
+    }
+}
+
+// Runs `write(init)` under `once`, marking `running` while it runs.
+//
+// This is shared by every `Lazy` with the same backend.
+#[inline(never)]
+unsafe fn call_once<B: OnceBackend>(once: &B, running: &AtomicBool, write: unsafe fn(*mut ()), init: *mut ()) {
+    once.call_once(|| {
+        let _running = Running::new(running);
+        write(init);
+    });
+}
+
+// Like `call_once`, for the lazy static called `name`, which is stored at
+// `lazy`.
+//
+// A panic of the initializer is recorded before it poisons the guard, so
+// that later accesses panic with its message.
+#[cfg(not(feature="spin_no_std"))]
+#[inline(never)]
+unsafe fn call_once_static<B: OnceBackend>(name: &'static str, lazy: *const (), once: &B, running: &AtomicBool,
+                                           write: unsafe fn(*mut ()), init: *mut ()) {
+    ::init::poison::check(lazy);
+    let _cycle = ::init::enter_at(name, lazy);
+    once.call_once(|| {
+        let _running = Running::new(running);
+        let result = panic::catch_unwind(AssertUnwindSafe(|| ::init::run(name, || write(init))));
+        if let Err(payload) = result {
+            ::init::poison::record(name, lazy, &*payload);
+            panic::resume_unwind(payload);
+        }
+    });
+}
+
+// Like `call_once`, for the lazy static called `name`, which is stored at
+// `lazy`.
+#[cfg(feature="spin_no_std")]
+#[inline(never)]
+unsafe fn call_once_static<B: OnceBackend>(name: &'static str, lazy: *const (), once: &B, running: &AtomicBool,
+                                           write: unsafe fn(*mut ()), init: *mut ()) {
+    let _cycle = ::init::enter_at(name, lazy);
+    once.call_once(|| {
+        let _running = Running::new(running);
+        ::init::run(name, || write(init));
+    });
+}
+
+impl<T, B: OnceBackend> Lazy<T, B> {
+    #[inline(always)]
+    #[allow(clippy::new_without_default)]
+    pub const fn new() -> Self {
+        Lazy(UnsafeCell::new(MaybeUninit::uninit()), UnsafeCell::new(B::INIT), AtomicBool::new(false))
+    }
+
+    #[inline(always)]
+    fn once(&self) -> &B {
+        unsafe { &*self.1.get() }
+    }
+
+    #[inline(always)]
+    pub fn get<F>(&self, f: F) -> &T
+        where F: FnOnce() -> T
+    {
+        // an initialized value is returned without entering the once guard
+        match self.try_get() {
+            Some(value) => value,
+            None => self.get_slow(f),
+        }
+    }
+
+    #[cold]
+    fn get_slow<F>(&self, f: F) -> &T
+        where F: FnOnce() -> T
+    {
+        let mut init = Init { f: Some(f), slot: self.0.get() };
+        unsafe {
+            call_once(self.once(), &self.2, Init::<T, F>::write, &mut init as *mut Init<T, F> as *mut ());
+            // `call_once` only returns once the value has been written
+            (*self.0.get()).assume_init_ref()
+        }
+    }
+
+    /// Like `get`, for the lazy static called `name`, which is initialized
+    /// by `f` through `init::enter` and `init::run`.
+    #[inline(always)]
+    pub fn get_static<F>(&self, name: &'static str, f: F) -> &T
+        where F: FnOnce() -> T
+    {
+        match self.try_get() {
+            Some(value) => value,
+            None => self.get_static_slow(name, f),
+        }
+    }
+
+    #[cold]
+    fn get_static_slow<F>(&self, name: &'static str, f: F) -> &T
+        where F: FnOnce() -> T
+    {
+        let mut init = Init { f: Some(f), slot: self.0.get() };
+        unsafe {
+            call_once_static(name, self as *const Self as *const (), self.once(), &self.2,
+                             Init::<T, F>::write, &mut init as *mut Init<T, F> as *mut ());
+            (*self.0.get()).assume_init_ref()
+        }
+    }
+
+    /// Like `get`, but without holding the once guard while `f` runs, so a
+    /// panic in `f` does not poison it.
+    #[inline(always)]
+    pub fn get_or_retry<F>(&self, f: F) -> &T
+        where F: FnOnce() -> T
+    {
+        if let Some(value) = self.try_get() {
+            return value;
+        }
+        let value = f();
+        self.get(|| value)
+    }
+
+    #[inline(always)]
+    pub fn try_get_or_init<F, E>(&self, f: F) -> Result<&T, E>
+        where F: FnOnce() -> Result<T, E>
+    {
+        if let Some(value) = self.try_get() {
+            return Ok(value);
+        }
+        let value = f()?;
+        Ok(self.get(|| value))
+    }
+
+    #[inline(always)]
+    pub fn try_get(&self) -> Option<&T> {
+        if self.once().is_completed() {
+            unsafe { Some((*self.0.get()).assume_init_ref()) }
+        } else {
+            None
+        }
+    }
+
+    #[inline(always)]
+    pub fn get_mut(&mut self) -> Option<&mut T> {
+        // the value is only stored once the initializer returned, and nothing
+        // can be initializing it concurrently
+        if self.once().is_completed() {
+            unsafe { Some(self.0.get_mut().assume_init_mut()) }
+        } else {
+            None
+        }
+    }
+
+    /// Takes the value out and re-arms the once guard.
+    pub fn take(&mut self) -> Option<T> {
+        // the exclusive reference rules out any other access
+        unsafe { self.take_unchecked() }
+    }
+
+    pub fn into_inner(mut self) -> Option<T> {
+        self.take()
+    }
+
+    #[inline(always)]
+    pub fn is_initialized(&self) -> bool {
+        self.once().is_completed()
+    }
+
+    /// Returns whether a thread is currently running the initializer passed
+    /// to `get`.
+    pub fn is_running(&self) -> bool {
+        self.2.load(Ordering::Acquire)
+    }
+
+    /// Drops the value and re-arms the once guard.
+    ///
+    /// # Safety
+    ///
+    /// The caller has to guarantee that there are no references to the value
+    /// and that nothing accesses this `Lazy` concurrently.
+    pub unsafe fn reset(&self) {
+        drop(self.take_unchecked());
+    }
+
+    /// Takes the value out and re-arms the once guard, like `take`, through
+    /// a shared reference.
+    ///
+    /// # Safety
+    ///
+    /// This has the same requirements as `reset`.
+    pub unsafe fn take_unchecked(&self) -> Option<T> {
+        let value = if self.once().is_completed() {
+            Some((*self.0.get()).assume_init_read())
+        } else {
+            None
+        };
+        *self.1.get() = B::INIT;
+        #[cfg(not(feature="spin_no_std"))]
+        ::init::poison::clear(self as *const Self as *const ());
+        value
+    }
+}
+
+impl<T, B: OnceBackend> Drop for Lazy<T, B> {
+    fn drop(&mut self) {
+        if self.once().is_completed() {
+            unsafe { self.0.get_mut().assume_init_drop() }
+        }
+    }
+}
+
+unsafe impl<T: Send + Sync, B: OnceBackend> Sync for Lazy<T, B> {}
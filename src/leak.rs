@@ -0,0 +1,86 @@
+// Copyright 2016 lazy-static.rs Developers
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+extern crate std;
+
+use self::std::boxed::Box;
+use at_exit;
+
+/// Moves `value` to the heap and leaks it, returning a reference that lives
+/// for the rest of the program.
+///
+/// This is for values that are only known at runtime, like configuration
+/// read during startup, but that are then shared like a static. With the
+/// `registry` feature, the value shows up in `lazy_static::registry()`.
+///
+/// Example:
+///
+/// ```rust
+/// extern crate lazy_static;
+///
+/// fn main() {
+///     let args: &'static Vec<String> = lazy_static::leak(std::env::args().collect());
+///     assert!(!args.is_empty());
+/// }
+/// ```
+pub fn leak<T: Sync + 'static>(value: T) -> &'static T {
+    register::<T>();
+    Box::leak(Box::new(value))
+}
+
+/// Like [`leak`](fn.leak.html), but drops the value when the process exits,
+/// in the same order as statics marked with `#[lazy(unsafe(drop_at_exit))]`.
+///
+/// # Safety
+///
+/// The returned reference must not be used after the value has been
+/// dropped, so it must not be used by the destructors of values that are
+/// dropped at exit after it, which are those leaked or initialized before
+/// it.
+///
+/// Example:
+///
+/// ```rust
+/// extern crate lazy_static;
+///
+/// use std::io::{BufWriter, Stdout, Write};
+/// use std::sync::Mutex;
+///
+/// fn main() {
+///     let output: &'static Mutex<BufWriter<Stdout>> = unsafe {
+///         lazy_static::leak_dropped_at_exit(Mutex::new(BufWriter::new(std::io::stdout())))
+///     };
+///     writeln!(output.lock().unwrap(), "flushed on exit").unwrap();
+/// }
+/// ```
+pub unsafe fn leak_dropped_at_exit<T: Send + Sync + 'static>(value: T) -> &'static T {
+    register::<T>();
+    let value = Leaked(Box::into_raw(Box::new(value)));
+    let leaked = &*value.0;
+    at_exit::__register_with(move || {
+        let value = value;
+        drop(Box::from_raw(value.0))
+    });
+    leaked
+}
+
+struct Leaked<T>(*mut T);
+
+// the value is only accessed through the pointer to drop it, which needs it
+// to be `Send`
+unsafe impl<T: Send> Send for Leaked<T> {}
+
+#[cfg(feature="registry")]
+fn register<T>() {
+    use core::any::type_name;
+    use registry::{self, StaticInfo};
+
+    registry::register(Box::leak(Box::new(StaticInfo::leaked(type_name::<T>()))));
+}
+
+#[cfg(not(feature="registry"))]
+fn register<T>() {}
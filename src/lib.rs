@@ -24,6 +24,29 @@ lazy_static! {
 }
 ```
 
+The type has to be written out, as for regular statics: it cannot be
+inferred from the expression with `_` or by leaving it out, as Rust requires
+the types of statics, and of the items generated for them, to be spelled out.
+
+```compile_fail
+# #[macro_use]
+# extern crate lazy_static;
+lazy_static! {
+    static ref INFERRED: _ = vec![1, 2, 3];
+}
+# fn main() {}
+```
+
+For types that cannot be named, like closures or iterator adapters, the value
+can be boxed into a trait object, like `Box<dyn Fn(u32) -> u32 + Send + Sync>`,
+or stored as an [unsized value](#unsized-values) of type `dyn Trait`.
+
+Restricted visibilities like `pub(crate)` or `pub(in some::path)` are
+accepted in place of `pub` as well.
+
+Lazy statics can be declared inside of function bodies as well, to keep a
+static that is only used by one function, like a lookup table, next to it.
+
 Attributes (including doc comments) are supported as well:
 
 ```rust
@@ -37,182 +60,2620 @@ lazy_static! {
 # }
 ```
 
+The companion `lazy_static_attr` crate provides the same declarations as an
+attribute on regular `static` items, like `#[lazy_static] static NAME: TYPE = EXPR;`.
+
 # Semantics
 
 For a given `static ref NAME: TYPE = EXPR;`, the macro generates a unique type that
 implements `Deref<TYPE>` and stores it in a static with name `NAME`. (Attributes end up
-attaching to this type.)
+attaching to this type, except for `#[cfg]` attributes, which apply to everything
+generated for the static.)
+
+The generated type also implements `Debug` and `Display` by forwarding to the
+value, if `TYPE` implements them, so that `println!("{:?}", NAME)` prints the
+value instead of requiring `*NAME`. Formatting the static initializes it.
+It implements `AsRef<TYPE>` and `Borrow<TYPE>` as well, so `&NAME` can be
+passed to functions taking `impl AsRef<TYPE>` without writing `&*NAME`.
 
 On first deref, `EXPR` gets evaluated and stored internally, such that all further derefs
 can return a reference to the same object. Note that this can lead to deadlocks
 if you have multiple lazy statics that depend on each other in their initialization.
+If the initializer of a static ends up dereferencing the same static on the same
+thread, directly or through other statics, this panics with a message naming the
+statics in the cycle instead, unless the `spin_no_std` feature is used. The same
+goes for statics whose initializers wait for each other on different threads,
+like `A` being initialized on one thread and dereferencing `B`, while `B` is
+being initialized on another thread and dereferences `A`.
 
 Apart from the lazy initialization, the resulting "static ref" variables
 have generally the same properties as regular "static" variables:
 
-- Any type in them needs to fulfill the `Sync` trait.
-- If the type has a destructor, then it will not run when the process exits.
+- Any type in them needs to fulfill the `Sync` trait, and the `Send` trait
+  as well, as the value may be dropped on another thread than the one that
+  created it, like by `lazy_static::shutdown()`.
+- If the type has a destructor, then it will not run when the process exits,
+  unless the static is marked with `#[lazy(unsafe(drop_at_exit))]`.
 
-# Example
+A type that is not `Send` and `Sync` is rejected with an error pointing at
+it, like "`Rc<u32>` cannot be used in lazy_static! because it is not Send and
+Sync":
 
-Using the macro:
+```compile_fail
+# #[macro_use]
+# extern crate lazy_static;
+use std::rc::Rc;
+
+lazy_static! {
+    static ref SHARED: Rc<u32> = Rc::new(1);
+}
+# fn main() {}
+```
+
+A `MutexGuard` is `Sync` but not `Send`, and is rejected as well, as it has to
+be dropped on the thread that locked the mutex:
+
+```compile_fail
+# #[macro_use]
+# extern crate lazy_static;
+use std::sync::{Mutex, MutexGuard};
+
+static LOCK: Mutex<()> = Mutex::new(());
+
+lazy_static! {
+    static ref GUARD: MutexGuard<'static, ()> = LOCK.lock().unwrap();
+}
+# fn main() {}
+```
+
+# Dependencies
+
+When the initializer of a static relies on other statics, for example because
+they configure a global resource it uses without dereferencing them, they can
+be declared with `#[lazy(depends_on = [OTHER, ...])]`. Initializing the static
+then initializes its dependencies first, in the order they are listed, which
+also makes them be dropped after it with `#[lazy(unsafe(drop_at_exit))]`.
 
 ```rust
-#[macro_use]
-extern crate lazy_static;
+# #[macro_use]
+# extern crate lazy_static;
+use std::sync::atomic::{AtomicBool, Ordering};
 
-use std::collections::HashMap;
+static LOGGING: AtomicBool = AtomicBool::new(false);
 
 lazy_static! {
-    static ref HASHMAP: HashMap<u32, &'static str> = {
-        let mut m = HashMap::new();
-        m.insert(0, "foo");
-        m.insert(1, "bar");
-        m.insert(2, "baz");
-        m
-    };
-    static ref COUNT: usize = HASHMAP.len();
-    static ref NUMBER: u32 = times_two(21);
+    static ref LOGGER: () = LOGGING.store(true, Ordering::SeqCst);
+
+    #[lazy(depends_on = [LOGGER])]
+    static ref SERVICE: bool = LOGGING.load(Ordering::SeqCst);
 }
 
-fn times_two(n: u32) -> u32 { n * 2 }
+fn main() {
+#     lazy_static::runtime_ready();
+    assert!(*SERVICE);
+    assert!(lazy_static::is_initialized(&LOGGER));
+}
+```
+
+# Destructors at exit
+
+Values that hold on to resources outside of the process, like temporary files
+or buffered log output, can be dropped when the process exits by marking the
+static with `#[lazy(unsafe(drop_at_exit))]`. Such statics are dropped in the
+reverse order of their initialization, from a handler registered with `atexit`, which
+runs when `main` returns or `std::process::exit` is called. Values that
+are only created at runtime can be leaked into a `&'static` reference that
+is dropped in the same order with `lazy_static::leak_dropped_at_exit`.
+
+```rust
+# #[macro_use]
+# extern crate lazy_static;
+use std::io::{BufWriter, Stdout, Write};
+use std::sync::Mutex;
 
+# #[cfg(not(feature="spin_no_std"))]
+lazy_static! {
+    #[lazy(unsafe(drop_at_exit))]
+    static ref OUTPUT: Mutex<BufWriter<Stdout>> = Mutex::new(BufWriter::new(std::io::stdout()));
+}
+
+# #[cfg(not(feature="spin_no_std"))]
 fn main() {
-    println!("The map has {} entries.", *COUNT);
-    println!("The entry for `0` is \"{}\".", HASHMAP.get(&0).unwrap());
-    println!("A expensive calculation on a static results in: {}.", *NUMBER);
+#     lazy_static::runtime_ready();
+    writeln!(OUTPUT.lock().unwrap(), "flushed on exit").unwrap();
 }
+# #[cfg(feature="spin_no_std")]
+# fn main() {}
 ```
 
-# Implementation details
+The destructors run while other threads may still be running, and a thread
+that uses the value after it was dropped reads freed memory. This is why the
+option is marked `unsafe`: a static marked like this must not be used by
+threads that outlive `main`, which the compiler cannot check, and it is
+rejected when it is written `#[lazy(drop_at_exit)]`:
+
+```compile_fail
+# #[macro_use]
+# extern crate lazy_static;
+lazy_static! {
+    #[lazy(drop_at_exit)]
+    static ref BUFFER: Vec<u8> = Vec::new();
+}
+# fn main() {}
+```
 
-The `Deref` implementation uses a hidden static variable that is guarded by a atomic check on each access. On stable Rust, the macro may need to allocate each static on the heap.
+This is not available with the `spin_no_std` feature, and the values are
+leaked instead when running under Miri, which does not support `atexit`.
 
-*/
+Programs that embed Rust and load and unload it repeatedly, or that want a
+clean report from a leak checker, can instead drop the values of all
+initialized statics at a point of their choosing with
+[`lazy_static::shutdown()`](fn.shutdown.html).
 
-#![cfg_attr(feature="nightly", feature(const_fn, allow_internal_unstable, core_intrinsics))]
+# Forking
 
-#![doc(html_root_url = "https://docs.rs/lazy_static/0.2.8")]
-#![no_std]
+A value that holds on to resources of the process, like a file descriptor, a
+lock or a pool of threads, does not work as expected in the child of a
+`fork`, where only the thread that called `fork` keeps running. Marking its
+static with `#[lazy(on_fork_child = HOOK)]` calls `HOOK` with a reference to
+the value in the child, through `pthread_atfork`, if the static has been
+initialized, so that the value can reopen its resources. The hooks of
+different statics run in the order the statics were initialized in. Other
+platforms do not have `fork`, so the hooks are never called there. This
+requires `std`.
 
-#[cfg(not(feature="nightly"))]
-#[doc(hidden)]
-pub mod lazy;
+```rust
+# #[macro_use]
+# extern crate lazy_static;
+use std::sync::atomic::{AtomicU32, Ordering};
 
-#[cfg(all(feature="nightly", not(feature="spin_no_std")))]
-#[path="nightly_lazy.rs"]
-#[doc(hidden)]
-pub mod lazy;
+struct Client {
+    pid: AtomicU32,
+}
 
-#[cfg(all(feature="nightly", feature="spin_no_std"))]
-#[path="core_lazy.rs"]
-#[doc(hidden)]
-pub mod lazy;
+impl Client {
+    fn reconnect(&self) {
+        self.pid.store(std::process::id(), Ordering::SeqCst);
+    }
+}
 
-#[doc(hidden)]
-pub use core::ops::Deref as __Deref;
+# #[cfg(not(feature="spin_no_std"))]
+lazy_static! {
+    #[lazy(on_fork_child = Client::reconnect)]
+    static ref CLIENT: Client = Client { pid: AtomicU32::new(std::process::id()) };
+}
 
-#[macro_export]
-#[cfg_attr(feature="nightly", allow_internal_unstable)]
-#[doc(hidden)]
-macro_rules! __lazy_static_internal {
-    ($(#[$attr:meta])* static ref $N:ident : $T:ty = $e:expr; $($t:tt)*) => {
-        __lazy_static_internal!(@PRIV, $(#[$attr])* static ref $N : $T = $e; $($t)*);
-    };
-    ($(#[$attr:meta])* pub static ref $N:ident : $T:ty = $e:expr; $($t:tt)*) => {
-        __lazy_static_internal!(@PUB, $(#[$attr])* static ref $N : $T = $e; $($t)*);
-    };
-    (@$VIS:ident, $(#[$attr:meta])* static ref $N:ident : $T:ty = $e:expr; $($t:tt)*) => {
-        __lazy_static_internal!(@MAKE TY, $VIS, $(#[$attr])*, $N);
-        impl $crate::__Deref for $N {
-            type Target = $T;
-            #[allow(unsafe_code)]
-            fn deref(&self) -> &$T {
-                unsafe {
-                    #[inline(always)]
-                    fn __static_ref_initialize() -> $T { $e }
-
-                    #[inline(always)]
-                    unsafe fn __stability() -> &'static $T {
-                        __lazy_static_create!(LAZY, $T);
-                        LAZY.get(__static_ref_initialize)
-                    }
-                    __stability()
-                }
-            }
-        }
-        impl $crate::LazyStatic for $N {
-            fn initialize(lazy: &Self) {
-                let _ = &**lazy;
-            }
-        }
-        __lazy_static_internal!($($t)*);
-    };
-    (@MAKE TY, PUB, $(#[$attr:meta])*, $N:ident) => {
-        #[allow(missing_copy_implementations)]
-        #[allow(non_camel_case_types)]
-        #[allow(dead_code)]
-        $(#[$attr])*
-        pub struct $N {__private_field: ()}
-        #[doc(hidden)]
-        pub static $N: $N = $N {__private_field: ()};
-    };
-    (@MAKE TY, PRIV, $(#[$attr:meta])*, $N:ident) => {
-        #[allow(missing_copy_implementations)]
-        #[allow(non_camel_case_types)]
-        #[allow(dead_code)]
-        $(#[$attr])*
-        struct $N {__private_field: ()}
-        #[doc(hidden)]
-        static $N: $N = $N {__private_field: ()};
-    };
-    () => ()
+# #[cfg(not(feature="spin_no_std"))]
+fn main() {
+#     lazy_static::runtime_ready();
+    assert_eq!(CLIENT.pid.load(Ordering::SeqCst), std::process::id());
 }
+# #[cfg(feature="spin_no_std")]
+# fn main() {}
+```
 
-#[macro_export]
-#[cfg_attr(feature="nightly", allow_internal_unstable)]
-macro_rules! lazy_static {
-    ($(#[$attr:meta])* static ref $N:ident : $T:ty = $e:expr; $($t:tt)*) => {
-        __lazy_static_internal!(@PRIV, $(#[$attr])* static ref $N : $T = $e; $($t)*);
-    };
-    ($(#[$attr:meta])* pub static ref $N:ident : $T:ty = $e:expr; $($t:tt)*) => {
-        __lazy_static_internal!(@PUB, $(#[$attr])* static ref $N : $T = $e; $($t)*);
+The initializer can also be a function, named with `fn` in place of an
+expression. It is called through a pointer, so that a large initializer is
+not inlined into the code that initializes the static, which statics of the
+same type that are initialized by a function share.
+
+```rust
+# #[macro_use]
+# extern crate lazy_static;
+fn load_keywords() -> Vec<&'static str> {
+    vec!["fn", "let", "match"]
+}
+
+lazy_static! {
+    static ref KEYWORDS: Vec<&'static str> = fn load_keywords;
+}
+
+fn main() {
+#     lazy_static::runtime_ready();
+    assert_eq!(KEYWORDS.len(), 3);
+}
+```
+
+# Constant initializers
+
+If the expression can be evaluated at compile time, the static can be
+declared with `static ref const`. It is then stored in a plain `static`
+without any synchronization, but is still accessed through `Deref`, so that
+call sites do not change if the initializer later needs to run at runtime.
+
+```rust
+# #[macro_use]
+# extern crate lazy_static;
+lazy_static! {
+    static ref const LIMITS: [u32; 3] = [10, 100, 1000];
+}
+
+fn main() {
+    assert_eq!(LIMITS.len(), 3);
+    assert!(lazy_static::is_initialized(&LIMITS));
+}
+```
+
+# Panicking initializers
+
+If the initializer of a static panics, the static is poisoned, and every
+later deref panics as well. Unless the `spin_no_std` feature is enabled,
+those panics carry the name of the static and the message of the original
+panic, so that its cause is not lost. For initializers that depend on transient state,
+like a service that may not be reachable yet, a static can be marked with
+`#[lazy(retry_on_panic)]` instead, so that the next deref runs the
+initializer again. Such an initializer is not run under a lock, so it may
+run on several threads at the same time, but only one value is ever stored.
+
+When panics abort the process, as with `panic = "abort"` in the profile,
+nothing is left to observe a poisoned static, so the once guards of this
+crate are built without keeping track of it.
+
+```rust
+# #[macro_use]
+# extern crate lazy_static;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static READY: AtomicBool = AtomicBool::new(false);
+
+lazy_static! {
+    #[lazy(retry_on_panic)]
+    static ref SERVICE: &'static str = {
+        assert!(READY.load(Ordering::SeqCst), "not ready yet");
+        "connected"
     };
-    () => ()
 }
 
-/// Support trait for enabling a few common operation on lazy static values.
-///
-/// This is implemented by each defined lazy static, and
-/// used by the free functions in this crate.
-pub trait LazyStatic {
-    #[doc(hidden)]
-    fn initialize(lazy: &Self);
+fn main() {
+#     lazy_static::runtime_ready();
+    assert!(std::panic::catch_unwind(|| *SERVICE).is_err());
+    READY.store(true, Ordering::SeqCst);
+    assert_eq!(*SERVICE, "connected");
 }
+```
 
-/// Takes a shared reference to a lazy static and initializes
-/// it if it has not been already.
-///
-/// This can be used to control the initialization point of a lazy static.
-///
-/// Example:
-///
-/// ```rust
-/// #[macro_use]
-/// extern crate lazy_static;
-///
-/// lazy_static! {
-///     static ref BUFFER: Vec<u8> = (0..65537).collect();
-/// }
-///
-/// fn main() {
-///     lazy_static::initialize(&BUFFER);
-///
-///     // ...
-///     work_with_initialized_data(&BUFFER);
-/// }
-/// # fn work_with_initialized_data(_: &[u8]) {}
-/// ```
-pub fn initialize<T: LazyStatic>(lazy: &T) {
-    LazyStatic::initialize(lazy);
+# Fallible initialization
+
+An initializer that can fail is declared with `static ref try` and has to
+return a `Result`. Such a static does not implement `Deref`, but provides a
+`try_deref()` method that hands out either the value or the error of the
+initializer. Failed initializations are not stored, so the next call to
+`try_deref()` runs the initializer again. If several threads try to
+initialize the static at the same time, the initializer may run on each of
+them, but only one successful value is ever stored.
+
+```rust
+# #[macro_use]
+# extern crate lazy_static;
+use std::num::ParseIntError;
+
+lazy_static! {
+    static ref try PORT: Result<u16, ParseIntError> = "8080".parse();
+}
+
+fn main() {
+#     lazy_static::runtime_ready();
+    assert_eq!(PORT.try_deref(), Ok(&8080));
+}
+```
+
+When every caller should observe the same outcome instead, like for a
+configuration that is loaded once, the static can be marked with
+`#[lazy(cache_error)]`. The initializer then runs exactly once, and the
+result is stored whether it is a success or an error, so that `try_deref()`
+returns `Result<&T, &E>`. It cannot be combined with other `#[lazy]` options,
+apart from a backend.
+
+```rust
+# #[macro_use]
+# extern crate lazy_static;
+use std::num::ParseIntError;
+
+lazy_static! {
+    #[lazy(cache_error)]
+    static ref try LIMIT: Result<u32, ParseIntError> = "unlimited".parse();
+}
+
+fn main() {
+#     lazy_static::runtime_ready();
+    let error = LIMIT.try_deref().unwrap_err();
+    assert!(std::ptr::eq(error, LIMIT.try_deref().unwrap_err()));
+}
+```
+
+```compile_fail
+# #[macro_use]
+# extern crate lazy_static;
+lazy_static! {
+    #[lazy(cache_error)]
+    #[lazy(cache_padded)]
+    static ref try LIMIT: Result<u32, std::num::ParseIntError> = "unlimited".parse();
+}
+# fn main() {}
+```
+
+Services that prefer a degraded default over failing at the first use can
+give a fallback with `#[lazy(fallback = EXPR)]`. The fallback is stored if
+the initializer returns an error, or if it panics (except with the
+`spin_no_std` feature), so the static always has a value and is dereferenced
+like a plain one, which takes the same options as well. `used_fallback()`
+tells whether that happened.
+
+```rust
+# #[macro_use]
+# extern crate lazy_static;
+use std::num::ParseIntError;
+
+lazy_static! {
+    #[lazy(fallback = 4)]
+    static ref try WORKERS: Result<usize, ParseIntError> = "lots".parse();
+}
+
+fn main() {
+#     lazy_static::runtime_ready();
+    assert_eq!(*WORKERS, 4);
+    assert!(WORKERS.used_fallback());
+}
+```
+
+A static whose initializer may block for long, like one that fetches its
+value over the network, can be given a deadline and a fallback with
+`#[lazy(deadline = DURATION, fallback = EXPR)]`. The initializer then runs on
+a background thread, and the first accesses wait for it until the deadline
+has passed, after which they get the fallback instead. Once the initializer
+returns, its value is swapped in for every later access, while references to
+the fallback stay valid. `is_fallback()` tells whether the static is currently
+dereferenced to the fallback, and `lazy_static::initialize` waits for the real
+value. This is not available with the `spin_no_std` feature.
+
+```rust
+# #[macro_use]
+# extern crate lazy_static;
+use std::time::Duration;
+
+fn fetch_banner() -> String {
+    std::thread::sleep(Duration::from_millis(200));
+    String::from("Welcome back!")
+}
+
+# #[cfg(not(feature="spin_no_std"))]
+lazy_static! {
+    #[lazy(deadline = Duration::from_millis(10), fallback = String::from("Welcome!"))]
+    static ref BANNER: String = fetch_banner();
+}
+
+# #[cfg(not(feature="spin_no_std"))]
+fn main() {
+#     lazy_static::runtime_ready();
+    assert_eq!(*BANNER, "Welcome!");
+    assert!(BANNER.is_fallback());
+    lazy_static::initialize(&BANNER);
+    assert_eq!(*BANNER, "Welcome back!");
+}
+# #[cfg(feature="spin_no_std")]
+# fn main() {}
+```
+
+# Runtime initializers
+
+A static can also be declared without an initializer, which is then given at
+runtime by calling `init_with` on the static, exactly once. This separates
+declaring a static from deciding how its value is built, like in a plugin
+host whose plugins provide the value. Accessing the static before
+`init_with` was called panics, but does not prevent it from being
+initialized later.
+
+```rust
+# #[macro_use]
+# extern crate lazy_static;
+lazy_static! {
+    static ref GREETING: String;
+}
+
+fn main() {
+#     lazy_static::runtime_ready();
+    let name = "plugin";
+    GREETING.init_with(move || format!("hello from {}", name));
+    assert_eq!(*GREETING, "hello from plugin");
+}
+```
+
+# Unsized values
+
+A static can have an unsized type, written as a slice `[T]`, as `str` or as
+a trait object `dyn Trait`. Its initializer has to return a `Box` of that
+type, which is stored in the static, and the static is dereferenced to the
+unsized value itself.
+
+```rust
+# #[macro_use]
+# extern crate lazy_static;
+use std::fmt::Display;
+
+lazy_static! {
+    static ref PRIMES: [u32] = vec![2, 3, 5, 7].into_boxed_slice();
+    static ref NAME: str = format!("{}-{}", "lazy", "static").into_boxed_str();
+    static ref ANSWER: dyn Display + Send + Sync = Box::new(42);
+}
+
+fn main() {
+#     lazy_static::runtime_ready();
+    let primes: &'static [u32] = &PRIMES;
+    assert_eq!(primes.len(), 4);
+    assert_eq!(&*NAME, "lazy-static");
+    assert_eq!(ANSWER.to_string(), "42");
+}
+```
+
+# Accessor functions
+
+Code that does not want to rely on `Deref` for globals can mark a static
+with `#[lazy(accessor = name)]`, which generates a function
+`fn name() -> &'static T` next to it, with the same visibility, that returns
+the value and initializes it on the first call. With
+`#[lazy(accessor_only = name)]`, the static itself is only visible in its
+module, so that the function is the only way to reach the value from
+outside of it.
+
+```rust
+# #[macro_use]
+# extern crate lazy_static;
+mod config {
+    lazy_static! {
+        /// The directories searched for plugins.
+        #[lazy(accessor_only = plugin_dirs)]
+        pub static ref PLUGIN_DIRS: Vec<&'static str> = vec!["/usr/lib/plugins"];
+    }
+}
+
+fn main() {
+#     lazy_static::runtime_ready();
+    assert_eq!(config::plugin_dirs().len(), 1);
+}
+```
+
+# Dynamic libraries
+
+Every dynamic library that links this crate statically, like a `cdylib`
+plugin, has its own copy of the statics declared in it, so a static shared
+through a common crate is initialized once per library. With the
+`process-global` feature, a static marked with
+`#[lazy(process_global = "KEY")]` is instead initialized once per process,
+and every copy of it refers to the value created by the first one, under a
+key that has to be the same, and has to name the same type, in every copy.
+The copies find each other through an exported symbol, on Unix platforms,
+if they are linked at startup or loaded with `RTLD_GLOBAL`. Elsewhere, the
+value is only shared by the statics of one copy. The value is never
+dropped, and the option cannot be combined with others.
+
+```rust
+# #[macro_use]
+# extern crate lazy_static;
+# #[cfg(feature="process-global")]
+lazy_static! {
+    #[lazy(process_global = "my_plugin_host::SESSION")]
+    static ref SESSION: String = format!("session-{}", std::process::id());
+}
+
+# #[cfg(feature="process-global")]
+fn main() {
+    assert!(SESSION.starts_with("session-"));
+}
+# #[cfg(not(feature="process-global"))]
+# fn main() {}
+```
+
+# Accessors for C
+
+A Rust library that is embedded in a C or C++ program can give it access to
+its statics with `#[lazy(ffi)]`, which exports an `extern "C"` function named
+after the static with a `_get` suffix, returning a pointer to the value and
+initializing it on the first call. `#[lazy(ffi = "symbol")]` chooses the
+name of the function instead. The pointer stays valid until the program
+exits, and the C code sees the same value as the Rust code. The value has to
+be of a sized type, ideally `#[repr(C)]`, and a panicking initializer aborts
+the process, as it cannot unwind into C.
+
+```rust
+# #[macro_use]
+# extern crate lazy_static;
+#[repr(C)]
+pub struct Limits {
+    pub max_connections: u32,
+    pub max_requests: u32,
+}
+
+lazy_static! {
+    // `const struct Limits *LIMITS_get(void);`
+    #[lazy(ffi)]
+    pub static ref LIMITS: Limits = Limits { max_connections: 64, max_requests: 1024 };
+
+    // `const uint64_t *app_start_time(void);`
+    #[lazy(ffi = "app_start_time")]
+    pub static ref START_TIME: u64 = 1_700_000_000;
+}
+
+fn main() {
+#     lazy_static::runtime_ready();
+    assert_eq!(LIMITS.max_connections, 64);
+    assert_eq!(*START_TIME, 1_700_000_000);
+}
+```
+
+# Without the macro
+
+The [`sync::Lazy`](sync/struct.Lazy.html) type provides the same lazy
+initialization as a plain type, for code that prefers declaring
+`static FOO: Lazy<T> = Lazy::new(|| ...);` over using a macro. For values
+that are computed elsewhere and only stored once, like parsed command line
+arguments, there is [`sync::OnceCell`](sync/struct.OnceCell.html). Cheap
+initializers on paths that must never block can use
+[`sync::RaceLazy`](sync/struct.RaceLazy.html), which lets racing threads each
+compute the value and keeps the first one. Values that are only used on a
+single thread, like in `thread_local!`, can use the types of
+[`unsync`](unsync/index.html), which skip the synchronization.
+
+# Cache line padding
+
+A static that is read on a hot path from many threads is slowed down by
+writes to unrelated statics that happen to share its cache line. Marking it
+with `#[lazy(cache_padded)]` aligns and pads its storage to a cache line of
+its own. For the types of [`sync`](sync/index.html), the same is done by
+wrapping them in [`sync::CachePadded`](sync/struct.CachePadded.html).
+
+```rust
+# #[macro_use]
+# extern crate lazy_static;
+lazy_static! {
+    #[lazy(cache_padded)]
+    static ref ROUTES: Vec<&'static str> = vec!["/", "/health"];
+}
+
+fn main() {
+#     lazy_static::runtime_ready();
+    assert_eq!(ROUTES.len(), 2);
+}
+```
+
+# Storage placement
+
+The storage of a static can be given attributes that control where the linker
+puts it. `#[lazy(link_section = "...")]` places it in a section of its own,
+and `#[lazy(used)]` keeps it in the binary even if nothing appears to refer to
+it. Any other attribute that applies to a `static` is passed on with
+`#[lazy(storage(...))]`, such as `#[lazy(storage(#[link_section = ".ram"] #[used]))]`.
+Only the storage is placed there: a boxed or unsized value, or anything the
+value allocates itself, still lives on the heap.
+
+```rust
+# #[macro_use]
+# extern crate lazy_static;
+lazy_static! {
+    #[lazy(used)]
+    static ref BUILD_ID: &'static str = "2f6c1a";
+}
+
+fn main() {
+#     lazy_static::runtime_ready();
+    assert_eq!(*BUILD_ID, "2f6c1a");
+}
+```
+
+# Mutable statics
+
+A static declared with `static ref mut` is wrapped in a mutex, which is
+lazily initialized with the value of the expression. The mutex is
+`std::sync::Mutex`, or `spin::Mutex` with the `spin_no_std` feature, and
+the static provides a `lock()` method returning the guard of the mutex
+directly. With `std`, it panics if the mutex has been poisoned.
+
+```rust
+# #[macro_use]
+# extern crate lazy_static;
+lazy_static! {
+    static ref mut NAMES: Vec<&'static str> = Vec::new();
+}
+
+fn main() {
+#     lazy_static::runtime_ready();
+    NAMES.lock().push("foo");
+    assert_eq!(NAMES.lock().len(), 1);
+}
+```
+
+For values that are read much more often than they are written, a static
+declared with `static ref rwlock` is wrapped in a `RwLock` instead, and
+provides `read()` and `write()` methods.
+
+```rust
+# #[macro_use]
+# extern crate lazy_static;
+use std::collections::HashMap;
+
+lazy_static! {
+    static ref rwlock ROUTES: HashMap<&'static str, u32> = HashMap::new();
+}
+
+fn main() {
+#     lazy_static::runtime_ready();
+    ROUTES.write().insert("/", 200);
+    assert_eq!(ROUTES.read().get("/"), Some(&200));
+}
+```
+
+# Asynchronous initialization
+
+A static declared with `static ref async` is initialized by awaiting a
+future, like an `async` block, and provides a `get()` method returning a
+future that resolves to a reference to the value. It does not depend on any
+runtime, so it works with any executor, including embedded ones with the
+`spin_no_std` feature, given the `alloc` crate. See the
+[`future`](future/index.html) module for details.
+
+# Thread-local statics
+
+A static declared with `static ref thread_local` holds a separate value for
+every thread, which is lazily initialized the first time the thread uses
+it. As the value does not live for the whole program, it is not accessed
+through `Deref`, but through a `with()` method:
+
+```rust
+# #[macro_use]
+# extern crate lazy_static;
+use std::cell::RefCell;
+
+lazy_static! {
+    static ref thread_local SCRATCH: RefCell<Vec<u8>> = RefCell::new(Vec::with_capacity(64));
+}
+
+fn main() {
+    SCRATCH.with(|scratch| scratch.borrow_mut().push(1));
+    assert_eq!(SCRATCH.with(|scratch| scratch.borrow().len()), 1);
+}
+```
+
+# Example
+
+Using the macro:
+
+```rust
+#[macro_use]
+extern crate lazy_static;
+
+use std::collections::HashMap;
+
+lazy_static! {
+    static ref HASHMAP: HashMap<u32, &'static str> = {
+        let mut m = HashMap::new();
+        m.insert(0, "foo");
+        m.insert(1, "bar");
+        m.insert(2, "baz");
+        m
+    };
+    static ref COUNT: usize = HASHMAP.len();
+    static ref NUMBER: u32 = times_two(21);
+}
+
+fn times_two(n: u32) -> u32 { n * 2 }
+
+fn main() {
+#     lazy_static::runtime_ready();
+    println!("The map has {} entries.", *COUNT);
+    println!("The entry for `0` is \"{}\".", HASHMAP.get(&0).unwrap());
+    println!("A expensive calculation on a static results in: {}.", *NUMBER);
+}
+```
+
+# Implementation details
+
+The `Deref` implementation uses a hidden static variable that is guarded by a atomic check on each access. The value is stored inline in that static, so initializing it does not allocate.
+Only that check is inlined at each access, while the initialization is a separate function marked `#[cold]`.
+The code around the initializer of each static, which runs it under the once guard of the static, is shared
+by all statics, so that crates with many of them do not grow by more than the initializers themselves.
+
+# Synchronization backends
+
+Each static is guarded by a synchronization primitive picked by the cargo
+features of this crate. A different one can be chosen for a single static
+with a `#[lazy(backend = TYPE)]` attribute, where `TYPE` implements
+[`backend::OnceBackend`](backend/trait.OnceBackend.html).
+For microcontrollers, on which statics may also be accessed from interrupt
+handlers, [`backend::CriticalSectionOnce`](backend/struct.CriticalSectionOnce.html)
+initializes them in a critical section, like the one of the
+`critical-section` crate with the feature of the same name.
+
+The backends of this crate can also be named with a string, so that a
+program can guard the statics used by its interrupt handlers differently
+from the other ones:
+
+- `"std"` is `std::sync::Once`, which is not available with `spin_no_std`.
+- `"parking_lot"` is `parking_lot::Once`, with the `parking_lot` feature.
+- `"spin"` is `spin::Once`, with the `spin` feature, which `spin_no_std`
+  enables.
+- `"critical_section"` is `CriticalSectionOnce<GlobalCriticalSection>`, with
+  the `critical-section` feature.
+
+```rust
+# #[macro_use]
+# extern crate lazy_static;
+# #[cfg(not(feature="spin_no_std"))]
+lazy_static! {
+    #[lazy(backend = "std")]
+    static ref NAMES: Vec<&'static str> = vec!["main", "worker"];
+}
+
+# #[cfg(not(feature="spin_no_std"))]
+fn main() {
+#     lazy_static::runtime_ready();
+    assert_eq!(NAMES.len(), 2);
+}
+# #[cfg(feature="spin_no_std")]
+# fn main() {}
+```
+
+On `wasm32` targets without the `atomics` target feature, which cannot run
+more than one thread, the default primitive is a plain flag instead, so that
+accessing an initialized static is a load and a branch.
+
+With the `atomics` target feature, which has to be enabled together with a
+standard library rebuilt for it, the memory is shared between web workers,
+and statics are guarded by the regular `std::sync::Once` (or `spin::Once`),
+which synchronizes across them. Browsers do not allow the main thread to
+block, though, so it should not access a static that a worker may be
+initializing at the same time; `lazy_static::get` can be used to check for
+the value without blocking.
+
+# Miri
+
+The values of lazy statics are stored in an `UnsafeCell` next to their once
+guard, and references to them are only handed out once the guard has
+completed, so code using them can be checked by Miri, including with
+`-Zmiri-strict-provenance` and `-Zmiri-tree-borrows`.
+
+The expansion does not contain any `static mut` either, so it is not
+affected by the `static_mut_refs` lint, which is an error in the 2024
+edition.
+
+# Model checking
+
+When compiled with `--cfg loom`, `sync::Lazy`, `sync::OnceCell`,
+`sync::LazyArray`, `sync::LazyArc` and `future::AsyncLazy` are built on the primitives of the
+[`loom`](https://docs.rs/loom) crate, so that code using them can be run
+under `loom::model` to check every interleaving of their initialization.
+Since `loom` types cannot be created in a constant, their `new` functions are
+not `const` then, and the `lazy_static!` macro can only be used together
+with the `spin_no_std` feature.
+
+# Cargo features
+
+This crate builds on stable Rust, and none of its features require a nightly
+compiler. It provides the following cargo features:
+
+- `spin_no_std`: This allows using this crate in a no-std environment, by depending on the standalone `spin` crate.
+- `ctor`: This allows marking statics with `#[lazy(eager)]`, which initializes
+  them before `main` runs, so that their initialization does not slow down the
+  first access, or with `#[lazy(background)]`, which starts initializing them
+  on a thread of their own when the program starts, while accessing them waits
+  for that to complete. The latter is not available with `spin_no_std`.
+- `eager-all`: This initializes every lazy static before `main` runs, as if it
+  was marked with `#[lazy(eager)]`, and implies `ctor`. Statics marked with
+  `#[lazy(background)]` are still initialized on their own thread, and the
+  ones with a deadline keep it. Statics marked with `#[lazy(retry_on_panic)]`,
+  and the ones given their initializer with `init_with`, are left to their
+  first access. A static that is initialized before `main` cannot be given a
+  value with `lazy_static::set` anymore. It is meant
+  for debugging: initialization order problems and panics in initializers show up
+  at startup, in the same order on every run, instead of at the first access.
+- `strict`: This makes initializing a lazy static panic until
+  `lazy_static::runtime_ready()` is called. Statics marked with
+  `#[lazy(eager)]` or `#[lazy(background)]`, and every static with
+  `eager-all`, are initialized before `main` runs, so they cannot be used with
+  it.
+- `metrics`: This records how long the initializer of every static takes, which can be
+  listed with `lazy_static::metrics()`. It requires `std`.
+- `parking_lot`: This guards statics with `parking_lot::Once` instead of
+  `std::sync::Once`, which is smaller and handles contention better. It has
+  no effect together with `spin_no_std`.
+- `critical-section`: This adds `backend::GlobalCriticalSection`, which guards
+  statics using the `critical-section` crate, for microcontrollers on which
+  they are accessed from interrupt handlers.
+- `registry`: This adds every static to a registry that can be walked with
+  `lazy_static::registry()`, to inspect or initialize all statics of a program.
+  `lazy_static::initialize_all()` and `lazy_static::initialize_module()` force
+  every static, or those of one module, during a warm-up phase.
+  Values leaked with `lazy_static::leak()` are added to it as well.
+  `lazy_static::dump()` and `lazy_static_dump!()` report the state of every
+  static, with how long its initializer took and on which thread, which
+  `Dump::to_prometheus()` exports in the text format of Prometheus.
+- `access-counts`: This counts how many times every static is dereferenced,
+  which is reported by `StaticInfo::accesses()` of the registry, and implies
+  `registry`.
+- `backtrace`: This captures a backtrace of the thread that first forces every
+  static, which is reported with that thread and the time by
+  `StaticInfo::first_init()` of the registry, to find out what initializes a
+  static earlier than expected. It is meant for debugging, as capturing a
+  backtrace is slow, requires `std`, and implies `registry`.
+- `process-global`: This allows `#[lazy(process_global = "KEY")]`, which
+  initializes a static once per process, instead of once per dynamic library
+  containing a copy of it. It requires `std`.
+- `serde`: This implements `Serialize` for every lazy static and for `sync::Lazy`, by
+  serializing the value, which initializes it.
+- `log`: This logs a warning through the `log` crate, naming the static, when
+  an initializer takes longer than 100ms, or the threshold set with
+  `lazy_static::set_slow_init_threshold()`. It has no effect together with
+  `spin_no_std`.
+- `tokio`: This adds `spawn()` to `static ref async` statics and to
+  `future::AsyncLazy`, which initializes them on a task of the current tokio
+  runtime, and makes their `blocking_get()` run the initializer inside the
+  runtime, refusing to block one of its worker threads. It has no effect
+  together with `spin_no_std`.
+- `tracing`: This runs every initializer inside a `lazy_static::initialize` span of the
+  `tracing` crate, whose `name` field holds the path of the static.
+- `nightly`: This no longer changes anything, as the value is stored inline on
+  stable Rust as well. It is only kept so that manifests enabling it still build.
+- `test-support`: This adds `lazy_static::reset` and `sync::Lazy::reset`, which drop
+  the value of a lazy static so that tests can start from a fresh value, and
+  `lazy_static::override_scoped`, which replaces it until a guard is dropped.
+
+*/
+
+#![doc(html_root_url = "https://docs.rs/lazy_static/0.2.8")]
+#![no_std]
+
+pub mod backend;
+pub mod sync;
+pub mod unsync;
+
+pub mod future;
+
+#[cfg(not(feature="spin_no_std"))]
+#[doc(hidden)]
+pub mod at_exit;
+
+#[cfg(not(feature="spin_no_std"))]
+#[doc(hidden)]
+pub mod env;
+
+#[cfg(not(feature="spin_no_std"))]
+mod epoch;
+
+#[cfg(not(feature="spin_no_std"))]
+#[doc(hidden)]
+pub mod fork;
+
+#[cfg(all(feature="process-global", not(feature="spin_no_std")))]
+#[doc(hidden)]
+pub mod global;
+
+#[cfg(not(feature="spin_no_std"))]
+mod leak;
+
+#[cfg(not(feature="spin_no_std"))]
+pub use leak::{leak, leak_dropped_at_exit};
+
+#[doc(hidden)]
+pub mod init;
+
+#[cfg(feature="metrics")]
+pub mod metrics;
+
+#[cfg(feature="metrics")]
+pub use metrics::metrics;
+
+#[cfg(feature="registry")]
+pub mod registry;
+
+#[cfg(feature="registry")]
+pub use registry::{initialize_all, initialize_module, registry};
+
+#[cfg(all(feature="registry", not(feature="spin_no_std")))]
+pub use registry::dump;
+
+#[cfg(any(not(loom), feature="spin_no_std"))]
+mod lazy_cell;
+
+#[cfg(not(feature="spin_no_std"))]
+#[doc(hidden)]
+pub mod lazy;
+
+#[cfg(feature="spin_no_std")]
+#[path="core_lazy.rs"]
+#[doc(hidden)]
+pub mod lazy;
+
+#[cfg(loom)]
+#[doc(hidden)]
+pub mod loom_lazy;
+
+#[cfg(not(feature="spin_no_std"))]
+use core::time::Duration;
+
+extern crate alloc;
+
+#[doc(hidden)]
+pub use alloc::boxed::Box as __Box;
+
+#[doc(hidden)]
+pub use core::ops::Deref as __Deref;
+
+#[doc(hidden)]
+pub use core::fmt as __fmt;
+
+#[doc(hidden)]
+pub use core::convert::AsRef as __AsRef;
+
+#[doc(hidden)]
+pub use core::borrow::Borrow as __Borrow;
+
+// the type of a static is checked with this before anything else uses it, so
+// that a type that is not `Send` and `Sync` gets an error naming it and
+// pointing at it
+#[doc(hidden)]
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` cannot be used in lazy_static! because it is not Send and Sync",
+    label = "the value of a lazy static is shared between threads, and may be dropped on another one",
+    note = "use `static ref thread_local` for a value that is not Send or not Sync"
+)]
+pub trait __Sync {}
+
+impl<T: ?Sized + Send + Sync> __Sync for T {}
+
+#[doc(hidden)]
+pub const fn __require_sync<T: ?Sized + __Sync>() {}
+
+// holds the `Lazy` of a static, whose type has been checked by
+// `__require_sync` instead, so that the error is not reported twice
+#[doc(hidden)]
+pub struct __Checked<L>(L);
+
+unsafe impl<L> Sync for __Checked<L> {}
+
+impl<L> __Checked<L> {
+    /// # Safety
+    ///
+    /// `L` has to be `Send` and `Sync`, which the macro checks with
+    /// `__require_sync`.
+    pub const unsafe fn new(lazy: L) -> Self {
+        __Checked(lazy)
+    }
+}
+
+impl<L> core::ops::Deref for __Checked<L> {
+    type Target = L;
+
+    #[inline(always)]
+    fn deref(&self) -> &L {
+        &self.0
+    }
+}
+
+#[cfg(feature="serde")]
+#[doc(hidden)]
+pub extern crate serde as __serde;
+
+#[cfg(feature="tracing")]
+extern crate tracing;
+
+#[cfg(feature="log")]
+extern crate log;
+
+#[cfg(feature="tokio")]
+#[doc(hidden)]
+pub extern crate tokio as __tokio;
+
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __lazy_static_internal {
+    // `#[cfg]` attributes are split off from the others, as they have to be
+    // applied to every generated item and not just to the wrapper type
+    (@ATTRS [$($cfg:tt)*] [$($attr:tt)*] [$($backend:tt)*] [$($opt:tt)*] #[cfg $($c:tt)*] $($t:tt)*) => {
+        $crate::__lazy_static_internal!(@ATTRS [$($cfg)* #[cfg $($c)*]] [$($attr)*] [$($backend)*] [$($opt)*] $($t)*);
+    };
+    // the backends of this crate can be named instead of spelling out their
+    // type
+    (@ATTRS [$($cfg:tt)*] [$($attr:tt)*] [$($backend:tt)*] [$($opt:tt)*] #[lazy(backend = "std")] $($t:tt)*) => {
+        $crate::__lazy_static_internal!(@ATTRS [$($cfg)*] [$($attr)*] [, $crate::backend::StdOnce] [$($opt)*] $($t)*);
+    };
+    (@ATTRS [$($cfg:tt)*] [$($attr:tt)*] [$($backend:tt)*] [$($opt:tt)*] #[lazy(backend = "parking_lot")] $($t:tt)*) => {
+        $crate::__lazy_static_internal!(@ATTRS [$($cfg)*] [$($attr)*] [, $crate::backend::ParkingLotOnce] [$($opt)*] $($t)*);
+    };
+    (@ATTRS [$($cfg:tt)*] [$($attr:tt)*] [$($backend:tt)*] [$($opt:tt)*] #[lazy(backend = "spin")] $($t:tt)*) => {
+        $crate::__lazy_static_internal!(@ATTRS [$($cfg)*] [$($attr)*] [, $crate::backend::SpinOnce] [$($opt)*] $($t)*);
+    };
+    (@ATTRS [$($cfg:tt)*] [$($attr:tt)*] [$($backend:tt)*] [$($opt:tt)*] #[lazy(backend = "critical_section")] $($t:tt)*) => {
+        $crate::__lazy_static_internal!(@ATTRS [$($cfg)*] [$($attr)*]
+            [, $crate::backend::CriticalSectionOnce<$crate::backend::GlobalCriticalSection>] [$($opt)*] $($t)*);
+    };
+    (@ATTRS [$($cfg:tt)*] [$($attr:tt)*] [$($backend:tt)*] [$($opt:tt)*] #[lazy(backend = $name:literal)] $($t:tt)*) => {
+        compile_error!(concat!("unknown backend ", stringify!($name), " in `#[lazy(backend = ...)]`, ",
+                               "expected \"std\", \"parking_lot\", \"spin\", \"critical_section\" or a type"));
+    };
+    (@ATTRS [$($cfg:tt)*] [$($attr:tt)*] [$($backend:tt)*] [$($opt:tt)*] #[lazy(backend = $B:ty)] $($t:tt)*) => {
+        $crate::__lazy_static_internal!(@ATTRS [$($cfg)*] [$($attr)*] [, $B] [$($opt)*] $($t)*);
+    };
+    (@ATTRS [$($cfg:tt)*] [$($attr:tt)*] [$($backend:tt)*] [$($opt:tt)*] #[lazy(depends_on = [$($dep:path),* $(,)*])] $($t:tt)*) => {
+        $crate::__lazy_static_internal!(@ATTRS [$($cfg)*] [$($attr)*] [$($backend)*] [$($opt)* (depends_on $($dep),*)] $($t)*);
+    };
+    // options that change the kind of a `static ref try` are put first, so
+    // that they are found whatever other options come along
+    (@ATTRS [$($cfg:tt)*] [$($attr:tt)*] [$($backend:tt)*] [$($opt:tt)*] #[lazy(fallback = $f:expr)] $($t:tt)*) => {
+        $crate::__lazy_static_internal!(@ATTRS [$($cfg)*] [$($attr)*] [$($backend)*] [(fallback $f) $($opt)*] $($t)*);
+    };
+    (@ATTRS [$($cfg:tt)*] [$($attr:tt)*] [$($backend:tt)*] [$($opt:tt)*] #[lazy(cache_error)] $($t:tt)*) => {
+        $crate::__lazy_static_internal!(@ATTRS [$($cfg)*] [$($attr)*] [$($backend)*] [cache_error $($opt)*] $($t)*);
+    };
+    (@ATTRS [$($cfg:tt)*] [$($attr:tt)*] [$($backend:tt)*] [$($opt:tt)*] #[lazy(deadline = $d:expr, fallback = $f:expr)] $($t:tt)*) => {
+        $crate::__lazy_static_internal!(@ATTRS [$($cfg)*] [$($attr)*] [$($backend)*] [$($opt)* (deadline $d, $f)] $($t)*);
+    };
+    // dropping a value that other threads may still be using is unsafe, so
+    // the option is only accepted as `unsafe(drop_at_exit)`
+    (@ATTRS [$($cfg:tt)*] [$($attr:tt)*] [$($backend:tt)*] [$($opt:tt)*] #[lazy(drop_at_exit)] $($t:tt)*) => {
+        compile_error!("`#[lazy(drop_at_exit)]` has to be written `#[lazy(unsafe(drop_at_exit))]`, \
+                        as the value must not be used by threads that outlive `main`");
+    };
+    (@ATTRS [$($cfg:tt)*] [$($attr:tt)*] [$($backend:tt)*] [$($opt:tt)*] #[lazy(unsafe(drop_at_exit))] $($t:tt)*) => {
+        $crate::__lazy_static_internal!(@ATTRS [$($cfg)*] [$($attr)*] [$($backend)*] [$($opt)* drop_at_exit] $($t)*);
+    };
+    (@ATTRS [$($cfg:tt)*] [$($attr:tt)*] [$($backend:tt)*] [$($opt:tt)*] #[lazy(ffi = $name:expr)] $($t:tt)*) => {
+        $crate::__lazy_static_internal!(@ATTRS [$($cfg)*] [$($attr)*] [$($backend)*] [$($opt)* (ffi $name)] $($t)*);
+    };
+    (@ATTRS [$($cfg:tt)*] [$($attr:tt)*] [$($backend:tt)*] [$($opt:tt)*] #[lazy(link_section = $s:expr)] $($t:tt)*) => {
+        $crate::__lazy_static_internal!(@ATTRS [$($cfg)*] [$($attr)*] [$($backend)*] [$($opt)* (storage #[link_section = $s])] $($t)*);
+    };
+    (@ATTRS [$($cfg:tt)*] [$($attr:tt)*] [$($backend:tt)*] [$($opt:tt)*] #[lazy(used)] $($t:tt)*) => {
+        $crate::__lazy_static_internal!(@ATTRS [$($cfg)*] [$($attr)*] [$($backend)*] [$($opt)* (storage #[used])] $($t)*);
+    };
+    (@ATTRS [$($cfg:tt)*] [$($attr:tt)*] [$($backend:tt)*] [$($opt:tt)*] #[lazy(storage($(#[$a:meta])*))] $($t:tt)*) => {
+        $crate::__lazy_static_internal!(@ATTRS [$($cfg)*] [$($attr)*] [$($backend)*] [$($opt)* (storage $(#[$a])*)] $($t)*);
+    };
+    (@ATTRS [$($cfg:tt)*] [$($attr:tt)*] [$($backend:tt)*] [$($opt:tt)*] #[lazy(accessor = $f:ident)] $($t:tt)*) => {
+        $crate::__lazy_static_internal!(@ATTRS [$($cfg)*] [$($attr)*] [$($backend)*] [$($opt)* (accessor $f)] $($t)*);
+    };
+    (@ATTRS [$($cfg:tt)*] [$($attr:tt)*] [$($backend:tt)*] [$($opt:tt)*] #[lazy(accessor_only = $f:ident)] $($t:tt)*) => {
+        $crate::__lazy_static_internal!(@ATTRS [$($cfg)*] [$($attr)*] [$($backend)*] [$($opt)* (accessor_only $f)] $($t)*);
+    };
+    (@ATTRS [$($cfg:tt)*] [$($attr:tt)*] [$($backend:tt)*] [$($opt:tt)*] #[lazy(on_fork_child = $f:expr)] $($t:tt)*) => {
+        $crate::__lazy_static_internal!(@ATTRS [$($cfg)*] [$($attr)*] [$($backend)*] [$($opt)* (on_fork_child $f)] $($t)*);
+    };
+    (@ATTRS [$($cfg:tt)*] [$($attr:tt)*] [$($backend:tt)*] [$($opt:tt)*] #[lazy(process_global = $id:expr)] $($t:tt)*) => {
+        $crate::__lazy_static_internal!(@ATTRS [$($cfg)*] [$($attr)*] [$($backend)*] [$($opt)* (process_global $id)] $($t)*);
+    };
+    (@ATTRS [$($cfg:tt)*] [$($attr:tt)*] [$($backend:tt)*] [$($opt:tt)*] #[lazy($o:ident)] $($t:tt)*) => {
+        $crate::__lazy_static_internal!(@ATTRS [$($cfg)*] [$($attr)*] [$($backend)*] [$($opt)* $o] $($t)*);
+    };
+    (@ATTRS [$($cfg:tt)*] [$($attr:tt)*] [$($backend:tt)*] [$($opt:tt)*] #[$($a:tt)*] $($t:tt)*) => {
+        $crate::__lazy_static_internal!(@ATTRS [$($cfg)*] [$($attr)* #[$($a)*]] [$($backend)*] [$($opt)*] $($t)*);
+    };
+    // optional visibility restrictions are wrapped in `()` to allow for
+    // explicitly passing otherwise implicit information about private items
+    (@ATTRS [$($cfg:tt)*] [$($attr:tt)*] [$($backend:tt)*] [(fallback $f:expr) cache_error $($opt:tt)*] ($($vis:tt)*) static ref try $N:ident $($t:tt)*) => {
+        compile_error!(concat!("lazy static `", stringify!($N), "` cannot have both ",
+                               "`#[lazy(fallback = ...)]` and `#[lazy(cache_error)]`"));
+    };
+    (@ATTRS [$($cfg:tt)*] [$($attr:tt)*] [$($backend:tt)*] [cache_error (fallback $f:expr) $($opt:tt)*] ($($vis:tt)*) static ref try $N:ident $($t:tt)*) => {
+        compile_error!(concat!("lazy static `", stringify!($N), "` cannot have both ",
+                               "`#[lazy(fallback = ...)]` and `#[lazy(cache_error)]`"));
+    };
+    // a static with a fallback always has a value, so it is dereferenced
+    // like a plain static, whose initializer runs the fallible one
+    (@ATTRS [$($cfg:tt)*] [$($attr:tt)*] [$($backend:tt)*] [(fallback $f:expr) $($opt:tt)*] ($($vis:tt)*) static ref try $N:ident : Result<$T:ty, $E:ty> = $e:expr; $($t:tt)*) => {
+        $crate::__lazy_static_internal!(@MAKE TY, [$($cfg)*] [$($attr)*] [$($opt)*], ($($vis)*), $N);
+        $crate::__lazy_static_internal!(@LAZY, [$($cfg)*] [$($backend)*] [$($opt)*] $N : $T);
+        $($cfg)*
+        impl $N {
+            /// Initializes the static if necessary, and returns whether its
+            /// initializer failed, so that it holds the fallback value.
+            #[allow(dead_code)]
+            $($vis)* fn used_fallback(&self) -> bool {
+                let _ = &**self;
+                Self::__static_ref_fallback().used()
+            }
+
+            fn __static_ref_fallback() -> &'static $crate::init::Fallback {
+                static FALLBACK: $crate::init::Fallback = $crate::init::Fallback::new();
+                &FALLBACK
+            }
+        }
+        $crate::__lazy_static_internal!(@TAIL, [$($cfg)*] [$($opt)*] $N : $T =
+            $N::__static_ref_fallback().run(|| -> Result<$T, $E> { $e }, || -> $T { $f }));
+        $crate::__lazy_static_internal!(@ACCESSORS, [$($cfg)*] [$($attr)*] [$($opt)*] ($($vis)*) $N : $T);
+        $crate::lazy_static!($($t)*);
+    };
+    // a process-global static only refers to the value, which is owned by
+    // the copy of this crate that created it
+    (@ATTRS [$($cfg:tt)*] [$($attr:tt)*] [$($backend:tt)*] [(process_global $id:expr)] ($($vis:tt)*) static ref $N:ident : $T:ty = $e:expr; $($t:tt)*) => {
+        $crate::__lazy_static_internal!(@MAKE TY, [$($cfg)*] [$($attr)*], ($($vis)*), $N);
+        $crate::__lazy_static_internal!(@LAZY, [$($cfg)*] [$($backend)*] [] $N : &'static $T);
+        $crate::__lazy_static_internal!(@TAIL, [$($cfg)*] [] [boxed] $N : $T, &'static $T =
+            $crate::__lazy_static_process_global!($id, $N : $T = $e));
+        $crate::lazy_static!($($t)*);
+    };
+    // the result is stored whatever it is, which the other options are not
+    // made for
+    (@ATTRS [$($cfg:tt)*] [$($attr:tt)*] [$($backend:tt)*] [cache_error $o:tt $($opt:tt)*] ($($vis:tt)*) static ref try $N:ident $($t:tt)*) => {
+        compile_error!(concat!("`#[lazy(cache_error)]` on lazy static `", stringify!($N),
+                               "` cannot be combined with other options"));
+    };
+    (@ATTRS [$($cfg:tt)*] [$($attr:tt)*] [$($backend:tt)*] [cache_error] ($($vis:tt)*) static ref try $N:ident : Result<$T:ty, $E:ty> = $e:expr; $($t:tt)*) => {
+        $crate::__lazy_static_internal!(@MAKE TY, [$($cfg)*] [$($attr)*], ($($vis)*), $N);
+        $crate::__lazy_static_internal!(@LAZY, [$($cfg)*] [$($backend)*] [cache_error] $N : Result<$T, $E>);
+        $($cfg)*
+        impl $N {
+            /// Returns a reference to the value, or to the error of the
+            /// initializer if the value could not be initialized.
+            #[allow(dead_code)]
+            #[inline]
+            $($vis)* fn try_deref(&self) -> Result<&$T, &$E> {
+                match Self::__static_ref_lazy().try_get() {
+                    Some(value) => value.as_ref(),
+                    None => Self::__static_ref_init().as_ref(),
+                }
+            }
+
+            #[cold]
+            #[inline(never)]
+            fn __static_ref_init() -> &'static Result<$T, $E> {
+                #[inline(always)]
+                fn __static_ref_initialize() -> Result<$T, $E> { $e }
+
+                let lazy = Self::__static_ref_lazy();
+                let _cycle = $crate::init::enter(Self::__STATIC_REF_NAME, lazy);
+                lazy.get(|| $crate::init::run(Self::__STATIC_REF_NAME, __static_ref_initialize))
+            }
+        }
+        $crate::lazy_static!($($t)*);
+    };
+    (@ATTRS [$($cfg:tt)*] [$($attr:tt)*] [$($backend:tt)*] [$($opt:tt)*] ($($vis:tt)*) static ref try $N:ident : Result<$T:ty, $E:ty> = $e:expr; $($t:tt)*) => {
+        $crate::__lazy_static_internal!(@MAKE TY, [$($cfg)*] [$($attr)*], ($($vis)*), $N);
+        $crate::__lazy_static_internal!(@LAZY, [$($cfg)*] [$($backend)*] [$($opt)*] $N : $T);
+        $($cfg)*
+        impl $N {
+            /// Returns a reference to the value, or the error of the
+            /// initializer if the value could not be initialized.
+            #[allow(dead_code)]
+            #[inline]
+            $($vis)* fn try_deref(&self) -> Result<&$T, $E> {
+                match Self::__static_ref_lazy().try_get() {
+                    Some(value) => Ok(value),
+                    None => Self::__static_ref_init(),
+                }
+            }
+
+            #[cold]
+            #[inline(never)]
+            fn __static_ref_init() -> Result<&'static $T, $E> {
+                #[inline(always)]
+                fn __static_ref_initialize() -> Result<$T, $E> { $e }
+
+                let lazy = Self::__static_ref_lazy();
+                let _cycle = $crate::init::enter(Self::__STATIC_REF_NAME, lazy);
+                lazy.try_get_or_init(|| $crate::init::run(Self::__STATIC_REF_NAME, __static_ref_initialize))
+            }
+        }
+        $crate::lazy_static!($($t)*);
+    };
+    (@ATTRS [$($cfg:tt)*] [$($attr:tt)*] [$($backend:tt)*] [$($opt:tt)*] ($($vis:tt)*) static ref thread_local $N:ident : $T:ty = $e:expr; $($t:tt)*) => {
+        $crate::__lazy_static_internal!(@MAKE TY, [$($cfg)*] [$($attr)*], ($($vis)*), $N);
+        $($cfg)*
+        impl $N {
+            /// Calls `f` with a reference to the value of the current
+            /// thread, initializing it first if necessary.
+            #[allow(dead_code)]
+            $($vis)* fn with<F, R>(&self, f: F) -> R
+                where F: FnOnce(&$T) -> R
+            {
+                #[inline(always)]
+                fn __static_ref_initialize() -> $T { $e }
+
+                #[inline(always)]
+                fn __static_ref_with<F, R>(f: F) -> R
+                    where F: FnOnce(&$T) -> R
+                {
+                    thread_local!(static LOCAL: $T = __static_ref_initialize());
+                    LOCAL.with(f)
+                }
+                __static_ref_with(f)
+            }
+        }
+        $crate::lazy_static!($($t)*);
+    };
+    (@ATTRS [$($cfg:tt)*] [$($attr:tt)*] [$($backend:tt)*] [$($opt:tt)*] ($($vis:tt)*) static ref async $N:ident : $T:ty = $e:expr; $($t:tt)*) => {
+        $crate::__lazy_static_internal!(@MAKE TY, [$($cfg)*] [$($attr)*], ($($vis)*), $N);
+        $($cfg)*
+        impl $N {
+            /// Returns a future resolving to a reference to the value, which
+            /// starts the initialization if it has not been started yet.
+            #[allow(dead_code)]
+            $($vis)* fn get(&'static self) -> $crate::future::Get<'static, $T> {
+                Self::__static_ref_lazy().get()
+            }
+
+            /// Blocks the current thread until the value has been
+            /// initialized, and returns a reference to it.
+            #[allow(dead_code)]
+            $($vis)* fn blocking_get(&'static self) -> &'static $T {
+                Self::__static_ref_lazy().blocking_get()
+            }
+
+            #[inline(always)]
+            fn __static_ref_lazy() -> &'static $crate::future::AsyncLazy<$T> {
+                #[inline(always)]
+                fn __static_ref_initialize() -> $crate::future::BoxFuture<$T> {
+                    $crate::future::__box_future($e)
+                }
+
+                static LAZY: $crate::future::AsyncLazy<$T> =
+                    $crate::future::AsyncLazy::new(__static_ref_initialize);
+                &LAZY
+            }
+        }
+        $crate::__lazy_static_tokio!([$($cfg)*] ($($vis)*) $N : $T);
+        $crate::lazy_static!($($t)*);
+    };
+    (@ATTRS [$($cfg:tt)*] [$($attr:tt)*] [$($backend:tt)*] [$($opt:tt)*] ($($vis:tt)*) static ref const $N:ident : $T:ty = $e:expr; $($t:tt)*) => {
+        $crate::__lazy_static_internal!(@MAKE TY, [$($cfg)*] [$($attr)*], ($($vis)*), $N);
+        $($cfg)*
+        const _: () = $crate::__require_sync::<$T>();
+        $($cfg)*
+        impl $crate::__Deref for $N {
+            type Target = $T;
+            fn deref(&self) -> &$T {
+                static __STATIC_REF_VALUE: $crate::__Checked<$T> = {
+                    let value: $T = $e;
+                    unsafe { $crate::__Checked::new(value) }
+                };
+                &__STATIC_REF_VALUE
+            }
+        }
+        $crate::__lazy_static_internal!(@FMT, [$($cfg)*] $N);
+        $crate::__lazy_static_internal!(@REF, [$($cfg)*] $N : $T);
+        $crate::__lazy_static_serde!([$($cfg)*] $N);
+        $($cfg)*
+        impl $crate::LazyStatic for $N {
+            type __Value = $T;
+            fn initialize(_: &Self) {}
+            fn is_initialized(_: &Self) -> bool {
+                true
+            }
+            fn get(lazy: &Self) -> Option<&$T> {
+                Some(&**lazy)
+            }
+            fn set(_: &Self, value: $T) -> Result<(), $T> {
+                Err(value)
+            }
+            unsafe fn reset(_: &Self) {}
+            unsafe fn take(_: &Self) -> Option<$T> {
+                None
+            }
+            fn is_running(_: &Self) -> bool {
+                false
+            }
+        }
+        $crate::lazy_static!($($t)*);
+    };
+    (@ATTRS [$($cfg:tt)*] [$($attr:tt)*] [$($backend:tt)*] [$($opt:tt)*] ($($vis:tt)*) static ref mut $N:ident : $T:ty = $e:expr; $($t:tt)*) => {
+        $($cfg)*
+        impl $N {
+            /// Locks the mutex, blocking the current thread until it is
+            /// available.
+            #[allow(dead_code)]
+            $($vis)* fn lock(&'static self) -> $crate::lazy::MutexGuard<'static, $T> {
+                $crate::lazy::lock(self)
+            }
+        }
+        $crate::__lazy_static_internal!(@ATTRS [$($cfg)*] [$($attr)*] [$($backend)*] [$($opt)*] ($($vis)*)
+            static ref $N : $crate::lazy::Mutex<$T> = $crate::lazy::Mutex::new($e); $($t)*);
+    };
+    (@ATTRS [$($cfg:tt)*] [$($attr:tt)*] [$($backend:tt)*] [$($opt:tt)*] ($($vis:tt)*) static ref rwlock $N:ident : $T:ty = $e:expr; $($t:tt)*) => {
+        $($cfg)*
+        impl $N {
+            /// Locks the value for reading, blocking the current thread
+            /// while it is locked for writing.
+            #[allow(dead_code)]
+            $($vis)* fn read(&'static self) -> $crate::lazy::RwLockReadGuard<'static, $T> {
+                $crate::lazy::read(self)
+            }
+
+            /// Locks the value for writing, blocking the current thread
+            /// while it is locked for reading or writing.
+            #[allow(dead_code)]
+            $($vis)* fn write(&'static self) -> $crate::lazy::RwLockWriteGuard<'static, $T> {
+                $crate::lazy::write(self)
+            }
+        }
+        $crate::__lazy_static_internal!(@ATTRS [$($cfg)*] [$($attr)*] [$($backend)*] [$($opt)*] ($($vis)*)
+            static ref $N : $crate::lazy::RwLock<$T> = $crate::lazy::RwLock::new($e); $($t)*);
+    };
+    // the initializer of a static without one is given at runtime, and the
+    // static is only retried when accessed before that
+    (@ATTRS [$($cfg:tt)*] [$($attr:tt)*] [$($backend:tt)*] [$($opt:tt)*] ($($vis:tt)*) static ref $N:ident : $T:ty; $($t:tt)*) => {
+        $($cfg)*
+        impl $N {
+            /// Sets the function that initializes the static on its first
+            /// access.
+            ///
+            /// Panics if the initializer has already been set.
+            #[allow(dead_code)]
+            $($vis)* fn init_with<F>(&self, init: F)
+                where F: FnOnce() -> $T + Send + 'static
+            {
+                // the initializer is taken out of the slot by the first
+                // access, so whether it was set is kept apart from it
+                let set = {
+                    let mut slot = $crate::lazy::lock(Self::__static_ref_init_with());
+                    let set = slot.0 || $crate::is_initialized(self);
+                    if !set {
+                        *slot = (true, Some($crate::__Box::new(init)));
+                    }
+                    set
+                };
+                if set {
+                    panic!(concat!("the initializer of lazy static `", stringify!($N), "` has already been set"));
+                }
+            }
+
+            fn __static_ref_init_with() -> &'static $crate::lazy::Mutex<(bool, Option<$crate::__Box<dyn FnOnce() -> $T + Send>>)> {
+                static INIT: $crate::lazy::Mutex<(bool, Option<$crate::__Box<dyn FnOnce() -> $T + Send>>)> = $crate::lazy::Mutex::new((false, None));
+                &INIT
+            }
+        }
+        $crate::__lazy_static_internal!(@ATTRS [$($cfg)*] [$($attr)*] [$($backend)*] [retry_on_panic $($opt)*] ($($vis)*)
+            static ref $N : $T = {
+                let init = $crate::lazy::lock($N::__static_ref_init_with()).1.take();
+                match init {
+                    Some(init) => init(),
+                    None => panic!(concat!("lazy static `", stringify!($N), "` was accessed before `",
+                                           stringify!($N), ".init_with()` was called")),
+                }
+            }; $($t)*);
+    };
+    // unsized values are boxed, which is only known from the way their
+    // type is written
+    (@ATTRS [$($cfg:tt)*] [$($attr:tt)*] [$($backend:tt)*] [$($opt:tt)*] ($($vis:tt)*) static ref $N:ident : [$E:ty] = $e:expr; $($t:tt)*) => {
+        $crate::__lazy_static_internal!(@UNSIZED [$($cfg)*] [$($attr)*] [$($backend)*] [$($opt)*] ($($vis)*) $N : [$E] = $e);
+        $crate::lazy_static!($($t)*);
+    };
+    (@ATTRS [$($cfg:tt)*] [$($attr:tt)*] [$($backend:tt)*] [$($opt:tt)*] ($($vis:tt)*) static ref $N:ident : str = $e:expr; $($t:tt)*) => {
+        $crate::__lazy_static_internal!(@UNSIZED [$($cfg)*] [$($attr)*] [$($backend)*] [$($opt)*] ($($vis)*) $N : str = $e);
+        $crate::lazy_static!($($t)*);
+    };
+    (@ATTRS [$($cfg:tt)*] [$($attr:tt)*] [$($backend:tt)*] [$($opt:tt)*] ($($vis:tt)*) static ref $N:ident : dyn $($t:tt)*) => {
+        $crate::__lazy_static_internal!(@DYN [$($cfg)*] [$($attr)*] [$($backend)*] [$($opt)*] ($($vis)*) $N [dyn] $($t)*);
+    };
+    // the bounds of a trait object cannot be followed by `=` in a `ty`
+    // fragment, so they are collected one token at a time instead
+    (@DYN [$($cfg:tt)*] [$($attr:tt)*] [$($backend:tt)*] [$($opt:tt)*] ($($vis:tt)*) $N:ident [$($T:tt)*] = $e:expr; $($t:tt)*) => {
+        $crate::__lazy_static_internal!(@UNSIZED [$($cfg)*] [$($attr)*] [$($backend)*] [$($opt)*] ($($vis)*) $N : $($T)* = $e);
+        $crate::lazy_static!($($t)*);
+    };
+    (@DYN [$($cfg:tt)*] [$($attr:tt)*] [$($backend:tt)*] [$($opt:tt)*] ($($vis:tt)*) $N:ident [$($T:tt)*] $next:tt $($t:tt)*) => {
+        $crate::__lazy_static_internal!(@DYN [$($cfg)*] [$($attr)*] [$($backend)*] [$($opt)*] ($($vis)*) $N [$($T)* $next] $($t)*);
+    };
+    (@UNSIZED [$($cfg:tt)*] [$($attr:tt)*] [$($backend:tt)*] [$($opt:tt)*] ($($vis:tt)*) $N:ident : $T:ty = $e:expr) => {
+        $crate::__lazy_static_internal!(@MAKE TY, [$($cfg)*] [$($attr)*] [$($opt)*], ($($vis)*), $N);
+        $crate::__lazy_static_internal!(@LAZY, [$($cfg)*] [$($backend)*] [$($opt)*] $N : $crate::__Box<$T>);
+        $crate::__lazy_static_internal!(@TAIL, [$($cfg)*] [$($opt)*] [boxed] $N : $T, $crate::__Box<$T> = $e);
+        $crate::__lazy_static_internal!(@ACCESSORS, [$($cfg)*] [$($attr)*] [$($opt)*] ($($vis)*) $N : $T);
+    };
+    // the type of a static cannot be inferred on stable Rust, neither from
+    // its initializer nor from the return type of a function, as it has to
+    // be named in the generated items, so this gives a better error than
+    // the one about `_` in them
+    (@ATTRS [$($cfg:tt)*] [$($attr:tt)*] [$($backend:tt)*] [$($opt:tt)*] ($($vis:tt)*) static ref $N:ident $(: _)* = $e:expr; $($t:tt)*) => {
+        compile_error!(concat!("the type of lazy static `", stringify!($N), "` has to be written out, ",
+                               "as in `static ref ", stringify!($N), ": Type = ...;`, ",
+                               "as Rust cannot infer the types of statics"));
+    };
+    // a function given as the initializer is called through a pointer, so
+    // that its body stays out of the code generated for the static
+    (@ATTRS [$($cfg:tt)*] [$($attr:tt)*] [$($backend:tt)*] [$($opt:tt)*] ($($vis:tt)*) static ref $N:ident : $T:ty = fn $init:path; $($t:tt)*) => {
+        $crate::__lazy_static_internal!(@ATTRS [$($cfg)*] [$($attr)*] [$($backend)*] [$($opt)*] ($($vis)*)
+            static ref $N : $T = $crate::init::call::<$T>($init); $($t)*);
+    };
+    (@ATTRS [$($cfg:tt)*] [$($attr:tt)*] [$($backend:tt)*] [$($opt:tt)*] ($($vis:tt)*) static ref $N:ident : $T:ty = $e:expr; $($t:tt)*) => {
+        $crate::__lazy_static_internal!(@MAKE TY, [$($cfg)*] [$($attr)*] [$($opt)*], ($($vis)*), $N);
+        $crate::__lazy_static_internal!(@LAZY, [$($cfg)*] [$($backend)*] [$($opt)*] $N : $T);
+        $crate::__lazy_static_internal!(@TAIL, [$($cfg)*] [$($opt)*] $N : $T = $e);
+        $crate::__lazy_static_internal!(@ACCESSORS, [$($cfg)*] [$($attr)*] [$($opt)*] ($($vis)*) $N : $T);
+        $crate::lazy_static!($($t)*);
+    };
+    // the backend is either empty, using the default of the `Lazy` type,
+    // or a comma followed by the type given in `#[lazy(backend = ...)]`
+    (@LAZY, [$($cfg:tt)*] [$($backend:tt)*] [$($opt:tt)*] $N:ident : $T:ty) => {
+        $($cfg)*
+        const _: () = $crate::__require_sync::<$T>();
+        $($cfg)*
+        impl $N {
+            const __STATIC_REF_NAME: &'static str = concat!(module_path!(), "::", stringify!($N));
+
+            #[inline(always)]
+            fn __static_ref_lazy() -> &'static $crate::lazy::Lazy<$T $($backend)*> {
+                $crate::__lazy_static_internal!(@STORAGE [$($opt)*] [] [] $crate::lazy::Lazy<$T $($backend)*>)
+            }
+        }
+    };
+    // the attributes of the storage, like `#[link_section]`, are collected
+    // from the options first, along with whether it is padded
+    (@STORAGE [(storage $($a:tt)*) $($opt:tt)*] [$($attr:tt)*] [$($pad:tt)*] $L:ty) => {
+        $crate::__lazy_static_internal!(@STORAGE [$($opt)*] [$($attr)* $($a)*] [$($pad)*] $L)
+    };
+    (@STORAGE [cache_padded $($opt:tt)*] [$($attr:tt)*] [$($pad:tt)*] $L:ty) => {
+        $crate::__lazy_static_internal!(@STORAGE [$($opt)*] [$($attr)*] [cache_padded] $L)
+    };
+    (@STORAGE [$skip:tt $($opt:tt)*] [$($attr:tt)*] [$($pad:tt)*] $L:ty) => {
+        $crate::__lazy_static_internal!(@STORAGE [$($opt)*] [$($attr)*] [$($pad)*] $L)
+    };
+    // `#[lazy(cache_padded)]` puts the `Lazy` in a cache line of its own,
+    // which is dereferenced to it when it is returned
+    (@STORAGE [] [$($attr:tt)*] [cache_padded] $L:ty) => {{
+        $($attr)*
+        static LAZY: $crate::sync::CachePadded<$crate::__Checked<$L>> =
+            $crate::sync::CachePadded::new(unsafe { $crate::__Checked::new(<$L>::new()) });
+        &LAZY
+    }};
+    (@STORAGE [] [$($attr:tt)*] [] $L:ty) => {{
+        $($attr)*
+        static LAZY: $crate::__Checked<$L> = unsafe { $crate::__Checked::new(<$L>::new()) };
+        &LAZY
+    }};
+    // options given with `#[lazy(...)]` hook into the initialization of the
+    // static, before (`@PRE`) and after (`@OPT`) the value has been computed
+    //
+    // the value is stored as `$S`, which is the target type `$T` itself,
+    // or a `Box<$T>` for unsized types and a `&'static $T` for process-global
+    // ones, as told by `$how`
+    (@TAIL, [$($cfg:tt)*] [$($opt:tt)*] [$how:ident] $N:ident : $T:ty, $S:ty = $e:expr) => {
+        $($cfg)*
+        impl $crate::__Deref for $N {
+            type Target = $T;
+            #[inline]
+            fn deref(&self) -> &Self::Target {
+                $crate::__lazy_static_accesses!(@HIT $N);
+                match Self::__static_ref_lazy().try_get() {
+                    Some(value) => $crate::__lazy_static_internal!(@DEREF $how, value),
+                    None => $crate::__lazy_static_internal!(@UNINIT [$($opt)*] $N),
+                }
+            }
+        }
+        // the initialization is kept out of line, so that only the check
+        // for an initialized value is inlined into every deref
+        $($cfg)*
+        impl $N {
+            // hooks the options of the static, and its shutdown, into a value
+            // that is about to be stored, whether it was computed or set
+            #[inline(always)]
+            fn __static_ref_store(value: $S) -> $S {
+                $($crate::__lazy_static_internal!(@OPT $opt, $N);)*
+                $crate::__lazy_static_shutdown!($N);
+                value
+            }
+
+            #[cold]
+            #[inline(never)]
+            fn __static_ref_init() -> &'static $T {
+                #[inline(always)]
+                fn __static_ref_initialize() -> $S {
+                    $($crate::__lazy_static_internal!(@PRE $opt, $N);)*
+                    $N::__static_ref_store($e)
+                }
+
+                let lazy = Self::__static_ref_lazy();
+                let value = if false $(|| $crate::__lazy_static_internal!(@IS retry_on_panic, $opt))* {
+                    let _cycle = $crate::init::enter(Self::__STATIC_REF_NAME, lazy);
+                    lazy.get_or_retry(|| $crate::init::run(Self::__STATIC_REF_NAME, __static_ref_initialize))
+                } else {
+                    lazy.get_static(Self::__STATIC_REF_NAME, __static_ref_initialize)
+                };
+                $crate::__lazy_static_internal!(@DEREF $how, value)
+            }
+        }
+        $crate::__lazy_static_internal!(@FMT, [$($cfg)*] $N);
+        $crate::__lazy_static_internal!(@REF, [$($cfg)*] $N : $T);
+        $crate::__lazy_static_serde!([$($cfg)*] $N);
+        $crate::__lazy_static_registry!([$($cfg)*] $N : $T);
+        $crate::__lazy_static_internal!(@ITEMS, [$($cfg)*] [$($opt)*] $N);
+        $crate::__lazy_static_eager_all!([$($cfg)*] [$($opt)*] $N);
+        $crate::__lazy_static_accesses!(@ITEMS, [$($cfg)*] $N);
+        $($cfg)*
+        impl $crate::LazyStatic for $N {
+            type __Value = $S;
+            fn initialize(_: &Self) {
+                if Self::__static_ref_lazy().try_get().is_none() {
+                    Self::__static_ref_init();
+                }
+            }
+            fn is_initialized(_: &Self) -> bool {
+                Self::__static_ref_lazy().is_initialized()
+            }
+            fn get(_: &Self) -> Option<&Self::Target> {
+                Self::__static_ref_lazy().try_get().map(|value| $crate::__lazy_static_internal!(@DEREF $how, value))
+            }
+            fn set(_: &Self, value: $S) -> Result<(), $S> {
+                let mut value = Some(value);
+                Self::__static_ref_lazy().get_static(Self::__STATIC_REF_NAME, || {
+                    $N::__static_ref_store(value.take().unwrap())
+                });
+                match value {
+                    Some(value) => Err(value),
+                    None => Ok(()),
+                }
+            }
+            unsafe fn reset(_: &Self) {
+                Self::__static_ref_lazy().reset()
+            }
+            unsafe fn take(_: &Self) -> Option<$S> {
+                Self::__static_ref_lazy().take_unchecked()
+            }
+            fn is_running(_: &Self) -> bool {
+                Self::__static_ref_lazy().is_running()
+            }
+        }
+    };
+    (@TAIL, [$($cfg:tt)*] [$($opt:tt)*] $N:ident : $T:ty = $e:expr) => {
+        $crate::__lazy_static_internal!(@TAIL, [$($cfg)*] [$($opt)*] [value] $N : $T, $T = $e);
+    };
+    // a static with a deadline is only initialized on the current thread
+    // through `lazy_static::initialize`, and waits for a background thread
+    // otherwise
+    (@UNINIT [(deadline $d:expr, $f:expr) $($opt:tt)*] $N:ident) => {
+        $N::__static_ref_deadline()
+    };
+    (@UNINIT [$skip:tt $($opt:tt)*] $N:ident) => {
+        $crate::__lazy_static_internal!(@UNINIT [$($opt)*] $N)
+    };
+    (@UNINIT [] $N:ident) => {
+        $N::__static_ref_init()
+    };
+    // functions returning the value, for code that spells out every access
+    // to a static instead of going through `Deref`
+    (@ACCESSORS, [$($cfg:tt)*] [$($attr:tt)*] [(accessor $f:ident) $($opt:tt)*] ($($vis:tt)*) $N:ident : $T:ty) => {
+        $crate::__lazy_static_internal!(@ACCESSOR, [$($cfg)*] [$($attr)*] ($($vis)*) $N : $T, $f);
+        $crate::__lazy_static_internal!(@ACCESSORS, [$($cfg)*] [$($attr)*] [$($opt)*] ($($vis)*) $N : $T);
+    };
+    (@ACCESSORS, [$($cfg:tt)*] [$($attr:tt)*] [(accessor_only $f:ident) $($opt:tt)*] ($($vis:tt)*) $N:ident : $T:ty) => {
+        $crate::__lazy_static_internal!(@ACCESSOR, [$($cfg)*] [$($attr)*] ($($vis)*) $N : $T, $f);
+        $crate::__lazy_static_internal!(@ACCESSORS, [$($cfg)*] [$($attr)*] [$($opt)*] ($($vis)*) $N : $T);
+    };
+    // `is_fallback` has the visibility of the static, which the other
+    // items generated for options do not need
+    (@ACCESSORS, [$($cfg:tt)*] [$($attr:tt)*] [(deadline $d:expr, $f:expr) $($opt:tt)*] ($($vis:tt)*) $N:ident : $T:ty) => {
+        $crate::__lazy_static_deadline!([$($cfg)*] ($($vis)*) $N, $d, $f);
+        $crate::__lazy_static_internal!(@ACCESSORS, [$($cfg)*] [$($attr)*] [$($opt)*] ($($vis)*) $N : $T);
+    };
+    (@ACCESSORS, [$($cfg:tt)*] [$($attr:tt)*] [$skip:tt $($opt:tt)*] ($($vis:tt)*) $N:ident : $T:ty) => {
+        $crate::__lazy_static_internal!(@ACCESSORS, [$($cfg)*] [$($attr)*] [$($opt)*] ($($vis)*) $N : $T);
+    };
+    (@ACCESSORS, [$($cfg:tt)*] [$($attr:tt)*] [] ($($vis:tt)*) $N:ident : $T:ty) => {};
+    (@ACCESSOR, [$($cfg:tt)*] [$($attr:tt)*] ($($vis:tt)*) $N:ident : $T:ty, $f:ident) => {
+        $($cfg)*
+        $($attr)*
+        #[inline]
+        $($vis)* fn $f() -> &'static $T {
+            $crate::__Deref::deref(&$N)
+        }
+    };
+    (@DEREF value, $value:expr) => { $value };
+    (@DEREF boxed, $value:expr) => { &**$value };
+    // runs `$body` before `main`, from the constructor section of the platform
+    (@CTOR, [$($cfg:tt)*] $body:block) => {
+        $($cfg)*
+        const _: () = {
+            extern "C" fn __static_ref_ctor() $body
+
+            #[used]
+            #[cfg_attr(any(target_os = "linux", target_os = "android", target_os = "freebsd",
+                           target_os = "netbsd", target_os = "openbsd", target_os = "dragonfly",
+                           target_os = "illumos", target_os = "solaris"),
+                       link_section = ".init_array")]
+            #[cfg_attr(any(target_os = "macos", target_os = "ios"), link_section = "__DATA,__mod_init_func")]
+            #[cfg_attr(windows, link_section = ".CRT$XCU")]
+            static __STATIC_REF_CTOR: extern "C" fn() = __static_ref_ctor;
+        };
+    };
+    // options that generate items next to the static
+    (@ITEMS, [$($cfg:tt)*] [eager $($opt:tt)*] $N:ident) => {
+        $crate::__lazy_static_eager!([$($cfg)*] $N);
+        $crate::__lazy_static_internal!(@ITEMS, [$($cfg)*] [$($opt)*] $N);
+    };
+    (@ITEMS, [$($cfg:tt)*] [background $($opt:tt)*] $N:ident) => {
+        $crate::__lazy_static_background!([$($cfg)*] $N);
+        $crate::__lazy_static_internal!(@ITEMS, [$($cfg)*] [$($opt)*] $N);
+    };
+    (@ITEMS, [$($cfg:tt)*] [ffi $($opt:tt)*] $N:ident) => {
+        $crate::__lazy_static_internal!(@FFI, [$($cfg)*] $N, concat!(stringify!($N), "_get"));
+        $crate::__lazy_static_internal!(@ITEMS, [$($cfg)*] [$($opt)*] $N);
+    };
+    (@ITEMS, [$($cfg:tt)*] [(ffi $name:expr) $($opt:tt)*] $N:ident) => {
+        $crate::__lazy_static_internal!(@FFI, [$($cfg)*] $N, $name);
+        $crate::__lazy_static_internal!(@ITEMS, [$($cfg)*] [$($opt)*] $N);
+    };
+    (@ITEMS, [$($cfg:tt)*] [$skip:tt $($opt:tt)*] $N:ident) => {
+        $crate::__lazy_static_internal!(@ITEMS, [$($cfg)*] [$($opt)*] $N);
+    };
+    (@ITEMS, [$($cfg:tt)*] [] $N:ident) => {};
+    // the accessor is exported under its symbol name whatever its
+    // visibility, and a panic in it aborts instead of unwinding into C
+    (@FFI, [$($cfg:tt)*] $N:ident, $name:expr) => {
+        $($cfg)*
+        const _: () = {
+            #[export_name = $name]
+            extern "C" fn __static_ref_get() -> *const <$N as $crate::__Deref>::Target {
+                &*$N
+            }
+        };
+    };
+    // the impls are generic over the target, so that they are only usable
+    // when it implements the trait, instead of failing to compile otherwise
+    (@FMT, [$($cfg:tt)*] $N:ident) => {
+        $($cfg)*
+        impl<T: ?Sized + $crate::__fmt::Debug> $crate::__fmt::Debug for $N
+            where $N: $crate::__Deref<Target = T>
+        {
+            fn fmt(&self, f: &mut $crate::__fmt::Formatter) -> $crate::__fmt::Result {
+                $crate::__fmt::Debug::fmt(&**self, f)
+            }
+        }
+        $($cfg)*
+        impl<T: ?Sized + $crate::__fmt::Display> $crate::__fmt::Display for $N
+            where $N: $crate::__Deref<Target = T>
+        {
+            fn fmt(&self, f: &mut $crate::__fmt::Formatter) -> $crate::__fmt::Result {
+                $crate::__fmt::Display::fmt(&**self, f)
+            }
+        }
+    };
+    // `Self::Target` keeps the `'static` lifetime of trait objects, which
+    // is lost when `$T` is written in the signature
+    (@REF, [$($cfg:tt)*] $N:ident : $T:ty) => {
+        $($cfg)*
+        impl $crate::__AsRef<$T> for $N {
+            fn as_ref(&self) -> &<Self as $crate::__Deref>::Target {
+                &**self
+            }
+        }
+        $($cfg)*
+        impl $crate::__Borrow<$T> for $N {
+            fn borrow(&self) -> &<Self as $crate::__Deref>::Target {
+                &**self
+            }
+        }
+    };
+    (@PRE (depends_on $($dep:path),*), $N:ident) => {
+        $($crate::initialize(&$dep);)*
+    };
+    (@PRE $opt:tt, $N:ident) => {};
+    (@OPT (depends_on $($dep:path),*), $N:ident) => {};
+    (@OPT drop_at_exit, $N:ident) => {
+        $crate::at_exit::__register(|| unsafe { $N::__static_ref_lazy().reset() })
+    };
+    (@OPT (on_fork_child $f:expr), $N:ident) => {{
+        static HOOK: $crate::fork::Hook = $crate::fork::Hook::new(|| $crate::fork::__run(&$N, $f));
+        $crate::fork::__register(&HOOK)
+    }};
+    (@OPT (storage $($a:tt)*), $N:ident) => {};
+    (@OPT (accessor $f:ident), $N:ident) => {};
+    (@OPT (accessor_only $f:ident), $N:ident) => {};
+    (@OPT ffi, $N:ident) => {};
+    (@OPT (ffi $name:expr), $N:ident) => {};
+    (@OPT (deadline $d:expr, $f:expr), $N:ident) => {};
+    (@OPT retry_on_panic, $N:ident) => {};
+    (@OPT eager, $N:ident) => {};
+    (@OPT background, $N:ident) => {};
+    (@OPT cache_padded, $N:ident) => {};
+    (@OPT (process_global $id:expr), $N:ident) => {
+        compile_error!(concat!("`#[lazy(process_global = ...)]` on lazy static `", stringify!($N),
+                               "` cannot be combined with other options"));
+    };
+    (@OPT (fallback $f:expr), $N:ident) => {
+        compile_error!(concat!("`#[lazy(fallback = ...)]` on lazy static `", stringify!($N),
+                               "` needs a fallible initializer, declared with `static ref try`"))
+    };
+    (@IS retry_on_panic, retry_on_panic) => { true };
+    (@IS $flag:ident, $opt:tt) => { false };
+    // a static with `#[lazy(accessor_only = ...)]` is only visible in its
+    // module, whatever its declared visibility, which its accessor has
+    (@MAKE TY, [$($cfg:tt)*] [$($attr:tt)*] [(accessor_only $f:ident) $($opt:tt)*], ($($vis:tt)*), $N:ident) => {
+        $crate::__lazy_static_internal!(@MAKE TY, [$($cfg)*] [$($attr)*], (), $N);
+    };
+    (@MAKE TY, [$($cfg:tt)*] [$($attr:tt)*] [$skip:tt $($opt:tt)*], ($($vis:tt)*), $N:ident) => {
+        $crate::__lazy_static_internal!(@MAKE TY, [$($cfg)*] [$($attr)*] [$($opt)*], ($($vis)*), $N);
+    };
+    (@MAKE TY, [$($cfg:tt)*] [$($attr:tt)*] [], ($($vis:tt)*), $N:ident) => {
+        $crate::__lazy_static_internal!(@MAKE TY, [$($cfg)*] [$($attr)*], ($($vis)*), $N);
+    };
+    (@MAKE TY, [$($cfg:tt)*] [$($attr:tt)*], ($($vis:tt)*), $N:ident) => {
+        #[allow(missing_copy_implementations)]
+        #[allow(non_camel_case_types)]
+        #[allow(dead_code)]
+        $($cfg)*
+        $($attr)*
+        $($vis)* struct $N {__private_field: ()}
+        #[doc(hidden)]
+        $($cfg)*
+        $($vis)* static $N: $N = $N {__private_field: ()};
+    };
+    () => ()
+}
+
+// whether `Serialize` is implemented depends on the features of this crate,
+// which are not visible to `#[cfg]` in the crate expanding the macro
+#[cfg(feature="serde")]
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __lazy_static_serde {
+    ([$($cfg:tt)*] $N:ident) => {
+        $($cfg)*
+        impl<T: ?Sized + $crate::__serde::Serialize> $crate::__serde::Serialize for $N
+            where $N: $crate::__Deref<Target = T>
+        {
+            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+                where S: $crate::__serde::Serializer
+            {
+                $crate::__serde::Serialize::serialize(&**self, serializer)
+            }
+        }
+    };
+}
+
+#[cfg(not(feature="serde"))]
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __lazy_static_serde {
+    ([$($cfg:tt)*] $N:ident) => {};
+}
+
+#[cfg(feature="registry")]
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __lazy_static_registry {
+    ([$($cfg:tt)*] $N:ident : $T:ty) => {
+        $($cfg)*
+        const _: () = {
+            static INFO: $crate::registry::StaticInfo = $crate::registry::StaticInfo::__new(
+                $N::__STATIC_REF_NAME,
+                stringify!($T),
+                || $crate::initialize(&$N),
+                || $crate::is_initialized(&$N),
+                $crate::__lazy_static_accesses!(@GET $N),
+            );
+
+            #[used]
+            #[cfg_attr(any(target_os = "linux", target_os = "android", target_os = "freebsd",
+                           target_os = "netbsd", target_os = "openbsd", target_os = "dragonfly",
+                           target_os = "illumos", target_os = "solaris"),
+                       link_section = "lazy_static_registry_0_2")]
+            #[cfg_attr(any(target_os = "macos", target_os = "ios"),
+                       link_section = "__DATA,__lzs_reg_0_2,regular,no_dead_strip")]
+            #[cfg_attr(windows, link_section = ".lzsreg$m")]
+            static ENTRY: $crate::registry::__Entry = Some(&INFO);
+        };
+    };
+}
+
+#[cfg(not(feature="registry"))]
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __lazy_static_registry {
+    ([$($cfg:tt)*] $N:ident : $T:ty) => {};
+}
+
+#[cfg(feature="access-counts")]
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __lazy_static_accesses {
+    (@ITEMS, [$($cfg:tt)*] $N:ident) => {
+        $($cfg)*
+        impl $N {
+            #[inline(always)]
+            fn __static_ref_accesses() -> &'static $crate::registry::AccessCounter {
+                static ACCESSES: $crate::registry::AccessCounter = $crate::registry::AccessCounter::new();
+                &ACCESSES
+            }
+        }
+    };
+    (@HIT $N:ident) => {
+        $N::__static_ref_accesses().hit()
+    };
+    (@GET $N:ident) => {
+        Some(|| $N::__static_ref_accesses().get())
+    };
+}
+
+#[cfg(not(feature="access-counts"))]
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __lazy_static_accesses {
+    (@ITEMS, [$($cfg:tt)*] $N:ident) => {};
+    (@HIT $N:ident) => {};
+    (@GET $N:ident) => { None };
+}
+
+#[cfg(feature="ctor")]
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __lazy_static_eager {
+    ([$($cfg:tt)*] $N:ident) => {
+        $crate::__lazy_static_internal!(@CTOR, [$($cfg)*] {
+            $crate::initialize(&$N);
+        });
+    };
+}
+
+#[cfg(not(feature="ctor"))]
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __lazy_static_eager {
+    ([$($cfg:tt)*] $N:ident) => {
+        compile_error!("`#[lazy(eager)]` requires the `ctor` feature of lazy_static");
+    };
+}
+
+#[cfg(all(feature="process-global", not(feature="spin_no_std")))]
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __lazy_static_process_global {
+    ($id:expr, $N:ident : $T:ty = $e:expr) => {
+        $crate::global::get_or_init::<$T, _>($id, || -> $T { $e })
+    };
+}
+
+#[cfg(not(all(feature="process-global", not(feature="spin_no_std"))))]
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __lazy_static_process_global {
+    ($id:expr, $N:ident : $T:ty = $e:expr) => {{
+        compile_error!("`#[lazy(process_global = ...)]` requires the `process-global` feature of lazy_static and `std`")
+    }};
+}
+
+#[cfg(not(feature="spin_no_std"))]
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __lazy_static_shutdown {
+    ($N:ident) => {{
+        static SHUTDOWN: $crate::at_exit::Shutdown =
+            $crate::at_exit::Shutdown::new(|| unsafe { $N::__static_ref_lazy().reset() });
+        $crate::at_exit::__register_shutdown(&SHUTDOWN)
+    }};
+}
+
+#[cfg(feature="spin_no_std")]
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __lazy_static_shutdown {
+    ($N:ident) => {};
+}
+
+// statics marked with `eager` or `background` are already scheduled by
+// their own constructor
+#[cfg(feature="eager-all")]
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __lazy_static_eager_all {
+    ([$($cfg:tt)*] [eager $($opt:tt)*] $N:ident) => {};
+    ([$($cfg:tt)*] [background $($opt:tt)*] $N:ident) => {};
+    ([$($cfg:tt)*] [(deadline $d:expr, $f:expr) $($opt:tt)*] $N:ident) => {};
+    // statics whose initializer may fail at first, including the ones
+    // given their initializer with `init_with`, would abort the program
+    ([$($cfg:tt)*] [retry_on_panic $($opt:tt)*] $N:ident) => {};
+    ([$($cfg:tt)*] [$skip:tt $($opt:tt)*] $N:ident) => {
+        $crate::__lazy_static_eager_all!([$($cfg)*] [$($opt)*] $N);
+    };
+    ([$($cfg:tt)*] [] $N:ident) => {
+        $crate::__lazy_static_eager!([$($cfg)*] $N);
+    };
+}
+
+#[cfg(not(feature="eager-all"))]
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __lazy_static_eager_all {
+    ([$($cfg:tt)*] [$($opt:tt)*] $N:ident) => {};
+}
+
+#[cfg(all(feature="tokio", not(feature="spin_no_std")))]
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __lazy_static_tokio {
+    ([$($cfg:tt)*] ($($vis:tt)*) $N:ident : $T:ty) => {
+        $($cfg)*
+        impl $N {
+            /// Starts the initialization on a task of the current tokio
+            /// runtime, without waiting for it.
+            #[allow(dead_code)]
+            $($vis)* fn spawn(&'static self) -> $crate::__tokio::task::JoinHandle<&'static $T> {
+                Self::__static_ref_lazy().spawn()
+            }
+        }
+    };
+}
+
+#[cfg(not(all(feature="tokio", not(feature="spin_no_std"))))]
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __lazy_static_tokio {
+    ([$($cfg:tt)*] ($($vis:tt)*) $N:ident : $T:ty) => {};
+}
+
+#[cfg(not(feature="spin_no_std"))]
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __lazy_static_deadline {
+    ([$($cfg:tt)*] ($($vis:tt)*) $N:ident, $d:expr, $f:expr) => {
+        $($cfg)*
+        impl $N {
+            /// Returns whether the static is dereferenced to its fallback
+            /// value, because its initializer has not returned within the
+            /// deadline, or has panicked.
+            #[allow(dead_code)]
+            $($vis)* fn is_fallback(&self) -> bool {
+                !$crate::is_initialized(self) && Self::__static_ref_deadline_state().expired($d)
+            }
+
+            fn __static_ref_deadline_state() -> &'static $crate::init::Deadline {
+                static DEADLINE: $crate::init::Deadline = $crate::init::Deadline::new();
+                &DEADLINE
+            }
+
+            #[cold]
+            #[inline(never)]
+            fn __static_ref_deadline() -> &'static <$N as $crate::__Deref>::Target {
+                static FALLBACK: $crate::lazy::Lazy<<$N as $crate::__Deref>::Target> = $crate::lazy::Lazy::new();
+
+                Self::__static_ref_deadline_state().wait(Self::__STATIC_REF_NAME, $d, || $crate::initialize(&$N));
+                match $crate::LazyStatic::get(&$N) {
+                    Some(value) => value,
+                    None => FALLBACK.get(|| $f),
+                }
+            }
+        }
+    };
+}
+
+#[cfg(feature="spin_no_std")]
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __lazy_static_deadline {
+    ([$($cfg:tt)*] ($($vis:tt)*) $N:ident, $d:expr, $f:expr) => {
+        compile_error!("`#[lazy(deadline = ..., fallback = ...)]` requires `std`");
+    };
+}
+
+#[cfg(all(feature="ctor", not(feature="spin_no_std")))]
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __lazy_static_background {
+    ([$($cfg:tt)*] $N:ident) => {
+        $crate::__lazy_static_internal!(@CTOR, [$($cfg)*] {
+            $crate::init::background($N::__STATIC_REF_NAME, || $crate::initialize(&$N));
+        });
+    };
+}
+
+#[cfg(not(all(feature="ctor", not(feature="spin_no_std"))))]
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __lazy_static_background {
+    ([$($cfg:tt)*] $N:ident) => {
+        compile_error!("`#[lazy(background)]` requires the `ctor` feature of lazy_static and `std`");
+    };
+}
+
+#[macro_export]
+macro_rules! lazy_static {
+    ($(#[$($attr:tt)*])* static ref $($t:tt)*) => {
+        // use `()` to explicitly forward the information about private items
+        $crate::__lazy_static_internal!(@ATTRS [] [] [] [] $(#[$($attr)*])* () static ref $($t)*);
+    };
+    ($(#[$($attr:tt)*])* pub static ref $($t:tt)*) => {
+        $crate::__lazy_static_internal!(@ATTRS [] [] [] [] $(#[$($attr)*])* (pub) static ref $($t)*);
+    };
+    ($(#[$($attr:tt)*])* pub ($($vis:tt)+) static ref $($t:tt)*) => {
+        $crate::__lazy_static_internal!(@ATTRS [] [] [] [] $(#[$($attr)*])* (pub ($($vis)+)) static ref $($t)*);
+    };
+    () => ()
+}
+
+/// Declares a lazy static that holds one of several implementations, picked
+/// by the CPU features detected at runtime.
+///
+/// Each arm lists the target features it needs, as accepted by
+/// `is_x86_feature_detected!` or `is_aarch64_feature_detected!`, after a
+/// `#[cfg]` attribute that gates it on its architecture. The arms are tried in order on
+/// the first access, and the first one whose features are all available is
+/// stored; the `_` arm is used if there is none. The value is usually a `fn`
+/// pointer or a boxed trait object, which is called through the static.
+///
+/// This is not available with the `spin_no_std` feature.
+///
+/// ```rust
+/// #[macro_use]
+/// extern crate lazy_static;
+///
+/// #[cfg(target_arch = "x86_64")]
+/// #[target_feature(enable = "avx2")]
+/// unsafe fn sum_avx2(values: &[u32]) -> u32 {
+///     values.iter().sum()
+/// }
+///
+/// fn sum_scalar(values: &[u32]) -> u32 {
+///     values.iter().sum()
+/// }
+///
+/// lazy_dispatch! {
+///     static ref SUM: fn(&[u32]) -> u32 = {
+///         #[cfg(target_arch = "x86_64")]
+///         "avx2" => |values| unsafe { sum_avx2(values) },
+///         _ => sum_scalar,
+///     };
+/// }
+///
+/// fn main() {
+/// #     lazy_static::runtime_ready();
+///     assert_eq!(SUM(&[1, 2, 3]), 6);
+/// }
+/// ```
+#[cfg(not(feature="spin_no_std"))]
+#[macro_export]
+macro_rules! lazy_dispatch {
+    ($(#[$($attr:tt)*])* static ref $($t:tt)*) => {
+        $crate::__lazy_dispatch_internal!($(#[$($attr)*])* () static ref $($t)*);
+    };
+    ($(#[$($attr:tt)*])* pub static ref $($t:tt)*) => {
+        $crate::__lazy_dispatch_internal!($(#[$($attr)*])* (pub) static ref $($t)*);
+    };
+    ($(#[$($attr:tt)*])* pub ($($vis:tt)+) static ref $($t:tt)*) => {
+        $crate::__lazy_dispatch_internal!($(#[$($attr)*])* (pub ($($vis)+)) static ref $($t)*);
+    };
+    () => ()
+}
+
+#[cfg(not(feature="spin_no_std"))]
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __lazy_dispatch_internal {
+    ($(#[$($attr:tt)*])* ($($vis:tt)*) static ref $N:ident : $T:ty = {
+        $(#[cfg($($cfg:tt)*)] $first:tt $(, $feature:tt)* => $imp:expr,)*
+        _ => $default:expr $(,)*
+    }; $($t:tt)*) => {
+        $crate::lazy_static! {
+            $(#[$($attr)*])*
+            $($vis)* static ref $N : $T = {
+                fn __static_ref_dispatch() -> $T {
+                    $(#[cfg($($cfg)*)] {
+                        if $crate::__lazy_dispatch_detect!($first) $(&& $crate::__lazy_dispatch_detect!($feature))* {
+                            return $imp;
+                        }
+                    })*
+                    $default
+                }
+                __static_ref_dispatch()
+            };
+        }
+        $crate::lazy_dispatch!($($t)*);
+    };
+}
+
+/// Prints a report of every lazy static to standard error.
+///
+/// The report lists each static with whether it has been initialized, how
+/// long its initializer took and on which thread, which helps to find out
+/// why a program is slow to start or hangs in the field. The same report is
+/// returned by [`lazy_static::dump()`](fn.dump.html), to be written
+/// somewhere else.
+///
+/// This requires the `registry` feature, and is not available with the
+/// `spin_no_std` feature.
+///
+/// ```rust
+/// #[macro_use]
+/// extern crate lazy_static;
+///
+/// lazy_static! {
+///     static ref GREETING: String = String::from("hello");
+/// }
+///
+/// fn main() {
+///     assert_eq!(GREETING.len(), 5);
+///     lazy_static_dump!();
+/// }
+/// ```
+#[cfg(all(feature="registry", not(feature="spin_no_std")))]
+#[macro_export]
+macro_rules! lazy_static_dump {
+    () => {
+        $crate::registry::__print_dump()
+    };
+}
+
+/// Declares lazy statics that are parsed from environment variables.
+///
+/// Each static names the variable it is read from, and optionally a default
+/// that is used if the variable is not set. The value is parsed with
+/// `FromStr` on the first access, which panics with the name of the
+/// variable, its value and the parse error if it is invalid, or if it is not
+/// set and there is no default.
+///
+/// This is not available with the `spin_no_std` feature.
+///
+/// ```rust
+/// #[macro_use]
+/// extern crate lazy_static;
+///
+/// lazy_env! {
+///     static PORT: u16 = "EXAMPLE_PORT", default 8080;
+///     pub static HOME: String = "HOME", default String::from("/");
+/// }
+///
+/// fn main() {
+/// #     lazy_static::runtime_ready();
+///     assert_eq!(*PORT, 8080);
+///     assert!(!HOME.is_empty());
+/// }
+/// ```
+#[cfg(not(feature="spin_no_std"))]
+#[macro_export]
+macro_rules! lazy_env {
+    ($(#[$($attr:tt)*])* static $($t:tt)*) => {
+        $crate::__lazy_env_internal!($(#[$($attr)*])* () static $($t)*);
+    };
+    ($(#[$($attr:tt)*])* pub static $($t:tt)*) => {
+        $crate::__lazy_env_internal!($(#[$($attr)*])* (pub) static $($t)*);
+    };
+    ($(#[$($attr:tt)*])* pub ($($vis:tt)+) static $($t:tt)*) => {
+        $crate::__lazy_env_internal!($(#[$($attr)*])* (pub ($($vis)+)) static $($t)*);
+    };
+    () => ()
+}
+
+#[cfg(not(feature="spin_no_std"))]
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __lazy_env_internal {
+    ($(#[$($attr:tt)*])* ($($vis:tt)*) static $N:ident : $T:ty = $var:expr, default $default:expr; $($t:tt)*) => {
+        $crate::lazy_static! {
+            $(#[$($attr)*])*
+            $($vis)* static ref $N : $T = $crate::env::or_default::<$T, _>($var, || $default);
+        }
+        $crate::lazy_env!($($t)*);
+    };
+    ($(#[$($attr:tt)*])* ($($vis:tt)*) static $N:ident : $T:ty = $var:expr; $($t:tt)*) => {
+        $crate::lazy_static! {
+            $(#[$($attr)*])*
+            $($vis)* static ref $N : $T = $crate::env::required::<$T>($var);
+        }
+        $crate::lazy_env!($($t)*);
+    };
+}
+
+// the feature names differ between architectures, so each arm is only
+// expanded on the architecture that its `#[cfg]` selects
+#[cfg(not(feature="spin_no_std"))]
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __lazy_dispatch_detect {
+    ($feature:tt) => {{
+        let detected: bool;
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        {
+            detected = ::std::arch::is_x86_feature_detected!($feature);
+        }
+        #[cfg(target_arch = "aarch64")]
+        {
+            detected = ::std::arch::is_aarch64_feature_detected!($feature);
+        }
+        #[cfg(not(any(target_arch = "x86", target_arch = "x86_64", target_arch = "aarch64")))]
+        {
+            detected = false;
+        }
+        detected
+    }};
+}
+
+/// Support trait for enabling a few common operation on lazy static values.
+///
+/// This is implemented by each defined lazy static, and
+/// used by the free functions in this crate. Statics declared with
+/// `static ref thread_local`, `static ref async`, or `static ref try`
+/// without a fallback do not dereference to a single value, so they do not
+/// implement it.
+pub trait LazyStatic: core::ops::Deref {
+    #[doc(hidden)]
+    type __Value;
+
+    #[doc(hidden)]
+    fn initialize(lazy: &Self);
+
+    #[doc(hidden)]
+    fn is_initialized(lazy: &Self) -> bool;
+
+    #[doc(hidden)]
+    fn get(lazy: &Self) -> Option<&Self::Target>;
+
+    #[doc(hidden)]
+    fn set(lazy: &Self, value: Self::__Value) -> Result<(), Self::__Value>;
+
+    #[doc(hidden)]
+    unsafe fn reset(lazy: &Self);
+
+    #[doc(hidden)]
+    unsafe fn take(lazy: &Self) -> Option<Self::__Value>;
+
+    #[doc(hidden)]
+    fn is_running(lazy: &Self) -> bool;
+}
+
+/// Takes a shared reference to a lazy static and initializes
+/// it if it has not been already.
+///
+/// This can be used to control the initialization point of a lazy static.
+///
+/// Example:
+///
+/// ```rust
+/// #[macro_use]
+/// extern crate lazy_static;
+///
+/// lazy_static! {
+///     static ref BUFFER: Vec<u8> = (0..255).collect();
+/// }
+///
+/// fn main() {
+/// #     lazy_static::runtime_ready();
+///     lazy_static::initialize(&BUFFER);
+///
+///     // ...
+///     work_with_initialized_data(&BUFFER);
+/// }
+/// # fn work_with_initialized_data(_: &[u8]) {}
+/// ```
+pub fn initialize<T: LazyStatic>(lazy: &T) {
+    LazyStatic::initialize(lazy);
+}
+
+/// Marks the application as ready for its lazy statics to be initialized.
+///
+/// With the `strict` feature, initializing any lazy static before this is
+/// called panics with the name of the static, which catches statics that
+/// are accessed by accident from constructors or during early startup, when
+/// the context they need is not set up yet. Without the feature, this does
+/// nothing, so libraries can call it unconditionally.
+///
+/// Example:
+///
+/// ```rust
+/// #[macro_use]
+/// extern crate lazy_static;
+///
+/// lazy_static! {
+///     static ref BUFFER: Vec<u8> = (0..255).collect();
+/// }
+///
+/// fn main() {
+///     // set up logging, configuration, ...
+///     lazy_static::runtime_ready();
+///     assert_eq!(BUFFER.len(), 255);
+/// }
+/// ```
+pub fn runtime_ready() {
+    init::ready();
+}
+
+/// Sets how long the initializer of a lazy static may take before a warning
+/// is logged, which is 100ms by default.
+///
+/// With the `log` feature, an initializer that takes longer logs a warning
+/// with the `lazy_static` target, naming the static, so that blocking
+/// initializations show up in production logs. Setting the threshold to
+/// `Duration::MAX` turns the warnings off.
+///
+/// Example:
+///
+/// ```rust
+/// extern crate lazy_static;
+///
+/// use std::time::Duration;
+///
+/// fn main() {
+///     lazy_static::set_slow_init_threshold(Duration::from_millis(500));
+/// }
+/// ```
+#[cfg(all(feature="log", not(feature="spin_no_std")))]
+pub fn set_slow_init_threshold(threshold: Duration) {
+    init::set_slow_threshold(threshold);
+}
+
+/// Returns whether a lazy static has already been initialized,
+/// without initializing it.
+///
+/// Example:
+///
+/// ```rust
+/// #[macro_use]
+/// extern crate lazy_static;
+///
+/// lazy_static! {
+///     static ref BUFFER: Vec<u8> = (0..255).collect();
+/// }
+///
+/// # #[cfg(not(feature="eager-all"))]
+/// fn main() {
+/// #     lazy_static::runtime_ready();
+///     assert!(!lazy_static::is_initialized(&BUFFER));
+///     lazy_static::initialize(&BUFFER);
+///     assert!(lazy_static::is_initialized(&BUFFER));
+/// }
+/// # #[cfg(feature="eager-all")]
+/// # fn main() {}
+/// ```
+pub fn is_initialized<T: LazyStatic>(lazy: &T) -> bool {
+    LazyStatic::is_initialized(lazy)
+}
+
+/// Returns a reference to the value of a lazy static if it has already
+/// been initialized, without initializing it otherwise.
+///
+/// This is useful in places where forcing the initialization would be
+/// wrong or too expensive, like in `Drop` impls or in logging code.
+///
+/// It never blocks: while another thread is running the initializer, it
+/// returns `None` instead of waiting for it, so it can be used from code
+/// that must not wait, like audio callbacks.
+///
+/// # Signal handlers
+///
+/// `get` is async-signal-safe: it only loads the state of the static, and
+/// never allocates, takes a lock or runs the initializer. A crash reporter
+/// can use it to read configuration from within a `SIGSEGV` handler, and gets
+/// `None` if the configuration was not initialized before the signal was
+/// raised. Dereferencing a static, or calling `initialize`, from a signal
+/// handler is not safe.
+///
+/// Example:
+///
+/// ```rust
+/// #[macro_use]
+/// extern crate lazy_static;
+///
+/// lazy_static! {
+///     static ref BUFFER: Vec<u8> = (0..255).collect();
+/// }
+///
+/// # #[cfg(not(feature="eager-all"))]
+/// fn main() {
+/// #     lazy_static::runtime_ready();
+///     assert_eq!(lazy_static::get(&BUFFER), None);
+///     lazy_static::initialize(&BUFFER);
+///     assert_eq!(lazy_static::get(&BUFFER).map(|b| b.len()), Some(255));
+/// }
+/// # #[cfg(feature="eager-all")]
+/// # fn main() {}
+/// ```
+pub fn get<T: LazyStatic>(lazy: &T) -> Option<&T::Target> {
+    LazyStatic::get(lazy)
+}
+
+/// Sets the value of a lazy static from the outside, so that its
+/// initializer never runs.
+///
+/// This lets `main` provide a value that is only known at runtime, like
+/// configuration parsed from the command line, to code that reads it from a
+/// lazy static. If the static has already been initialized, or is being
+/// initialized by another thread, the value is handed back in `Err` once
+/// that has completed. The value of an unsized static is passed as a `Box`,
+/// like its initializer returns it. A value that is set counts as the
+/// initialization of the static, so it is dropped by
+/// `#[lazy(unsafe(drop_at_exit))]` and [`shutdown`](fn.shutdown.html) like a
+/// computed one. With the `eager-all` feature, the static has already been
+/// initialized before `main` runs, so this always fails.
+///
+/// Example:
+///
+/// ```rust
+/// #[macro_use]
+/// extern crate lazy_static;
+///
+/// lazy_static! {
+///     static ref LOG_LEVEL: String = "info".to_string();
+/// }
+///
+/// # #[cfg(not(feature="eager-all"))]
+/// fn main() {
+/// #     lazy_static::runtime_ready();
+///     assert_eq!(lazy_static::set(&LOG_LEVEL, "debug".to_string()), Ok(()));
+///     assert_eq!(*LOG_LEVEL, "debug");
+///     assert_eq!(lazy_static::set(&LOG_LEVEL, "trace".to_string()), Err("trace".to_string()));
+/// }
+/// # #[cfg(feature="eager-all")]
+/// # fn main() {}
+/// ```
+pub fn set<T: LazyStatic>(lazy: &T, value: T::__Value) -> Result<(), T::__Value> {
+    LazyStatic::set(lazy, value)
+}
+
+/// Returns a reference to the value of a lazy static, initializing it if
+/// necessary, but waits at most `timeout` for another thread that is
+/// currently initializing it.
+///
+/// If the static is not being initialized, it is initialized by the current
+/// thread, which is not affected by the timeout. This is not available with
+/// the `spin_no_std` feature.
+///
+/// Example:
+///
+/// ```rust
+/// #[macro_use]
+/// extern crate lazy_static;
+///
+/// use std::time::Duration;
+///
+/// lazy_static! {
+///     static ref BUFFER: Vec<u8> = (0..255).collect();
+/// }
+///
+/// fn main() {
+/// #     lazy_static::runtime_ready();
+///     let buffer = lazy_static::get_timeout(&BUFFER, Duration::from_millis(10)).unwrap();
+///     assert_eq!(buffer.len(), 255);
+/// }
+/// ```
+#[cfg(not(feature="spin_no_std"))]
+pub fn get_timeout<T: LazyStatic>(lazy: &T, timeout: Duration) -> Result<&T::Target, Timeout> {
+    lazy::wait_timeout(timeout, || LazyStatic::get(lazy), || LazyStatic::is_running(lazy), || &**lazy)
+}
+
+/// The error returned by [`get_timeout`](fn.get_timeout.html) when another
+/// thread did not finish initializing a lazy static in time.
+#[cfg(not(feature="spin_no_std"))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Timeout(());
+
+#[cfg(not(feature="spin_no_std"))]
+impl core::fmt::Display for Timeout {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        f.write_str("timed out waiting for the initialization of a lazy static")
+    }
+}
+
+/// Drops the values of all initialized lazy statics, in the reverse order
+/// of their initialization, so that they are initialized again on their
+/// next access.
+///
+/// A static is dropped before the statics it depends on through
+/// `#[lazy(depends_on = [...])]`, as those are initialized first. If a
+/// destructor initializes another static, that one is dropped as well.
+/// Statics declared with `static ref const`, `static ref thread_local`,
+/// `static ref async`, or `static ref try` without a fallback (including
+/// `#[lazy(cache_error)]`) are not dropped. This is not available with the
+/// `spin_no_std` feature.
+///
+/// # Safety
+///
+/// No references to any of the values may be alive, and no other thread may
+/// access a lazy static while this runs.
+///
+/// Example:
+///
+/// ```rust
+/// #[macro_use]
+/// extern crate lazy_static;
+///
+/// lazy_static! {
+///     static ref BUFFER: Vec<u8> = vec![0; 4096];
+/// }
+///
+/// fn main() {
+/// #     lazy_static::runtime_ready();
+///     assert_eq!(BUFFER.len(), 4096);
+///     unsafe { lazy_static::shutdown() };
+///     assert!(!lazy_static::is_initialized(&BUFFER));
+/// }
+/// ```
+#[cfg(not(feature="spin_no_std"))]
+pub unsafe fn shutdown() {
+    at_exit::shutdown()
+}
+
+/// Drops the value of a lazy static, so that it is initialized again on
+/// its next access.
+///
+/// This is meant for tests that need a fresh value for each test case, and
+/// is only available with the `test-support` feature.
+///
+/// # Safety
+///
+/// No references to the value may be alive, and no other thread may access
+/// the lazy static while it is reset.
+///
+/// Example:
+///
+/// ```rust
+/// #[macro_use]
+/// extern crate lazy_static;
+///
+/// lazy_static! {
+///     static ref BUFFER: Vec<u8> = (0..255).collect();
+/// }
+///
+/// fn main() {
+///     lazy_static::initialize(&BUFFER);
+///     unsafe { lazy_static::reset(&BUFFER) };
+///     assert!(!lazy_static::is_initialized(&BUFFER));
+/// }
+/// ```
+#[cfg(feature="test-support")]
+pub unsafe fn reset<T: LazyStatic>(lazy: &T) {
+    LazyStatic::reset(lazy)
+}
+
+/// Replaces the value of a lazy static with `value` until the returned guard
+/// is dropped, which restores the previous value, or leaves the static
+/// uninitialized if it was.
+///
+/// This lets tests replace global configuration without leaking it into
+/// other tests. Overrides of all statics are serialized with a global lock,
+/// which the guard holds, so a test that overrides a static waits for other
+/// tests to drop their guards first. This is only available with the
+/// `test-support` feature, and not with the `spin_no_std` feature.
+///
+/// Panics if the value of the static cannot be replaced, like for a
+/// `static ref const`.
+///
+/// # Safety
+///
+/// No references to the value may be alive when the guard is created or
+/// dropped, and no other thread may access the lazy static at these points.
+///
+/// Example:
+///
+/// ```rust
+/// #[macro_use]
+/// extern crate lazy_static;
+///
+/// lazy_static! {
+///     static ref ENDPOINT: String = "https://example.com".to_string();
+/// }
+///
+/// fn main() {
+///     {
+///         let _guard = unsafe { lazy_static::override_scoped(&ENDPOINT, "http://localhost".to_string()) };
+///         assert_eq!(*ENDPOINT, "http://localhost");
+///     }
+///     assert_eq!(*ENDPOINT, "https://example.com");
+/// }
+/// ```
+#[cfg(all(feature="test-support", not(feature="spin_no_std")))]
+pub unsafe fn override_scoped<T: LazyStatic>(lazy: &'static T, value: T::__Value) -> Override<T> {
+    let lock = lazy::override_lock();
+    let previous = LazyStatic::take(lazy);
+    if LazyStatic::set(lazy, value).is_err() {
+        panic!("the value of this lazy static cannot be overridden");
+    }
+    Override {
+        lazy,
+        previous,
+        _lock: lock,
+    }
+}
+
+/// The guard returned by [`override_scoped`](fn.override_scoped.html), which
+/// restores the previous value of the lazy static when it is dropped.
+#[cfg(all(feature="test-support", not(feature="spin_no_std")))]
+#[must_use = "the previous value is restored as soon as the guard is dropped"]
+pub struct Override<T: LazyStatic + 'static> {
+    lazy: &'static T,
+    previous: Option<T::__Value>,
+    _lock: lazy::OverrideLock,
+}
+
+#[cfg(all(feature="test-support", not(feature="spin_no_std")))]
+impl<T: LazyStatic + 'static> Drop for Override<T> {
+    fn drop(&mut self) {
+        // the creator of the guard promised that nothing refers to the value
+        // at this point
+        unsafe { LazyStatic::reset(self.lazy) };
+        if let Some(previous) = self.previous.take() {
+            let _ = LazyStatic::set(self.lazy, previous);
+        }
+    }
 }
@@ -0,0 +1,152 @@
+// Copyright 2016 lazy-static.rs Developers
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+// The `Lazy` of `lazy.rs`, built on the primitives of `loom` so that its
+// synchronization can be model checked. `loom` has no `Once`, so the once
+// guard is a mutex and a state flag, and none of its types can be created in
+// a constant, so this can only back values created at runtime.
+
+extern crate loom;
+
+use core::marker::PhantomData;
+use self::loom::cell::UnsafeCell;
+use self::loom::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use self::loom::sync::Mutex;
+
+const INCOMPLETE: usize = 0;
+const COMPLETE: usize = 1;
+
+pub struct Lazy<T, B = ()> {
+    value: UnsafeCell<Option<T>>,
+    state: AtomicUsize,
+    lock: Mutex<()>,
+    running: AtomicBool,
+    _backend: PhantomData<B>,
+}
+
+// Marks a `Lazy` as being initialized while it is alive.
+struct Running<'a>(&'a AtomicBool);
+
+impl<'a> Running<'a> {
+    fn new(running: &'a AtomicBool) -> Self {
+        running.store(true, Ordering::Release);
+        Running(running)
+    }
+}
+
+impl<'a> Drop for Running<'a> {
+    fn drop(&mut self) {
+        self.0.store(false, Ordering::Release);
+    }
+}
+
+impl<T, B> Lazy<T, B> {
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> Self {
+        Lazy {
+            value: UnsafeCell::new(None),
+            state: AtomicUsize::new(INCOMPLETE),
+            lock: Mutex::new(()),
+            running: AtomicBool::new(false),
+            _backend: PhantomData,
+        }
+    }
+
+    fn call_once<F: FnOnce()>(&self, f: F) {
+        if self.state.load(Ordering::Acquire) == COMPLETE {
+            return;
+        }
+        let _lock = self.lock.lock().unwrap();
+        if self.state.load(Ordering::Acquire) != COMPLETE {
+            f();
+            self.state.store(COMPLETE, Ordering::Release);
+        }
+    }
+
+    pub fn get<F>(&self, f: F) -> &T
+        where F: FnOnce() -> T
+    {
+        self.call_once(|| {
+            let _running = Running::new(&self.running);
+            let value = f();
+            self.value.with_mut(|slot| unsafe { *slot = Some(value) });
+        });
+        self.value.with(|slot| match unsafe { &*slot } {
+            Some(ref x) => x,
+            None => unreachable!(),
+        })
+    }
+
+    pub fn get_static<F>(&self, name: &'static str, f: F) -> &T
+        where F: FnOnce() -> T
+    {
+        let _cycle = ::init::enter(name, self);
+        self.get(|| ::init::run(name, f))
+    }
+
+    pub fn get_or_retry<F>(&self, f: F) -> &T
+        where F: FnOnce() -> T
+    {
+        if let Some(value) = self.try_get() {
+            return value;
+        }
+        let value = f();
+        self.get(|| value)
+    }
+
+    pub fn try_get_or_init<F, E>(&self, f: F) -> Result<&T, E>
+        where F: FnOnce() -> Result<T, E>
+    {
+        if let Some(value) = self.try_get() {
+            return Ok(value);
+        }
+        let value = f()?;
+        Ok(self.get(|| value))
+    }
+
+    pub fn try_get(&self) -> Option<&T> {
+        if self.is_initialized() {
+            self.value.with(|slot| unsafe { (*slot).as_ref() })
+        } else {
+            None
+        }
+    }
+
+    pub fn get_mut(&mut self) -> Option<&mut T> {
+        self.value.with_mut(|slot| unsafe { (*slot).as_mut() })
+    }
+
+    pub fn take(&mut self) -> Option<T> {
+        let value = self.value.with_mut(|slot| unsafe { (*slot).take() });
+        self.state.store(INCOMPLETE, Ordering::Relaxed);
+        value
+    }
+
+    pub fn into_inner(self) -> Option<T> {
+        self.value.into_inner()
+    }
+
+    pub fn is_initialized(&self) -> bool {
+        self.state.load(Ordering::Acquire) == COMPLETE
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.running.load(Ordering::Acquire)
+    }
+
+    pub unsafe fn reset(&self) {
+        drop(self.take_unchecked());
+    }
+
+    pub unsafe fn take_unchecked(&self) -> Option<T> {
+        let value = self.value.with_mut(|slot| (*slot).take());
+        self.state.store(INCOMPLETE, Ordering::Release);
+        value
+    }
+}
+
+unsafe impl<T: Send + Sync, B> Sync for Lazy<T, B> {}
@@ -0,0 +1,59 @@
+// Copyright 2016 lazy-static.rs Developers
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+/*!
+Timing of the initializers of lazy statics.
+
+With the `metrics` feature, the duration of every initializer that has run
+is recorded, and can be listed with [`lazy_static::metrics()`](fn.metrics.html),
+for example to find out which statics make the startup of a program slow.
+
+```rust
+#[macro_use]
+extern crate lazy_static;
+
+lazy_static! {
+    static ref TABLE: Vec<u64> = (0..1000).map(|n| n * n).collect();
+}
+
+fn main() {
+    let _ = TABLE.len();
+    let timing = lazy_static::metrics().into_iter().find(|t| t.name.ends_with("::TABLE")).unwrap();
+    println!("{} took {:?}", timing.name, timing.duration);
+}
+```
+*/
+
+extern crate std;
+
+use self::std::prelude::v1::*;
+use self::std::sync::Mutex;
+use self::std::time::Duration;
+
+pub use self::std::time::Instant;
+
+static TIMINGS: Mutex<Vec<InitTiming>> = Mutex::new(Vec::new());
+
+/// The duration of one run of the initializer of a lazy static.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InitTiming {
+    /// The path of the static, including the module it is declared in.
+    pub name: &'static str,
+    /// The wall-clock time the initializer took.
+    pub duration: Duration,
+}
+
+#[doc(hidden)]
+pub fn record(name: &'static str, duration: Duration) {
+    TIMINGS.lock().unwrap_or_else(|e| e.into_inner()).push(InitTiming { name, duration });
+}
+
+/// Returns how long the initializers of the lazy statics that have been
+/// initialized so far took, in the order they finished.
+pub fn metrics() -> Vec<InitTiming> {
+    TIMINGS.lock().unwrap_or_else(|e| e.into_inner()).clone()
+}
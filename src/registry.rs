@@ -0,0 +1,667 @@
+// Copyright 2016 lazy-static.rs Developers
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+/*!
+A registry of all lazy statics declared in a program.
+
+With the `registry` feature, every static declared with `static ref` adds
+itself to a global list, which can be walked with
+[`lazy_static::registry()`](fn.registry.html), for example to initialize all
+statics during a warm-up phase or to report their state in a health check.
+The list covers the whole program, including the statics declared in
+dependencies. Statics declared with `static ref const` are always
+initialized, and the ones declared with `static ref thread_local`,
+`static ref async`, or `static ref try` without a fallback have no value that
+could be forced without a thread, a runtime or a way to report the error, so
+they are not in the list.
+
+```rust
+#[macro_use]
+extern crate lazy_static;
+
+lazy_static! {
+    static ref PRIMES: Vec<u32> = vec![2, 3, 5, 7];
+}
+
+fn main() {
+    let info = lazy_static::registry().find(|s| s.name().ends_with("::PRIMES")).unwrap();
+    assert_eq!(info.type_name(), "Vec<u32>");
+    assert!(!info.is_initialized());
+    info.initialize();
+    assert_eq!(PRIMES.len(), 4);
+}
+```
+
+Services that want to fail fast can force every static before they start
+accepting traffic with [`lazy_static::initialize_all()`](fn.initialize_all.html),
+or only the statics of one module with
+[`lazy_static::initialize_module()`](fn.initialize_module.html). A panicking
+initializer propagates out of these functions like it would out of the first
+dereference of its static.
+
+```rust
+#[macro_use]
+extern crate lazy_static;
+
+mod cache {
+    lazy_static! {
+        pub static ref SQUARES: Vec<u64> = (0..1000).map(|n| n * n).collect();
+    }
+}
+
+fn main() {
+    lazy_static::initialize_all();
+    assert!(lazy_static::is_initialized(&cache::SQUARES));
+}
+```
+
+With the `access-counts` feature, every dereference of a static is counted
+as well, and [`StaticInfo::accesses()`](struct.StaticInfo.html#method.accesses)
+reports the count, to find statics that are never used, or that are used so
+often that they deserve a closer look.
+
+Values leaked with [`lazy_static::leak()`](fn.leak.html) are registered as
+well, and can be told apart with
+[`StaticInfo::is_leaked()`](struct.StaticInfo.html#method.is_leaked).
+
+With the `backtrace` feature, the first time a static is forced is recorded
+as well, with the backtrace and name of the thread that forced it, which
+[`StaticInfo::first_init()`](struct.StaticInfo.html#method.first_init)
+reports, to answer what initialized a static so early without a debugger.
+
+To diagnose a program in the field, [`lazy_static::dump()`](fn.dump.html)
+reports every static with its state, and how long its initializer took on
+which thread, and [`lazy_static_dump!()`](../macro.lazy_static_dump.html)
+prints that report to standard error. The same report can be exported in
+the text format of Prometheus with
+[`Dump::to_prometheus()`](struct.Dump.html#method.to_prometheus), to be
+scraped along with the other metrics of a service.
+
+Every static places a pointer to its information in a section of the
+executable, which the linker collects from all crates, so the list is
+complete before any code runs, without running constructors. This works on
+the targets that produce ELF, Mach-O or PE files, while on other targets, like
+WebAssembly, the registry only holds leaked values.
+*/
+
+use core::ptr;
+use core::slice;
+use core::sync::atomic::{AtomicPtr, AtomicUsize, Ordering};
+
+/// Information about a declared lazy static.
+pub struct StaticInfo {
+    name: &'static str,
+    type_name: &'static str,
+    initialize: fn(),
+    is_initialized: fn() -> bool,
+    accesses: Option<fn() -> usize>,
+    leaked: bool,
+    next: AtomicPtr<StaticInfo>,
+}
+
+impl StaticInfo {
+    #[doc(hidden)]
+    pub const fn __new(
+        name: &'static str,
+        type_name: &'static str,
+        initialize: fn(),
+        is_initialized: fn() -> bool,
+        accesses: Option<fn() -> usize>,
+    ) -> Self {
+        StaticInfo {
+            name,
+            type_name,
+            initialize,
+            is_initialized,
+            accesses,
+            leaked: false,
+            next: AtomicPtr::new(ptr::null_mut()),
+        }
+    }
+
+    /// The information about a value leaked with `lazy_static::leak`.
+    #[cfg(not(feature="spin_no_std"))]
+    pub(crate) fn leaked(type_name: &'static str) -> Self {
+        StaticInfo {
+            name: "<leaked>",
+            type_name,
+            initialize: || {},
+            is_initialized: || true,
+            accesses: None,
+            leaked: true,
+            next: AtomicPtr::new(ptr::null_mut()),
+        }
+    }
+
+    /// The path of the static, including the module it is declared in.
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// The type of the static, as written in its declaration.
+    pub fn type_name(&self) -> &'static str {
+        self.type_name
+    }
+
+    /// Initializes the static if it has not been already.
+    pub fn initialize(&self) {
+        (self.initialize)()
+    }
+
+    /// Returns whether the static has already been initialized.
+    pub fn is_initialized(&self) -> bool {
+        (self.is_initialized)()
+    }
+
+    /// Returns how many times the static has been dereferenced, or `None`
+    /// if the `access-counts` feature is disabled.
+    ///
+    /// Initializing the static through `lazy_static::initialize` or
+    /// [`initialize`](#method.initialize) is not counted, so statics that
+    /// are only warmed up still show as unused.
+    pub fn accesses(&self) -> Option<usize> {
+        self.accesses.map(|accesses| accesses())
+    }
+
+    /// Returns whether this is a value leaked with
+    /// [`lazy_static::leak`](../fn.leak.html) instead of a declared static.
+    ///
+    /// Leaked values are named `<leaked>` and are always initialized.
+    pub fn is_leaked(&self) -> bool {
+        self.leaked
+    }
+
+    /// Returns where and when the static was first forced, with the
+    /// `backtrace` feature, or `None` if its initializer has not run yet.
+    #[cfg(all(feature="backtrace", not(feature="spin_no_std")))]
+    pub fn first_init(&self) -> Option<FirstInit> {
+        if self.leaked {
+            return None;
+        }
+        report::first_init(self.name)
+    }
+}
+
+/// Counts the dereferences of a static, with the `access-counts` feature.
+#[doc(hidden)]
+pub struct AccessCounter(AtomicUsize);
+
+impl AccessCounter {
+    #[allow(clippy::new_without_default)]
+    pub const fn new() -> Self {
+        AccessCounter(AtomicUsize::new(0))
+    }
+
+    #[inline(always)]
+    pub fn hit(&self) {
+        self.0.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn get(&self) -> usize {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// An entry of the section holding the statics of the program.
+///
+/// Linkers may pad between the entries of different crates, which is read
+/// as `None`.
+#[doc(hidden)]
+pub type __Entry = Option<&'static StaticInfo>;
+
+// the name of the section includes the version of the crate, so that copies
+// of it that disagree about the layout of `StaticInfo` use different ones
+#[cfg(all(not(miri), any(target_os = "linux", target_os = "android", target_os = "freebsd",
+                         target_os = "netbsd", target_os = "openbsd", target_os = "dragonfly",
+                         target_os = "illumos", target_os = "solaris")))]
+mod section {
+    use super::__Entry;
+
+    // only the addresses of the bounds are used
+    extern "C" {
+        #[link_name = "__start_lazy_static_registry_0_2"]
+        static START: u8;
+        #[link_name = "__stop_lazy_static_registry_0_2"]
+        static STOP: u8;
+    }
+
+    // the section has to exist for the linker to define its bounds, even
+    // if no static is declared
+    #[used]
+    #[link_section = "lazy_static_registry_0_2"]
+    static EMPTY: __Entry = None;
+
+    pub fn entries() -> &'static [__Entry] {
+        unsafe {
+            // keeps `EMPTY` in the same object file as the bounds
+            let _ = core::ptr::read_volatile(&EMPTY);
+            let start = &START as *const u8 as *const __Entry;
+            let len = (&STOP as *const u8 as *const __Entry).offset_from(start) as usize;
+            core::slice::from_raw_parts(start, len)
+        }
+    }
+}
+
+#[cfg(all(not(miri), any(target_os = "macos", target_os = "ios")))]
+mod section {
+    use super::__Entry;
+
+    extern "C" {
+        #[link_name = "\x01section$start$__DATA$__lzs_reg_0_2"]
+        static START: u8;
+        #[link_name = "\x01section$end$__DATA$__lzs_reg_0_2"]
+        static STOP: u8;
+    }
+
+    #[used]
+    #[link_section = "__DATA,__lzs_reg_0_2,regular,no_dead_strip"]
+    static EMPTY: __Entry = None;
+
+    pub fn entries() -> &'static [__Entry] {
+        unsafe {
+            let _ = core::ptr::read_volatile(&EMPTY);
+            let start = &START as *const u8 as *const __Entry;
+            let len = (&STOP as *const u8 as *const __Entry).offset_from(start) as usize;
+            core::slice::from_raw_parts(start, len)
+        }
+    }
+}
+
+// the linker sorts the sections `.lzsreg$...` by their suffix, so the
+// entries of the statics, in `$m`, are between the ones in `$a` and `$z`
+#[cfg(all(not(miri), windows))]
+mod section {
+    use super::__Entry;
+
+    #[used]
+    #[link_section = ".lzsreg$a"]
+    static START: __Entry = None;
+
+    #[used]
+    #[link_section = ".lzsreg$z"]
+    static STOP: __Entry = None;
+
+    pub fn entries() -> &'static [__Entry] {
+        unsafe {
+            let start = (&START as *const __Entry).add(1);
+            let len = (&STOP as *const __Entry).offset_from(start) as usize;
+            core::slice::from_raw_parts(start, len)
+        }
+    }
+}
+
+#[cfg(any(miri, not(any(target_os = "linux", target_os = "android", target_os = "freebsd",
+                        target_os = "netbsd", target_os = "openbsd", target_os = "dragonfly",
+                        target_os = "illumos", target_os = "solaris",
+                        target_os = "macos", target_os = "ios", windows))))]
+mod section {
+    use super::__Entry;
+
+    pub fn entries() -> &'static [__Entry] {
+        &[]
+    }
+}
+
+static HEAD: AtomicPtr<StaticInfo> = AtomicPtr::new(ptr::null_mut());
+
+// adds a value that is registered at runtime, like a leaked one
+#[cfg(not(feature="spin_no_std"))]
+pub(crate) fn register(info: &'static StaticInfo) {
+    let info_ptr = info as *const StaticInfo as *mut StaticInfo;
+    let mut head = HEAD.load(Ordering::Acquire);
+    loop {
+        info.next.store(head, Ordering::Relaxed);
+        match HEAD.compare_exchange_weak(head, info_ptr, Ordering::AcqRel, Ordering::Acquire) {
+            Ok(_) => return,
+            Err(current) => head = current,
+        }
+    }
+}
+
+/// An iterator over the registered lazy statics.
+///
+/// This is returned by [`lazy_static::registry()`](fn.registry.html).
+#[derive(Clone)]
+pub struct Registry {
+    entries: slice::Iter<'static, __Entry>,
+    next: Option<&'static StaticInfo>,
+}
+
+impl Iterator for Registry {
+    type Item = &'static StaticInfo;
+
+    fn next(&mut self) -> Option<&'static StaticInfo> {
+        if let Some(info) = self.entries.by_ref().flatten().next() {
+            return Some(info);
+        }
+        let info = self.next?;
+        self.next = unsafe { info.next.load(Ordering::Acquire).as_ref() };
+        Some(info)
+    }
+}
+
+/// Returns an iterator over all lazy statics declared in the program, in
+/// no particular order.
+pub fn registry() -> Registry {
+    Registry {
+        entries: section::entries().iter(),
+        next: unsafe { HEAD.load(Ordering::Acquire).as_ref() },
+    }
+}
+
+/// Initializes every lazy static declared in the program.
+///
+/// This leaves out the kinds of statics that are not in the registry, like
+/// the ones declared with `static ref try` without a fallback.
+pub fn initialize_all() {
+    for info in registry() {
+        info.initialize();
+    }
+}
+
+/// Initializes the lazy statics declared in the module at `path`, or in any
+/// module nested in it.
+///
+/// The path is written like the result of `module_path!()`, so a module can
+/// warm up its own statics with `initialize_module(module_path!())`.
+pub fn initialize_module(path: &str) {
+    for info in registry().filter(|s| in_module(s.name(), path)) {
+        info.initialize();
+    }
+}
+
+fn in_module(name: &str, path: &str) -> bool {
+    name.len() > path.len()
+        && name.starts_with(path)
+        && name[path.len()..].starts_with("::")
+}
+
+#[cfg(not(feature="spin_no_std"))]
+pub use self::report::{dump, Dump, InitState, StaticReport};
+
+#[cfg(not(feature="spin_no_std"))]
+#[doc(hidden)]
+pub use self::report::{Started, __print_dump};
+
+#[cfg(all(feature="backtrace", not(feature="spin_no_std")))]
+pub use self::report::FirstInit;
+
+#[cfg(not(feature="spin_no_std"))]
+mod report {
+    extern crate std;
+
+    use self::std::fmt::{self, Write};
+    use self::std::format;
+    use self::std::string::String;
+    use self::std::sync::Mutex;
+    use self::std::thread;
+    use self::std::time::{Duration, Instant};
+    use self::std::vec::Vec;
+    #[cfg(feature="backtrace")]
+    use self::std::backtrace::Backtrace;
+    #[cfg(feature="backtrace")]
+    use self::std::sync::Arc;
+    use super::registry;
+
+    // how long the last run of the initializer of each static took, and on
+    // which thread
+    static INITS: Mutex<Vec<(&'static str, Duration, String)>> = Mutex::new(Vec::new());
+
+    // where and when each static was first forced
+    #[cfg(feature="backtrace")]
+    static FIRST_INITS: Mutex<Vec<(&'static str, FirstInit)>> = Mutex::new(Vec::new());
+
+    fn thread_name() -> String {
+        let current = thread::current();
+        match current.name() {
+            Some(thread) => String::from(thread),
+            None => format!("{:?}", current.id()),
+        }
+    }
+
+    /// The start of an initializer, which is recorded once it returns.
+    pub struct Started(Instant);
+
+    impl Started {
+        pub fn now(name: &'static str) -> Self {
+            #[cfg(feature="backtrace")]
+            record_first(name);
+            let _ = name;
+            Started(Instant::now())
+        }
+
+        pub fn record(self, name: &'static str) {
+            let duration = self.0.elapsed();
+            let thread = thread_name();
+            let mut inits = INITS.lock().unwrap_or_else(|e| e.into_inner());
+            inits.retain(|&(n, _, _)| n != name);
+            inits.push((name, duration, thread));
+        }
+    }
+
+    /// Where and when a static was first forced, returned by
+    /// [`StaticInfo::first_init()`](struct.StaticInfo.html#method.first_init).
+    #[cfg(feature="backtrace")]
+    #[derive(Clone, Debug)]
+    pub struct FirstInit {
+        /// The name of the thread that forced the static, or its id if it
+        /// has no name.
+        pub thread: String,
+        /// When the initializer started.
+        pub at: Instant,
+        /// The stack of the thread when the initializer started.
+        pub backtrace: Arc<Backtrace>,
+    }
+
+    #[cfg(feature="backtrace")]
+    fn record_first(name: &'static str) {
+        let first_inits = || FIRST_INITS.lock().unwrap_or_else(|e| e.into_inner());
+        if first_inits().iter().any(|&(n, _)| n == name) {
+            return;
+        }
+        // captured without the lock, as walking the stack is slow
+        let first = FirstInit {
+            thread: thread_name(),
+            at: Instant::now(),
+            backtrace: Arc::new(Backtrace::force_capture()),
+        };
+        let mut first_inits = first_inits();
+        if !first_inits.iter().any(|&(n, _)| n == name) {
+            first_inits.push((name, first));
+        }
+    }
+
+    #[cfg(feature="backtrace")]
+    pub fn first_init(name: &str) -> Option<FirstInit> {
+        let first_inits = FIRST_INITS.lock().unwrap_or_else(|e| e.into_inner());
+        first_inits.iter().find(|&&(n, _)| n == name).map(|(_, first)| first.clone())
+    }
+
+    /// Whether a static in a [`Dump`](struct.Dump.html) has been initialized.
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub enum InitState {
+        /// The static has not been initialized yet.
+        Uninitialized,
+        /// A thread is running the initializer of the static.
+        Initializing,
+        /// The static has been initialized.
+        Initialized,
+    }
+
+    /// The state of one static in a [`Dump`](struct.Dump.html).
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    pub struct StaticReport {
+        /// The path of the static, including the module it is declared in.
+        pub name: &'static str,
+        /// The type of the static, as written in its declaration.
+        pub type_name: &'static str,
+        /// Whether the static has been initialized.
+        pub state: InitState,
+        /// Whether this is a value leaked with `lazy_static::leak`.
+        pub leaked: bool,
+        /// How long the initializer took, if it has run to completion.
+        pub duration: Option<Duration>,
+        /// The name of the thread that ran the initializer, or its id if it
+        /// has no name.
+        pub thread: Option<String>,
+        /// How many times the static was dereferenced, with the
+        /// `access-counts` feature.
+        pub accesses: Option<usize>,
+    }
+
+    /// A report of every registered static, returned by
+    /// [`lazy_static::dump()`](fn.dump.html).
+    ///
+    /// It is displayed as one line per static, to be written to a log.
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    pub struct Dump {
+        /// The statics, sorted by name.
+        pub statics: Vec<StaticReport>,
+    }
+
+    impl Dump {
+        /// Formats the report in the text format of Prometheus.
+        ///
+        /// Every static is exported with its path and type as labels:
+        ///
+        /// - `lazy_static_initialized` is `1` once the static has been
+        ///   initialized, and `0` before,
+        /// - `lazy_static_init_duration_seconds` is how long its initializer
+        ///   took, if that is known,
+        /// - `lazy_static_accesses_total` is how many times it was
+        ///   dereferenced, with the `access-counts` feature.
+        ///
+        /// Leaked values are left out, as they have no name to tell them
+        /// apart.
+        ///
+        /// ```rust
+        /// #[macro_use]
+        /// extern crate lazy_static;
+        ///
+        /// lazy_static! {
+        ///     static ref PORTS: Vec<u16> = vec![80, 443];
+        /// }
+        ///
+        /// fn main() {
+        ///     let _ = PORTS.len();
+        ///     let metrics = lazy_static::dump().to_prometheus();
+        ///     assert!(metrics.contains("::PORTS\",type=\"Vec<u16>\"} 1\n"));
+        /// }
+        /// ```
+        pub fn to_prometheus(&self) -> String {
+            let mut out = String::new();
+            let statics: Vec<&StaticReport> = self.statics.iter().filter(|s| !s.leaked).collect();
+            metric(&mut out, "initialized", "gauge", "Whether the lazy static has been initialized.");
+            for s in &statics {
+                let initialized = (s.state == InitState::Initialized) as u8;
+                sample(&mut out, "initialized", s, initialized);
+            }
+            metric(&mut out, "init_duration_seconds", "gauge", "How long the initializer of the lazy static took.");
+            for s in &statics {
+                if let Some(duration) = s.duration {
+                    sample(&mut out, "init_duration_seconds", s, duration.as_secs_f64());
+                }
+            }
+            if statics.iter().any(|s| s.accesses.is_some()) {
+                metric(&mut out, "accesses_total", "counter", "How many times the lazy static was dereferenced.");
+                for s in &statics {
+                    if let Some(accesses) = s.accesses {
+                        sample(&mut out, "accesses_total", s, accesses);
+                    }
+                }
+            }
+            out
+        }
+    }
+
+    fn metric(out: &mut String, name: &str, kind: &str, help: &str) {
+        let _ = writeln!(out, "# HELP lazy_static_{} {}", name, help);
+        let _ = writeln!(out, "# TYPE lazy_static_{} {}", name, kind);
+    }
+
+    fn sample<V: fmt::Display>(out: &mut String, name: &str, s: &StaticReport, value: V) {
+        let _ = writeln!(out, "lazy_static_{}{{name=\"{}\",type=\"{}\"}} {}",
+                         name, Label(s.name), Label(s.type_name), value);
+    }
+
+    // escapes a label value the way the text format requires
+    struct Label<'a>(&'a str);
+
+    impl<'a> fmt::Display for Label<'a> {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            for c in self.0.chars() {
+                match c {
+                    '\\' => f.write_str("\\\\")?,
+                    '"' => f.write_str("\\\"")?,
+                    '\n' => f.write_str("\\n")?,
+                    c => f.write_char(c)?,
+                }
+            }
+            Ok(())
+        }
+    }
+
+    impl fmt::Display for Dump {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            let initialized = self.statics.iter().filter(|s| s.state == InitState::Initialized).count();
+            writeln!(f, "lazy statics: {} registered, {} initialized", self.statics.len(), initialized)?;
+            for s in &self.statics {
+                write!(f, "  {}: {}", s.name, s.type_name)?;
+                match s.state {
+                    InitState::Uninitialized => write!(f, ", uninitialized")?,
+                    InitState::Initializing => write!(f, ", initializing")?,
+                    InitState::Initialized => write!(f, ", initialized")?,
+                }
+                if let Some(duration) = s.duration {
+                    write!(f, " in {:?}", duration)?;
+                }
+                if let Some(ref thread) = s.thread {
+                    write!(f, " on thread `{}`", thread)?;
+                }
+                if s.leaked {
+                    write!(f, " (leaked)")?;
+                }
+                writeln!(f)?;
+            }
+            Ok(())
+        }
+    }
+
+    /// Returns a report of every lazy static declared in the program, with
+    /// its state, and how long its initializer took on which thread.
+    ///
+    /// The duration and thread are only known for initializers that ran to
+    /// completion after the program started.
+    pub fn dump() -> Dump {
+        let inits = INITS.lock().unwrap_or_else(|e| e.into_inner()).clone();
+        let mut statics: Vec<StaticReport> = registry().map(|info| {
+            let init = inits.iter().find(|&&(name, _, _)| name == info.name());
+            let state = if info.is_initialized() {
+                InitState::Initialized
+            } else if ::init::is_running(info.name()) {
+                InitState::Initializing
+            } else {
+                InitState::Uninitialized
+            };
+            StaticReport {
+                name: info.name(),
+                type_name: info.type_name(),
+                state,
+                leaked: info.is_leaked(),
+                duration: init.map(|&(_, duration, _)| duration),
+                thread: init.map(|init| init.2.clone()),
+                accesses: info.accesses(),
+            }
+        }).collect();
+        statics.sort_by_key(|s| s.name);
+        Dump { statics }
+    }
+
+    pub fn __print_dump() {
+        std::eprint!("{}", dump());
+    }
+}
@@ -0,0 +1,1727 @@
+// Copyright 2016 lazy-static.rs Developers
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+/*!
+Thread-safe lazily initialized values that can be used without the macro.
+
+The types in this module are built on the same synchronization as the
+statics declared with `lazy_static!`, but are plain types that can be
+named, stored in `static`s directly, or used as regular values.
+
+[`Lazy`](struct.Lazy.html) is initialized by a function given when it is
+created, while [`OnceCell`](struct.OnceCell.html) can be set to a value
+computed elsewhere, for example from the command line arguments.
+[`LazyArray`](struct.LazyArray.html) initializes each of its elements
+separately, and [`LazyMap`](struct.LazyMap.html) holds a separate lazily initialized value
+for every key, and [`LazyTypeMap`](struct.LazyTypeMap.html) one for every
+type. [`RaceLazy`](struct.RaceLazy.html) never blocks, but may run its
+initialization function on several threads at the same time, and
+[`LazyArc`](struct.LazyArc.html) hands out `Arc` clones of its value.
+[`ReleasableLazy`](struct.ReleasableLazy.html) drops its value when the last
+of those is dropped, and initializes it again on the next access.
+[`ExpiringLazy`](struct.ExpiringLazy.html) initializes its value again once
+it is older than a given duration.
+[`ReloadableLazy`](struct.ReloadableLazy.html) can have its value replaced
+at runtime, also when the process receives `SIGHUP`, while readers keep the
+snapshot they got.
+[`EpochReloadableLazy`](struct.EpochReloadableLazy.html) does the same
+without updating a shared reference count on every read.
+[`CachePadded`](struct.CachePadded.html) keeps any of them from sharing
+a cache line with their neighbors.
+
+```rust
+extern crate lazy_static;
+
+use std::collections::HashMap;
+use lazy_static::sync::Lazy;
+
+static HASHMAP: Lazy<HashMap<u32, &'static str>> = Lazy::new(|| {
+    let mut m = HashMap::new();
+    m.insert(0, "foo");
+    m.insert(1, "bar");
+    m
+});
+
+fn main() {
+    assert_eq!(HASHMAP.get(&1), Some(&"bar"));
+}
+```
+*/
+
+use core::cell::Cell;
+use core::ops::{Deref, DerefMut, Index};
+#[cfg(not(feature="spin_no_std"))]
+use core::time::Duration;
+use lazy;
+
+/// A value that is initialized on its first access.
+///
+/// The initialization function is run exactly once, even if several threads
+/// access the value concurrently, and the initialized value is reached
+/// through `Deref`.
+pub struct Lazy<T, F = fn() -> T> {
+    cell: lazy::Lazy<T>,
+    init: Cell<Option<F>>,
+    // set once `take` has taken out the value that `init` returned
+    taken: Cell<bool>,
+}
+
+impl<T, F> Lazy<T, F> {
+    /// Creates a new lazy value that is initialized by calling `init`.
+    #[cfg(not(loom))]
+    pub const fn new(init: F) -> Self {
+        Lazy {
+            cell: lazy::Lazy::new(),
+            init: Cell::new(Some(init)),
+            taken: Cell::new(false),
+        }
+    }
+
+    /// Creates a new lazy value that is initialized by calling `init`.
+    #[cfg(loom)]
+    pub fn new(init: F) -> Self {
+        Lazy {
+            cell: lazy::Lazy::new(),
+            init: Cell::new(Some(init)),
+            taken: Cell::new(false),
+        }
+    }
+
+    /// Returns a reference to the value if it has already been initialized.
+    ///
+    /// This never blocks, and returns `None` while another thread is running
+    /// the initialization function.
+    pub fn try_get(this: &Self) -> Option<&T> {
+        this.cell.try_get()
+    }
+
+    /// Returns whether the value has already been initialized, without
+    /// initializing it.
+    ///
+    /// This is a single atomic load, so it can be used for logging, metrics
+    /// or deciding whether there is anything to tear down.
+    pub fn is_initialized(this: &Self) -> bool {
+        this.cell.is_initialized()
+    }
+
+    /// Returns a mutable reference to the value if it has already been
+    /// initialized.
+    pub fn get_mut(this: &mut Self) -> Option<&mut T> {
+        this.cell.get_mut()
+    }
+
+    /// Consumes the lazy value, returning the value if it has been
+    /// initialized.
+    pub fn into_inner(this: Self) -> Option<T> {
+        this.cell.into_inner()
+    }
+
+    /// Takes the value out if it has been initialized.
+    ///
+    /// The initialization function has already been used up at that
+    /// point, so dereferencing `this` afterwards panics with a message
+    /// saying that the value was taken.
+    pub fn take(this: &mut Self) -> Option<T> {
+        let value = this.cell.take();
+        if value.is_some() {
+            this.taken.set(true);
+        }
+        value
+    }
+}
+
+impl<T, F: FnOnce() -> T> Lazy<T, F> {
+    /// Forces the initialization of the value and returns a reference to it.
+    ///
+    /// This is equivalent to dereferencing `this`, but more explicit.
+    pub fn force(this: &Self) -> &T {
+        this.cell.get(|| match this.init.take() {
+            Some(init) => init(),
+            None if this.taken.get() => panic!("Lazy instance has had its value taken"),
+            None => panic!("Lazy instance has previously been poisoned"),
+        })
+    }
+
+    /// Like `force`, but waits at most `timeout` for another thread that is
+    /// currently initializing the value.
+    ///
+    /// This is not available with the `spin_no_std` feature.
+    #[cfg(not(feature="spin_no_std"))]
+    pub fn get_timeout(this: &Self, timeout: Duration) -> Result<&T, ::Timeout> {
+        lazy::wait_timeout(timeout, || this.cell.try_get(), || this.cell.is_running(), || Lazy::force(this))
+    }
+
+    /// Drops the value, so that the next access initializes it again by
+    /// calling `init`.
+    ///
+    /// This is only available with the `test-support` feature.
+    ///
+    /// # Safety
+    ///
+    /// No references to the value may be alive, and no other thread may
+    /// access `this` while it is reset.
+    #[cfg(feature="test-support")]
+    pub unsafe fn reset(this: &Self, init: F) {
+        this.cell.reset();
+        this.init.set(Some(init));
+        this.taken.set(false);
+    }
+}
+
+impl<T, F: FnOnce() -> T> Deref for Lazy<T, F> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        Lazy::force(self)
+    }
+}
+
+#[cfg(feature="serde")]
+impl<T: ::__serde::Serialize, F: FnOnce() -> T> ::__serde::Serialize for Lazy<T, F> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where S: ::__serde::Serializer
+    {
+        (**self).serialize(serializer)
+    }
+}
+
+unsafe impl<T: Send + Sync, F: Send> Sync for Lazy<T, F> {}
+unsafe impl<T: Send, F: Send> Send for Lazy<T, F> {}
+
+/// A cell that can be written to only once.
+///
+/// Unlike [`Lazy`](struct.Lazy.html), the value is not produced by a function
+/// fixed at creation, but is either set explicitly or computed by the first
+/// call to `get_or_init`.
+///
+/// ```rust
+/// extern crate lazy_static;
+///
+/// use lazy_static::sync::OnceCell;
+///
+/// static NAME: OnceCell<String> = OnceCell::new();
+///
+/// fn main() {
+///     assert!(NAME.get().is_none());
+///     NAME.set(std::env::args().next().unwrap_or_default()).unwrap();
+///     assert!(NAME.get().is_some());
+///     assert!(NAME.set(String::new()).is_err());
+/// }
+/// ```
+pub struct OnceCell<T> {
+    cell: lazy::Lazy<T>,
+}
+
+impl<T> OnceCell<T> {
+    /// Creates a new empty cell.
+    #[cfg(not(loom))]
+    pub const fn new() -> Self {
+        OnceCell {
+            cell: lazy::Lazy::new(),
+        }
+    }
+
+    /// Creates a new empty cell.
+    #[cfg(loom)]
+    pub fn new() -> Self {
+        OnceCell {
+            cell: lazy::Lazy::new(),
+        }
+    }
+
+    /// Returns a reference to the value, or `None` if the cell is empty.
+    pub fn get(&self) -> Option<&T> {
+        self.cell.try_get()
+    }
+
+    /// Returns a mutable reference to the value, or `None` if the cell is
+    /// empty.
+    pub fn get_mut(&mut self) -> Option<&mut T> {
+        self.cell.get_mut()
+    }
+
+    /// Consumes the cell, returning its value if it has one.
+    pub fn into_inner(self) -> Option<T> {
+        self.cell.into_inner()
+    }
+
+    /// Takes the value out of the cell, leaving it empty.
+    pub fn take(&mut self) -> Option<T> {
+        self.cell.take()
+    }
+
+    /// Sets the value of the cell.
+    ///
+    /// Returns `Err(value)` if the cell already had a value.
+    pub fn set(&self, value: T) -> Result<(), T> {
+        let mut value = Some(value);
+        self.cell.get(|| value.take().unwrap());
+        match value {
+            None => Ok(()),
+            Some(value) => Err(value),
+        }
+    }
+
+    /// Returns a reference to the value, initializing it with `f` if the
+    /// cell is empty.
+    ///
+    /// If several threads call this concurrently, only one of them runs its
+    /// function, and the others wait for it to complete.
+    pub fn get_or_init<F>(&self, f: F) -> &T
+        where F: FnOnce() -> T
+    {
+        self.cell.get(f)
+    }
+}
+
+impl<T> Default for OnceCell<T> {
+    fn default() -> Self {
+        OnceCell::new()
+    }
+}
+
+unsafe impl<T: Send + Sync> Sync for OnceCell<T> {}
+unsafe impl<T: Send> Send for OnceCell<T> {}
+
+/// A fixed-size array whose elements are initialized separately, on their
+/// first access.
+///
+/// Each element is computed by calling the initialization function with its
+/// index, at most once, so that lookup tables of which only a few entries
+/// are used do not have to be computed as a whole.
+///
+/// ```rust
+/// extern crate lazy_static;
+///
+/// use lazy_static::sync::LazyArray;
+///
+/// static FACTORIALS: LazyArray<u64, 21> = LazyArray::new(|n| (1..=n as u64).product());
+///
+/// fn main() {
+///     assert_eq!(FACTORIALS[5], 120);
+///     assert_eq!(LazyArray::try_get(&FACTORIALS, 5), Some(&120));
+///     assert_eq!(LazyArray::try_get(&FACTORIALS, 6), None);
+/// }
+/// ```
+pub struct LazyArray<T, const N: usize, F = fn(usize) -> T> {
+    cells: [lazy::Lazy<T>; N],
+    init: F,
+}
+
+impl<T, const N: usize, F> LazyArray<T, N, F> {
+    /// Creates a new array whose elements are initialized by calling `init`
+    /// with their index.
+    #[cfg(not(loom))]
+    pub const fn new(init: F) -> Self {
+        LazyArray {
+            cells: [const { lazy::Lazy::new() }; N],
+            init,
+        }
+    }
+
+    /// Creates a new array whose elements are initialized by calling `init`
+    /// with their index.
+    #[cfg(loom)]
+    pub fn new(init: F) -> Self {
+        LazyArray {
+            cells: [(); N].map(|()| lazy::Lazy::new()),
+            init,
+        }
+    }
+
+    /// Returns a reference to the element at `index` if it has already been
+    /// initialized.
+    ///
+    /// This never blocks, and returns `None` if `index` is out of bounds.
+    pub fn try_get(this: &Self, index: usize) -> Option<&T> {
+        this.cells.get(index).and_then(|cell| cell.try_get())
+    }
+
+    /// Returns a mutable reference to the element at `index` if it has
+    /// already been initialized.
+    pub fn get_mut(this: &mut Self, index: usize) -> Option<&mut T> {
+        this.cells.get_mut(index).and_then(|cell| cell.get_mut())
+    }
+}
+
+impl<T, const N: usize, F: Fn(usize) -> T> LazyArray<T, N, F> {
+    /// Returns a reference to the element at `index`, initializing it if
+    /// necessary, or `None` if `index` is out of bounds.
+    pub fn get(this: &Self, index: usize) -> Option<&T> {
+        this.cells.get(index).map(|cell| cell.get(|| (this.init)(index)))
+    }
+}
+
+impl<T, const N: usize, F: Fn(usize) -> T> Index<usize> for LazyArray<T, N, F> {
+    type Output = T;
+
+    fn index(&self, index: usize) -> &T {
+        match LazyArray::get(self, index) {
+            Some(value) => value,
+            None => panic!("index out of bounds: the len is {} but the index is {}", N, index),
+        }
+    }
+}
+
+unsafe impl<T: Send + Sync, const N: usize, F: Sync> Sync for LazyArray<T, N, F> {}
+
+/// A value aligned to, and padded to the size of, a cache line.
+///
+/// Values that are read from many threads, like a lazy value on a hot path,
+/// get slower when the cache line holding them is shared with values that are
+/// written to often, as every write invalidates it for all readers. Wrapping
+/// them in `CachePadded` gives them a cache line of their own. Lazy statics
+/// declared with `lazy_static!` can be marked with `#[lazy(cache_padded)]`
+/// instead.
+///
+/// ```rust
+/// extern crate lazy_static;
+///
+/// use std::mem;
+/// use lazy_static::sync::{CachePadded, Lazy};
+///
+/// static CONFIG: CachePadded<Lazy<Vec<u32>>> = CachePadded::new(Lazy::new(|| vec![1, 2, 3]));
+///
+/// fn main() {
+///     assert_eq!(CONFIG.len(), 3);
+///     assert_eq!(&*CONFIG as *const _ as usize % mem::align_of::<CachePadded<()>>(), 0);
+/// }
+/// ```
+// most 64-bit targets fetch cache lines in pairs, so those pad to 128 bytes
+#[cfg_attr(any(target_arch = "x86_64", target_arch = "aarch64", target_arch = "powerpc64"), repr(align(128)))]
+#[cfg_attr(not(any(target_arch = "x86_64", target_arch = "aarch64", target_arch = "powerpc64")), repr(align(64)))]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct CachePadded<T>(T);
+
+impl<T> CachePadded<T> {
+    /// Wraps `value` in its own cache line.
+    pub const fn new(value: T) -> Self {
+        CachePadded(value)
+    }
+
+    /// Returns the wrapped value.
+    pub fn into_inner(this: Self) -> T {
+        this.0
+    }
+}
+
+impl<T> Deref for CachePadded<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for CachePadded<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+#[cfg(not(feature="spin_no_std"))]
+pub use self::map::{LazyMap, LazyTypeMap};
+
+#[cfg(not(feature="spin_no_std"))]
+mod map {
+    extern crate std;
+
+    use self::std::prelude::v1::*;
+    use self::std::any::{Any, TypeId};
+    use self::std::borrow::Borrow;
+    use self::std::collections::HashMap;
+    use self::std::hash::Hash;
+    use self::std::sync::Mutex;
+    use core::marker::PhantomData;
+    use super::OnceCell;
+
+    /// A map that lazily initializes a value for each key.
+    ///
+    /// The value of a key is computed by the first call to `get_or_init` with
+    /// it, and stays in the map for as long as the map lives. Like for
+    /// [`OnceCell`](struct.OnceCell.html), the function runs at most once per
+    /// key, and threads asking for the same key wait for it, while values of
+    /// different keys can be initialized at the same time.
+    ///
+    /// This is not available with the `spin_no_std` feature.
+    ///
+    /// ```rust
+    /// extern crate lazy_static;
+    ///
+    /// use lazy_static::sync::LazyMap;
+    ///
+    /// static SQUARES: LazyMap<u64, u64> = LazyMap::new();
+    ///
+    /// fn main() {
+    ///     assert_eq!(*SQUARES.get_or_init(12, || 12 * 12), 144);
+    ///     assert_eq!(*SQUARES.get_or_init(12, || unreachable!()), 144);
+    ///     assert_eq!(SQUARES.get(&3), None);
+    /// }
+    /// ```
+    pub struct LazyMap<K, V> {
+        // the cells are boxed so that references to the values stay valid
+        // when the map grows
+        map: Mutex<Option<HashMap<K, Box<OnceCell<V>>>>>,
+        // handing out `&V` to several threads requires `V: Sync`
+        _values: PhantomData<OnceCell<V>>,
+    }
+
+    impl<K, V> LazyMap<K, V> {
+        /// Creates a new empty map.
+        pub const fn new() -> Self {
+            LazyMap {
+                map: Mutex::new(None),
+                _values: PhantomData,
+            }
+        }
+    }
+
+    impl<K: Eq + Hash, V> LazyMap<K, V> {
+        /// Returns a reference to the value of `key`, or `None` if it has not
+        /// been initialized yet.
+        pub fn get<Q>(&self, key: &Q) -> Option<&V>
+            where K: Borrow<Q>, Q: Eq + Hash + ?Sized
+        {
+            let map = self.map.lock().unwrap_or_else(|e| e.into_inner());
+            let cell: *const OnceCell<V> = &**map.as_ref()?.get(key)?;
+            drop(map);
+            unsafe { (*cell).get() }
+        }
+
+        /// Returns a reference to the value of `key`, initializing it with `f`
+        /// if it has not been initialized yet.
+        pub fn get_or_init<F>(&self, key: K, f: F) -> &V
+            where F: FnOnce() -> V
+        {
+            let mut map = self.map.lock().unwrap_or_else(|e| e.into_inner());
+            let cell: *const OnceCell<V> = &**map
+                .get_or_insert_with(HashMap::new)
+                .entry(key)
+                .or_insert_with(|| Box::new(OnceCell::new()));
+            drop(map);
+            // the cell is never removed from the map, so it lives as long as
+            // `self`; it is initialized without holding the lock
+            unsafe { (*cell).get_or_init(f) }
+        }
+    }
+
+    impl<K, V> Default for LazyMap<K, V> {
+        fn default() -> Self {
+            LazyMap::new()
+        }
+    }
+
+    /// A map that lazily initializes one value for each type.
+    ///
+    /// This lets libraries and frameworks hand out a single instance of a
+    /// type, without declaring a static for every type up front. There is a
+    /// map shared by the whole program, returned by
+    /// [`global`](#method.global), and other maps can be declared as statics.
+    ///
+    /// This is not available with the `spin_no_std` feature.
+    ///
+    /// ```rust
+    /// extern crate lazy_static;
+    ///
+    /// use lazy_static::sync::LazyTypeMap;
+    ///
+    /// #[derive(Debug, PartialEq)]
+    /// struct Settings { verbose: bool }
+    ///
+    /// fn main() {
+    ///     let settings: &'static Settings = LazyTypeMap::global().get_or_init(|| Settings { verbose: true });
+    ///     assert!(settings.verbose);
+    ///     assert_eq!(LazyTypeMap::global().get::<Settings>(), Some(settings));
+    ///     assert_eq!(LazyTypeMap::global().get::<String>(), None);
+    /// }
+    /// ```
+    pub struct LazyTypeMap {
+        map: LazyMap<TypeId, Box<dyn Any + Send + Sync>>,
+    }
+
+    impl LazyTypeMap {
+        /// Creates a new empty map.
+        pub const fn new() -> Self {
+            LazyTypeMap {
+                map: LazyMap::new(),
+            }
+        }
+
+        /// Returns the map shared by the whole program.
+        pub fn global() -> &'static LazyTypeMap {
+            static GLOBAL: LazyTypeMap = LazyTypeMap::new();
+            &GLOBAL
+        }
+
+        /// Returns a reference to the value of type `T`, or `None` if it has
+        /// not been initialized yet.
+        pub fn get<T: Any + Send + Sync>(&self) -> Option<&T> {
+            self.map.get(&TypeId::of::<T>()).and_then(|value| value.downcast_ref())
+        }
+
+        /// Returns a reference to the value of type `T`, initializing it with
+        /// `f` if it has not been initialized yet.
+        pub fn get_or_init<T: Any + Send + Sync, F>(&self, f: F) -> &T
+            where F: FnOnce() -> T
+        {
+            self.map
+                .get_or_init(TypeId::of::<T>(), || Box::new(f()))
+                .downcast_ref()
+                .expect("value of a different type stored for a TypeId")
+        }
+    }
+
+    impl Default for LazyTypeMap {
+        fn default() -> Self {
+            LazyTypeMap::new()
+        }
+    }
+}
+
+#[cfg(not(feature="spin_no_std"))]
+pub use self::race::{Global, RaceLazy};
+
+#[cfg(not(feature="spin_no_std"))]
+mod race {
+    extern crate std;
+
+    use self::std::alloc::{self, GlobalAlloc, Layout};
+    use core::marker::PhantomData;
+    use core::mem;
+    use core::ops::Deref;
+    use core::ptr::{self, NonNull};
+    use core::sync::atomic::{AtomicPtr, Ordering};
+
+    /// The allocator registered with `#[global_allocator]`, or the default
+    /// allocator of the standard library if there is none.
+    ///
+    /// This is the allocator used by [`RaceLazy`](struct.RaceLazy.html)
+    /// unless another one is given with `RaceLazy::new_in`.
+    #[derive(Copy, Clone, Debug, Default)]
+    pub struct Global;
+
+    unsafe impl GlobalAlloc for Global {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            alloc::alloc(layout)
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+            alloc::dealloc(ptr, layout)
+        }
+    }
+
+    /// A lazy value that never blocks, at the cost of possibly running its
+    /// initialization function more than once.
+    ///
+    /// Threads that find the value uninitialized all call `init`, and the
+    /// first of them to publish its result wins; the values computed by the
+    /// others are dropped. Every thread sees the same value afterwards. This
+    /// suits initializers that are cheap and idempotent, on paths that must
+    /// not wait for another thread. The value is allocated on the heap, so
+    /// that publishing it is a single compare-and-swap.
+    ///
+    /// The allocation goes through the global allocator, or through the
+    /// allocator `A` given to [`new_in`](#method.new_in), which lets arena
+    /// or embedded applications keep the value out of the general-purpose
+    /// heap.
+    ///
+    /// This is not available with the `spin_no_std` feature.
+    ///
+    /// ```rust
+    /// extern crate lazy_static;
+    ///
+    /// use lazy_static::sync::RaceLazy;
+    ///
+    /// static PAGE_SIZE: RaceLazy<usize> = RaceLazy::new(|| 4096);
+    ///
+    /// fn main() {
+    ///     assert_eq!(RaceLazy::try_get(&PAGE_SIZE), None);
+    ///     assert_eq!(*PAGE_SIZE, 4096);
+    ///     assert_eq!(RaceLazy::try_get(&PAGE_SIZE), Some(&4096));
+    /// }
+    /// ```
+    pub struct RaceLazy<T, F = fn() -> T, A: GlobalAlloc = Global> {
+        value: AtomicPtr<T>,
+        init: F,
+        alloc: A,
+        _owns: PhantomData<T>,
+    }
+
+    impl<T, F> RaceLazy<T, F> {
+        /// Creates a new lazy value that is initialized by calling `init`.
+        pub const fn new(init: F) -> Self {
+            RaceLazy::new_in(init, Global)
+        }
+    }
+
+    impl<T, F, A: GlobalAlloc> RaceLazy<T, F, A> {
+        /// Creates a new lazy value that is initialized by calling `init`,
+        /// and whose value is allocated with `alloc`.
+        ///
+        /// ```rust
+        /// extern crate lazy_static;
+        ///
+        /// use std::alloc::System;
+        /// use lazy_static::sync::RaceLazy;
+        ///
+        /// static NAME: RaceLazy<String, fn() -> String, System> =
+        ///     RaceLazy::new_in(|| "system".to_string(), System);
+        ///
+        /// fn main() {
+        ///     assert_eq!(*NAME, "system");
+        /// }
+        /// ```
+        pub const fn new_in(init: F, alloc: A) -> Self {
+            RaceLazy {
+                value: AtomicPtr::new(ptr::null_mut()),
+                init,
+                alloc,
+                _owns: PhantomData,
+            }
+        }
+
+        /// Returns a reference to the value if it has already been
+        /// initialized.
+        pub fn try_get(this: &Self) -> Option<&T> {
+            // the value is only freed by `drop` and `into_inner`, which own
+            // the lazy value
+            unsafe { this.value.load(Ordering::Acquire).as_ref() }
+        }
+
+        /// Returns whether the value has already been initialized, without
+        /// initializing it.
+        pub fn is_initialized(this: &Self) -> bool {
+            !this.value.load(Ordering::Acquire).is_null()
+        }
+
+        /// Returns a mutable reference to the value if it has already been
+        /// initialized.
+        pub fn get_mut(this: &mut Self) -> Option<&mut T> {
+            unsafe { this.value.get_mut().as_mut() }
+        }
+
+        /// Consumes the lazy value, returning the value if it has been
+        /// initialized.
+        pub fn into_inner(mut this: Self) -> Option<T> {
+            let value = mem::replace(this.value.get_mut(), ptr::null_mut());
+            if value.is_null() {
+                None
+            } else {
+                Some(unsafe { this.free(value) })
+            }
+        }
+
+        fn allocate(&self, value: T) -> *mut T {
+            let layout = Layout::new::<T>();
+            if layout.size() == 0 {
+                return NonNull::dangling().as_ptr();
+            }
+            let ptr = unsafe { self.alloc.alloc(layout) } as *mut T;
+            if ptr.is_null() {
+                alloc::handle_alloc_error(layout);
+            }
+            unsafe { ptr.write(value) };
+            ptr
+        }
+
+        // `ptr` has to come from `allocate` on the same lazy value, and is
+        // not used anymore afterwards
+        unsafe fn free(&self, ptr: *mut T) -> T {
+            let value = ptr.read();
+            let layout = Layout::new::<T>();
+            if layout.size() != 0 {
+                self.alloc.dealloc(ptr as *mut u8, layout);
+            }
+            value
+        }
+    }
+
+    impl<T, F: Fn() -> T, A: GlobalAlloc> RaceLazy<T, F, A> {
+        /// Forces the initialization of the value and returns a reference to
+        /// it, calling `init` even if another thread is calling it as well.
+        pub fn force(this: &Self) -> &T {
+            if let Some(value) = RaceLazy::try_get(this) {
+                return value;
+            }
+            let value = this.allocate((this.init)());
+            match this.value.compare_exchange(ptr::null_mut(), value, Ordering::AcqRel, Ordering::Acquire) {
+                Ok(_) => unsafe { &*value },
+                Err(winner) => {
+                    // another thread published its value first
+                    drop(unsafe { this.free(value) });
+                    unsafe { &*winner }
+                }
+            }
+        }
+    }
+
+    impl<T, F: Fn() -> T, A: GlobalAlloc> Deref for RaceLazy<T, F, A> {
+        type Target = T;
+
+        fn deref(&self) -> &T {
+            RaceLazy::force(self)
+        }
+    }
+
+    impl<T, F, A: GlobalAlloc> Drop for RaceLazy<T, F, A> {
+        fn drop(&mut self) {
+            let value = *self.value.get_mut();
+            if !value.is_null() {
+                drop(unsafe { self.free(value) });
+            }
+        }
+    }
+
+    unsafe impl<T: Send + Sync, F: Sync, A: GlobalAlloc + Sync> Sync for RaceLazy<T, F, A> {}
+    unsafe impl<T: Send, F: Send, A: GlobalAlloc + Send> Send for RaceLazy<T, F, A> {}
+}
+
+#[cfg(not(feature="spin_no_std"))]
+pub use self::arc::{LazyArc, ReleasableLazy};
+
+#[cfg(not(feature="spin_no_std"))]
+mod arc {
+    extern crate std;
+
+    use self::std::sync::{Arc, Mutex, Weak};
+    use core::cell::Cell;
+    use core::ops::Deref;
+    use lazy;
+
+    /// A lazy value that hands out `Arc` clones of itself.
+    ///
+    /// Like [`Lazy`](struct.Lazy.html), the value is initialized once, on its
+    /// first access, but it is stored in an `Arc`, so that owned handles to
+    /// it can be passed to spawned tasks or FFI callbacks that require them,
+    /// while it can still be dereferenced like a `Lazy`.
+    ///
+    /// This is not available with the `spin_no_std` feature.
+    ///
+    /// ```rust
+    /// extern crate lazy_static;
+    ///
+    /// use std::sync::Arc;
+    /// use std::thread;
+    /// use lazy_static::sync::LazyArc;
+    ///
+    /// static NAMES: LazyArc<Vec<String>> = LazyArc::new(|| vec!["a".to_string(), "b".to_string()]);
+    ///
+    /// fn main() {
+    ///     let names: Arc<Vec<String>> = LazyArc::get(&NAMES);
+    ///     let len = thread::spawn(move || names.len()).join().unwrap();
+    ///     assert_eq!(len, NAMES.len());
+    /// }
+    /// ```
+    pub struct LazyArc<T, F = fn() -> T> {
+        cell: lazy::Lazy<Arc<T>>,
+        init: Cell<Option<F>>,
+    }
+
+    impl<T, F> LazyArc<T, F> {
+        /// Creates a new lazy value that is initialized by calling `init`.
+        #[cfg(not(loom))]
+        pub const fn new(init: F) -> Self {
+            LazyArc {
+                cell: lazy::Lazy::new(),
+                init: Cell::new(Some(init)),
+            }
+        }
+
+        /// Creates a new lazy value that is initialized by calling `init`.
+        #[cfg(loom)]
+        pub fn new(init: F) -> Self {
+            LazyArc {
+                cell: lazy::Lazy::new(),
+                init: Cell::new(Some(init)),
+            }
+        }
+
+        /// Returns a clone of the `Arc` holding the value if it has already
+        /// been initialized.
+        ///
+        /// This never blocks, and returns `None` while another thread is
+        /// running the initialization function.
+        pub fn try_get(this: &Self) -> Option<Arc<T>> {
+            this.cell.try_get().cloned()
+        }
+
+        /// Returns whether the value has already been initialized, without
+        /// initializing it.
+        pub fn is_initialized(this: &Self) -> bool {
+            this.cell.is_initialized()
+        }
+    }
+
+    impl<T, F: FnOnce() -> T> LazyArc<T, F> {
+        /// Returns a clone of the `Arc` holding the value, initializing it
+        /// if necessary.
+        pub fn get(this: &Self) -> Arc<T> {
+            LazyArc::force(this).clone()
+        }
+
+        fn force(this: &Self) -> &Arc<T> {
+            this.cell.get(|| match this.init.take() {
+                Some(init) => Arc::new(init()),
+                None => panic!("Lazy instance has previously been poisoned"),
+            })
+        }
+    }
+
+    impl<T, F: FnOnce() -> T> Deref for LazyArc<T, F> {
+        type Target = T;
+
+        fn deref(&self) -> &T {
+            LazyArc::force(self)
+        }
+    }
+
+    // the value is shared with the owners of the handed out `Arc`s, which
+    // may be on any thread
+    unsafe impl<T: Send + Sync, F: Send> Sync for LazyArc<T, F> {}
+    unsafe impl<T: Send + Sync, F: Send> Send for LazyArc<T, F> {}
+
+    /// A lazy value that is dropped again once nothing uses it.
+    ///
+    /// Accessing the value returns an `Arc` that acts as a guard: when the
+    /// last guard is dropped, so is the value, and the next access runs the
+    /// initialization function again. This lets long-running programs
+    /// reclaim the memory of large caches while they are idle.
+    ///
+    /// The initialization function runs under a lock, so that there is never
+    /// more than one value at a time.
+    ///
+    /// This is not available with the `spin_no_std` feature.
+    ///
+    /// ```rust
+    /// extern crate lazy_static;
+    ///
+    /// use lazy_static::sync::ReleasableLazy;
+    ///
+    /// static CACHE: ReleasableLazy<Vec<u64>> = ReleasableLazy::new(|| (0..1000).collect());
+    ///
+    /// fn main() {
+    ///     let cache = CACHE.get();
+    ///     assert_eq!(cache[10], 10);
+    ///     assert!(CACHE.is_initialized());
+    ///     drop(cache);
+    ///     assert!(!CACHE.is_initialized());
+    /// }
+    /// ```
+    pub struct ReleasableLazy<T, F = fn() -> T> {
+        value: Mutex<Option<Weak<T>>>,
+        init: F,
+    }
+
+    impl<T, F> ReleasableLazy<T, F> {
+        /// Creates a new lazy value that is initialized by calling `init`.
+        pub const fn new(init: F) -> Self {
+            ReleasableLazy {
+                value: Mutex::new(None),
+                init,
+            }
+        }
+
+        /// Returns a guard for the value if it is currently alive.
+        pub fn try_get(&self) -> Option<Arc<T>> {
+            let value = self.value.lock().unwrap_or_else(|e| e.into_inner());
+            value.as_ref().and_then(Weak::upgrade)
+        }
+
+        /// Returns whether the value is currently alive, without
+        /// initializing it.
+        pub fn is_initialized(&self) -> bool {
+            self.try_get().is_some()
+        }
+    }
+
+    impl<T, F: Fn() -> T> ReleasableLazy<T, F> {
+        /// Returns a guard for the value, initializing it first if it has
+        /// not been initialized yet or has been released since.
+        pub fn get(&self) -> Arc<T> {
+            let mut value = self.value.lock().unwrap_or_else(|e| e.into_inner());
+            if let Some(arc) = value.as_ref().and_then(Weak::upgrade) {
+                return arc;
+            }
+            let arc = Arc::new((self.init)());
+            *value = Some(Arc::downgrade(&arc));
+            arc
+        }
+    }
+}
+
+#[cfg(not(feature="spin_no_std"))]
+pub use self::expiring::ExpiringLazy;
+
+#[cfg(not(feature="spin_no_std"))]
+mod expiring {
+    extern crate std;
+
+    use self::std::sync::{Arc, Mutex};
+    use self::std::time::Instant;
+    use core::time::Duration;
+
+    /// A lazy value that is initialized again once it is older than a given
+    /// duration.
+    ///
+    /// This is meant for values that must not be cached forever, like
+    /// authentication tokens or the results of DNS lookups. The value is
+    /// handed out in an `Arc`, so that a caller can keep using the value it
+    /// got while it is replaced. The initialization function runs under a
+    /// lock, so only one thread refreshes an expired value, while the others
+    /// wait for it.
+    ///
+    /// This is not available with the `spin_no_std` feature.
+    ///
+    /// ```rust
+    /// extern crate lazy_static;
+    ///
+    /// use std::time::Duration;
+    /// use lazy_static::sync::ExpiringLazy;
+    ///
+    /// static TOKEN: ExpiringLazy<String> = ExpiringLazy::new(Duration::from_secs(60), || "secret".to_string());
+    ///
+    /// fn main() {
+    ///     assert_eq!(*TOKEN.get(), "secret");
+    /// }
+    /// ```
+    pub struct ExpiringLazy<T, F = fn() -> T> {
+        value: Mutex<Option<(Instant, Arc<T>)>>,
+        ttl: Duration,
+        init: F,
+    }
+
+    impl<T, F> ExpiringLazy<T, F> {
+        /// Creates a new lazy value that is initialized by calling `init`,
+        /// and again whenever the value is older than `ttl`.
+        pub const fn new(ttl: Duration, init: F) -> Self {
+            ExpiringLazy {
+                value: Mutex::new(None),
+                ttl,
+                init,
+            }
+        }
+
+        /// Returns the value if it has been initialized and has not expired
+        /// yet.
+        pub fn try_get(&self) -> Option<Arc<T>> {
+            let value = self.value.lock().unwrap_or_else(|e| e.into_inner());
+            match *value {
+                Some((created, ref arc)) if created.elapsed() < self.ttl => Some(arc.clone()),
+                _ => None,
+            }
+        }
+
+        /// Drops the cached value, so that the next access initializes it
+        /// again.
+        pub fn invalidate(&self) {
+            let value = self.value.lock().unwrap_or_else(|e| e.into_inner()).take();
+            drop(value);
+        }
+    }
+
+    impl<T, F: Fn() -> T> ExpiringLazy<T, F> {
+        /// Returns the value, initializing it first if it has not been
+        /// initialized yet or has expired.
+        pub fn get(&self) -> Arc<T> {
+            let mut value = self.value.lock().unwrap_or_else(|e| e.into_inner());
+            if let Some((created, ref arc)) = *value {
+                if created.elapsed() < self.ttl {
+                    return arc.clone();
+                }
+            }
+            let arc = Arc::new((self.init)());
+            *value = Some((Instant::now(), arc.clone()));
+            arc
+        }
+    }
+}
+
+#[cfg(not(feature="spin_no_std"))]
+pub use self::reload::ReloadableLazy;
+
+#[cfg(not(feature="spin_no_std"))]
+mod reload {
+    extern crate std;
+
+    use self::std::boxed::Box;
+    use self::std::fs;
+    use self::std::panic::{self, AssertUnwindSafe};
+    use self::std::path::PathBuf;
+    use self::std::sync::{Arc, Mutex};
+    use self::std::thread;
+    use self::std::time::SystemTime;
+    use core::marker::PhantomData;
+    use core::ptr;
+    use core::sync::atomic::{AtomicPtr, AtomicUsize, Ordering};
+    use core::time::Duration;
+    use epoch;
+
+    /// A lazy value that can be replaced at runtime.
+    ///
+    /// This is meant for global configuration or feature flags that are
+    /// reloaded while the program runs. Readers get a snapshot of the value
+    /// in an `Arc`, which stays consistent while a writer replaces the value,
+    /// and the old value is dropped once the last reader drops its snapshot.
+    /// The current value is swapped atomically, so taking a snapshot never
+    /// locks or waits for a writer: a reader only marks its thread as
+    /// reading, like [`EpochReloadableLazy`](struct.EpochReloadableLazy.html)
+    /// does, while it clones the `Arc`. Writers, including the
+    /// initialization function, are serialized by a lock, so readers keep
+    /// getting the current value while a new one is computed.
+    ///
+    /// The `Arc` of a replaced value is only released once no reader can
+    /// still be cloning it, which may be after a later value is stored, so
+    /// a replaced value that no reader holds is not necessarily dropped
+    /// right away.
+    ///
+    /// Every stored value gets a new [`generation`](#method.generation), so
+    /// that long-lived workers can check with
+    /// [`has_changed`](#method.has_changed) whether the state they derived
+    /// from a snapshot is stale.
+    ///
+    /// This is not available with the `spin_no_std` feature.
+    ///
+    /// ```rust
+    /// extern crate lazy_static;
+    ///
+    /// use lazy_static::sync::ReloadableLazy;
+    ///
+    /// static LIMITS: ReloadableLazy<Vec<u32>> = ReloadableLazy::new(|| vec![10, 20]);
+    ///
+    /// fn main() {
+    ///     let before = LIMITS.get();
+    ///     LIMITS.update(|limits| limits.iter().map(|l| l * 2).collect());
+    ///     assert_eq!(*LIMITS.get(), [20, 40]);
+    ///     assert_eq!(*before, [10, 20]);
+    /// }
+    /// ```
+    pub struct ReloadableLazy<T, F = fn() -> T> {
+        value: AtomicPtr<(Arc<T>, usize)>,
+        generation: AtomicUsize,
+        writer: Mutex<()>,
+        init: F,
+        // the values are shared with readers and released by writers, on
+        // any thread
+        _marker: PhantomData<Arc<T>>,
+    }
+
+    impl<T, F> ReloadableLazy<T, F> {
+        /// Creates a new lazy value that is initialized by calling `init`,
+        /// unless a value is stored before its first access, and that is
+        /// reloaded by calling it again.
+        pub const fn new(init: F) -> Self {
+            ReloadableLazy {
+                value: AtomicPtr::new(ptr::null_mut()),
+                generation: AtomicUsize::new(0),
+                writer: Mutex::new(()),
+                init,
+                _marker: PhantomData,
+            }
+        }
+
+        /// Returns a snapshot of the value if it has already been
+        /// initialized.
+        pub fn try_get(&self) -> Option<Arc<T>> {
+            self.try_get_with_generation().map(|(value, _)| value)
+        }
+
+        /// Returns a snapshot of the value together with its generation if
+        /// it has already been initialized.
+        pub fn try_get_with_generation(&self) -> Option<(Arc<T>, usize)> {
+            let _guard = epoch::pin();
+            unsafe { self.value.load(Ordering::Acquire).as_ref().cloned() }
+        }
+
+        /// Returns the generation of the current value, which starts at 1
+        /// for the first value and grows by one for every value stored after
+        /// it, or 0 if the value has not been initialized.
+        ///
+        /// This is a single atomic load, so it is cheap enough to be checked
+        /// on every iteration of a worker loop.
+        pub fn generation(&self) -> usize {
+            self.generation.load(Ordering::Acquire)
+        }
+
+        /// Returns whether a value has been stored since the value of
+        /// generation `since` was.
+        pub fn has_changed(&self, since: usize) -> bool {
+            self.generation() != since
+        }
+
+        /// Returns whether the value has already been initialized, without
+        /// initializing it.
+        pub fn is_initialized(&self) -> bool {
+            !self.value.load(Ordering::Acquire).is_null()
+        }
+    }
+
+    impl<T: Send + Sync + 'static, F> ReloadableLazy<T, F> {
+        /// Replaces the value, returning the previous one if the value had
+        /// been initialized.
+        ///
+        /// Readers that took a snapshot before keep the previous value.
+        pub fn replace(&self, value: T) -> Option<Arc<T>> {
+            let _writer = self.writer.lock().unwrap_or_else(|e| e.into_inner());
+            self.swap(Arc::new(value))
+        }
+
+        // only called by writers, which are serialized, so the generation
+        // cannot change between loading and storing it
+        fn swap(&self, value: Arc<T>) -> Option<Arc<T>> {
+            let generation = self.generation.load(Ordering::Relaxed) + 1;
+            let previous = self.value.swap(Box::into_raw(Box::new((value, generation))), Ordering::AcqRel);
+            self.generation.store(generation, Ordering::Release);
+            if previous.is_null() {
+                return None;
+            }
+            // readers may still be cloning the `Arc` in the previous slot,
+            // which is only freed once they are done
+            let value = unsafe { (*previous).0.clone() };
+            unsafe { epoch::retire(previous) };
+            Some(value)
+        }
+    }
+
+    impl<T, F> Drop for ReloadableLazy<T, F> {
+        fn drop(&mut self) {
+            let value = *self.value.get_mut();
+            if !value.is_null() {
+                drop(unsafe { Box::from_raw(value) });
+            }
+        }
+    }
+
+    impl<T: Send + Sync + 'static, F: Fn() -> T> ReloadableLazy<T, F> {
+        /// Returns a snapshot of the value, initializing it if necessary.
+        pub fn get(&self) -> Arc<T> {
+            self.get_with_generation().0
+        }
+
+        /// Returns a snapshot of the value together with its generation,
+        /// initializing it if necessary.
+        ///
+        /// ```rust
+        /// extern crate lazy_static;
+        ///
+        /// use lazy_static::sync::ReloadableLazy;
+        ///
+        /// static CONFIG: ReloadableLazy<String> = ReloadableLazy::new(|| "v1".to_string());
+        ///
+        /// fn main() {
+        ///     let (config, generation) = CONFIG.get_with_generation();
+        ///     let derived = config.to_uppercase();
+        ///     CONFIG.replace("v2".to_string());
+        ///     if CONFIG.has_changed(generation) {
+        ///         assert_ne!(derived, CONFIG.get().to_uppercase());
+        ///     }
+        /// }
+        /// ```
+        pub fn get_with_generation(&self) -> (Arc<T>, usize) {
+            if let Some(value) = self.try_get_with_generation() {
+                return value;
+            }
+            let _writer = self.writer.lock().unwrap_or_else(|e| e.into_inner());
+            self.force();
+            self.try_get_with_generation().unwrap()
+        }
+
+        /// Replaces the value with the result of calling the initialization
+        /// function again, and returns the new value.
+        ///
+        /// If the function panics, the previous value is kept.
+        pub fn reload(&self) -> Arc<T> {
+            let _writer = self.writer.lock().unwrap_or_else(|e| e.into_inner());
+            let value = Arc::new((self.init)());
+            self.swap(value.clone());
+            value
+        }
+
+        /// Replaces the value with the result of calling `f` with the
+        /// current one, initializing it first if necessary.
+        ///
+        /// Writers are serialized, so no other value is stored between
+        /// reading the current value and storing the new one, while readers
+        /// keep getting the current value until `f` returns.
+        pub fn update<G>(&self, f: G) -> Arc<T>
+            where G: FnOnce(&T) -> T
+        {
+            let _writer = self.writer.lock().unwrap_or_else(|e| e.into_inner());
+            let current = self.force();
+            let value = Arc::new(f(&current));
+            self.swap(value.clone());
+            value
+        }
+
+        fn force(&self) -> Arc<T> {
+            if let Some(value) = self.try_get() {
+                return value;
+            }
+            let value = Arc::new((self.init)());
+            self.swap(value.clone());
+            value
+        }
+    }
+
+    impl<T, F> ReloadableLazy<T, F>
+        where T: Send + Sync + 'static,
+              F: Fn() -> T + Sync + 'static
+    {
+        /// Reloads the value whenever the file at `path` changes, for values
+        /// that are read from a configuration file.
+        ///
+        /// The file is polled every `interval` on a background thread, and
+        /// counts as changed when its modification time or its length is
+        /// different, or when it is created or removed. If the
+        /// initialization function panics, for example on a file that is
+        /// only partially written, the previous value is kept, and the file
+        /// is reloaded on its next change.
+        ///
+        /// ```rust,no_run
+        /// extern crate lazy_static;
+        ///
+        /// use std::time::Duration;
+        /// use lazy_static::sync::ReloadableLazy;
+        ///
+        /// static CONFIG: ReloadableLazy<String> = ReloadableLazy::new(|| {
+        ///     std::fs::read_to_string("/etc/my-daemon.conf").unwrap()
+        /// });
+        ///
+        /// fn main() {
+        ///     CONFIG.reload_on_change("/etc/my-daemon.conf", Duration::from_secs(1));
+        /// }
+        /// ```
+        pub fn reload_on_change<P: Into<PathBuf>>(&'static self, path: P, interval: Duration) {
+            let path = path.into();
+            let mut last = fingerprint(&path);
+            thread::Builder::new()
+                .name("lazy_static watch".into())
+                .spawn(move || loop {
+                    thread::sleep(interval);
+                    let current = fingerprint(&path);
+                    if current != last {
+                        last = current;
+                        reload(self);
+                    }
+                })
+                .expect("cannot spawn the thread that watches a file");
+        }
+
+        /// Reloads the value whenever the process receives `SIGHUP`, so
+        /// that `kill -HUP` reloads it like the configuration of most
+        /// daemons.
+        ///
+        /// This is the same as `reload_on_signal` with `SIGHUP`, and is only
+        /// available on Unix platforms.
+        #[cfg(unix)]
+        pub fn reload_on_sighup(&'static self) {
+            self.reload_on_signal(signal::SIGHUP)
+        }
+
+        /// Reloads the value whenever the process receives the signal
+        /// `signum`.
+        ///
+        /// This installs a handler for the signal, which replaces the one
+        /// installed before, by the program or by another library, so that
+        /// it is not called anymore. The values are reloaded on a background
+        /// thread, as signal handlers cannot allocate or take locks. If the
+        /// initialization function panics, the previous value is kept.
+        /// Several values can be reloaded on the same signal.
+        ///
+        /// Panics if `signum` is not a signal number below the number of
+        /// bits of a `usize`, which excludes the real-time signals, or if
+        /// the handler cannot be installed.
+        ///
+        /// This is only available on Unix platforms.
+        ///
+        /// ```rust,no_run
+        /// extern crate lazy_static;
+        ///
+        /// use lazy_static::sync::ReloadableLazy;
+        ///
+        /// static CONFIG: ReloadableLazy<String> = ReloadableLazy::new(|| {
+        ///     std::fs::read_to_string("/etc/my-daemon.conf").unwrap()
+        /// });
+        ///
+        /// fn main() {
+        ///     CONFIG.reload_on_sighup();
+        ///     loop {
+        ///         let config = CONFIG.get();
+        ///         // serve requests with the configuration
+        /// #       break;
+        ///     }
+        /// }
+        /// ```
+        #[cfg(unix)]
+        pub fn reload_on_signal(&'static self, signum: i32) {
+            signal::register(signum, self)
+        }
+    }
+
+    trait Reload: Sync {
+        fn reload(&self);
+    }
+
+    impl<T: Send + Sync + 'static, F: Fn() -> T + Sync> Reload for ReloadableLazy<T, F> {
+        fn reload(&self) {
+            ReloadableLazy::reload(self);
+        }
+    }
+
+    // the panic is reported by the panic hook, and the previous value stays
+    fn reload(value: &dyn Reload) {
+        let _ = panic::catch_unwind(AssertUnwindSafe(|| value.reload()));
+    }
+
+    fn fingerprint(path: &PathBuf) -> Option<(Option<SystemTime>, u64)> {
+        let metadata = fs::metadata(path).ok()?;
+        Some((metadata.modified().ok(), metadata.len()))
+    }
+
+    #[cfg(unix)]
+    mod signal {
+        extern crate libc;
+        extern crate std;
+
+        use self::libc::c_int;
+        use self::std::io;
+        use self::std::sync::{Mutex, Once};
+        use self::std::thread;
+        use self::std::vec::Vec;
+        use core::mem;
+        use core::ptr;
+        use core::sync::atomic::{AtomicBool, AtomicI32, AtomicUsize, Ordering};
+
+        use super::{reload, Reload};
+
+        pub const SIGHUP: i32 = libc::SIGHUP;
+
+        static RELOADS: Mutex<Vec<(i32, &'static dyn Reload)>> = Mutex::new(Vec::new());
+        // the handler only marks the signal as pending, and wakes the
+        // reloading thread through a pipe, as writing to it is one of the
+        // few things a signal handler may do
+        static PENDING: AtomicUsize = AtomicUsize::new(0);
+        static NOTIFIED: AtomicBool = AtomicBool::new(false);
+        static WRITE_FD: AtomicI32 = AtomicI32::new(-1);
+        static START: Once = Once::new();
+
+        #[cfg(any(target_os = "linux", target_os = "emscripten", target_os = "hurd",
+                  target_os = "redox", target_os = "dragonfly"))]
+        fn errno() -> Option<*mut c_int> {
+            Some(unsafe { libc::__errno_location() })
+        }
+
+        #[cfg(any(target_os = "android", target_os = "netbsd", target_os = "openbsd"))]
+        fn errno() -> Option<*mut c_int> {
+            Some(unsafe { libc::__errno() })
+        }
+
+        #[cfg(any(target_vendor = "apple", target_os = "freebsd"))]
+        fn errno() -> Option<*mut c_int> {
+            Some(unsafe { libc::__error() })
+        }
+
+        #[cfg(any(target_os = "solaris", target_os = "illumos"))]
+        fn errno() -> Option<*mut c_int> {
+            Some(unsafe { libc::___errno() })
+        }
+
+        // errno is left as the write sets it where it cannot be found
+        #[cfg(not(any(target_os = "linux", target_os = "emscripten", target_os = "hurd",
+                      target_os = "redox", target_os = "dragonfly", target_os = "android",
+                      target_os = "netbsd", target_os = "openbsd", target_vendor = "apple",
+                      target_os = "freebsd", target_os = "solaris", target_os = "illumos")))]
+        fn errno() -> Option<*mut c_int> {
+            None
+        }
+
+        extern "C" fn handler(signum: c_int) {
+            PENDING.fetch_or(1 << signum, Ordering::SeqCst);
+            // the pipe holds at most one byte, so writing never blocks
+            if !NOTIFIED.swap(true, Ordering::SeqCst) {
+                // the signal may interrupt code between a failed call and
+                // its check of errno, which the write would overwrite
+                let errno = errno();
+                let saved = errno.map(|errno| unsafe { *errno });
+                unsafe { libc::write(WRITE_FD.load(Ordering::SeqCst), &0u8 as *const u8 as *const _, 1) };
+                if let (Some(errno), Some(saved)) = (errno, saved) {
+                    unsafe { *errno = saved };
+                }
+            }
+        }
+
+        pub fn register(signum: i32, reload: &'static dyn Reload) {
+            if signum <= 0 || signum as u32 >= usize::BITS {
+                panic!("signal {} cannot be used to reload lazy values", signum);
+            }
+            START.call_once(start);
+            let mut reloads = RELOADS.lock().unwrap_or_else(|e| e.into_inner());
+            if !reloads.iter().any(|&(s, _)| s == signum) {
+                install(signum);
+            }
+            reloads.push((signum, reload));
+        }
+
+        // any handler installed before is replaced, like `signal` does, but
+        // system calls interrupted by the signal are restarted
+        fn install(signum: i32) {
+            let mut action: libc::sigaction = unsafe { mem::zeroed() };
+            action.sa_sigaction = handler as extern "C" fn(c_int) as libc::sighandler_t;
+            action.sa_flags = libc::SA_RESTART;
+            if unsafe { libc::sigemptyset(&mut action.sa_mask) } != 0
+                || unsafe { libc::sigaction(signum, &action, ptr::null_mut()) } != 0 {
+                panic!("cannot handle signal {} to reload lazy values: {}", signum, io::Error::last_os_error());
+            }
+        }
+
+        // the pipe is not inherited by programs the process executes, which
+        // would keep it open and could write to it
+        #[cfg(any(target_os = "linux", target_os = "android", target_os = "freebsd",
+                  target_os = "netbsd", target_os = "openbsd", target_os = "dragonfly",
+                  target_os = "solaris", target_os = "illumos"))]
+        fn pipe(fds: &mut [c_int; 2]) -> io::Result<()> {
+            if unsafe { libc::pipe2(fds.as_mut_ptr(), libc::O_CLOEXEC) } != 0 {
+                return Err(io::Error::last_os_error());
+            }
+            Ok(())
+        }
+
+        // another thread may execute a program between the two calls, which
+        // is as good as it gets without `pipe2`
+        #[cfg(not(any(target_os = "linux", target_os = "android", target_os = "freebsd",
+                      target_os = "netbsd", target_os = "openbsd", target_os = "dragonfly",
+                      target_os = "solaris", target_os = "illumos")))]
+        fn pipe(fds: &mut [c_int; 2]) -> io::Result<()> {
+            if unsafe { libc::pipe(fds.as_mut_ptr()) } != 0 {
+                return Err(io::Error::last_os_error());
+            }
+            for &fd in fds.iter() {
+                if unsafe { libc::fcntl(fd, libc::F_SETFD, libc::FD_CLOEXEC) } != 0 {
+                    return Err(io::Error::last_os_error());
+                }
+            }
+            Ok(())
+        }
+
+        fn start() {
+            let mut fds = [0; 2];
+            if let Err(e) = pipe(&mut fds) {
+                panic!("cannot create a pipe to reload lazy values: {}", e);
+            }
+            let read_fd = fds[0];
+            WRITE_FD.store(fds[1], Ordering::SeqCst);
+            thread::Builder::new()
+                .name("lazy_static reload".into())
+                .spawn(move || loop {
+                    let mut byte = 0u8;
+                    // this fails when interrupted by a signal
+                    if unsafe { libc::read(read_fd, &mut byte as *mut u8 as *mut _, 1) } != 1 {
+                        continue;
+                    }
+                    NOTIFIED.store(false, Ordering::SeqCst);
+                    let pending = PENDING.swap(0, Ordering::SeqCst);
+                    let reloads = RELOADS.lock().unwrap_or_else(|e| e.into_inner()).clone();
+                    for (signum, reload_value) in reloads {
+                        if pending & (1 << signum) != 0 {
+                            reload(reload_value);
+                        }
+                    }
+                })
+                .expect("cannot spawn the thread that reloads lazy values");
+        }
+    }
+}
+
+#[cfg(not(feature="spin_no_std"))]
+pub use self::epoch_reload::{EpochGuard, EpochReloadableLazy};
+
+#[cfg(not(feature="spin_no_std"))]
+mod epoch_reload {
+    extern crate std;
+
+    use self::std::boxed::Box;
+    use self::std::sync::{Arc, Mutex};
+    use core::marker::PhantomData;
+    use core::ops::Deref;
+    use core::ptr;
+    use core::sync::atomic::{AtomicPtr, AtomicUsize, Ordering};
+    use epoch;
+
+    /// A lazy value that can be replaced at runtime, whose readers do not
+    /// share a reference count.
+    ///
+    /// Like [`ReloadableLazy`](struct.ReloadableLazy.html), readers see a
+    /// consistent value while a writer replaces it, but instead of cloning
+    /// an `Arc`, which makes every reader on every core update the same
+    /// counter, a reader only marks its own thread as reading, and a
+    /// replaced value is dropped once every thread that was reading at the
+    /// time it was replaced has stopped. This is for extremely hot values,
+    /// for which the reference count shows up in profiles.
+    ///
+    /// The guard returned by [`load`](#method.load) keeps the value alive,
+    /// and cannot leave its thread. A replaced value is only dropped when a
+    /// later value is stored, so that the last replaced values stay alive
+    /// until the next replacement.
+    ///
+    /// This is not available with the `spin_no_std` feature.
+    ///
+    /// ```rust
+    /// extern crate lazy_static;
+    ///
+    /// use lazy_static::sync::EpochReloadableLazy;
+    ///
+    /// static ROUTES: EpochReloadableLazy<Vec<&'static str>> = EpochReloadableLazy::new(|| vec!["/"]);
+    ///
+    /// fn main() {
+    ///     assert_eq!(ROUTES.load().len(), 1);
+    ///     ROUTES.update(|routes| routes.iter().cloned().chain(Some("/health")).collect());
+    ///     assert_eq!(*ROUTES.load(), ["/", "/health"]);
+    /// }
+    /// ```
+    pub struct EpochReloadableLazy<T, F = fn() -> T> {
+        value: AtomicPtr<T>,
+        generation: AtomicUsize,
+        writer: Mutex<()>,
+        init: F,
+        // the values are shared with readers and dropped by writers, on any
+        // thread
+        _marker: PhantomData<Arc<T>>,
+    }
+
+    impl<T, F> EpochReloadableLazy<T, F> {
+        /// Creates a new lazy value that is initialized by calling `init`,
+        /// unless a value is stored before its first access, and that is
+        /// reloaded by calling it again.
+        pub const fn new(init: F) -> Self {
+            EpochReloadableLazy {
+                value: AtomicPtr::new(ptr::null_mut()),
+                generation: AtomicUsize::new(0),
+                writer: Mutex::new(()),
+                init,
+                _marker: PhantomData,
+            }
+        }
+
+        /// Returns a guard for the value if it has already been
+        /// initialized.
+        pub fn try_load(&self) -> Option<EpochGuard<'_, T>> {
+            let guard = epoch::pin();
+            let value = unsafe { self.value.load(Ordering::Acquire).as_ref()? };
+            Some(EpochGuard {
+                value,
+                _guard: guard,
+            })
+        }
+
+        /// Returns whether the value has already been initialized, without
+        /// initializing it.
+        pub fn is_initialized(&self) -> bool {
+            !self.value.load(Ordering::Acquire).is_null()
+        }
+
+        /// Returns the generation of the current value, like
+        /// [`ReloadableLazy::generation`](struct.ReloadableLazy.html#method.generation).
+        pub fn generation(&self) -> usize {
+            self.generation.load(Ordering::Acquire)
+        }
+
+        /// Returns whether a value has been stored since the value of
+        /// generation `since` was.
+        pub fn has_changed(&self, since: usize) -> bool {
+            self.generation() != since
+        }
+    }
+
+    impl<T: Send + Sync + 'static, F> EpochReloadableLazy<T, F> {
+        /// Replaces the value.
+        ///
+        /// Readers that loaded the previous value keep it until they drop
+        /// their guard.
+        pub fn replace(&self, value: T) {
+            let _writer = self.writer.lock().unwrap_or_else(|e| e.into_inner());
+            self.swap(value);
+        }
+
+        // only called by writers, which are serialized
+        fn swap(&self, value: T) {
+            let generation = self.generation.load(Ordering::Relaxed) + 1;
+            let previous = self.value.swap(Box::into_raw(Box::new(value)), Ordering::AcqRel);
+            self.generation.store(generation, Ordering::Release);
+            if !previous.is_null() {
+                unsafe { epoch::retire(previous) };
+            }
+        }
+    }
+
+    impl<T: Send + Sync + 'static, F: Fn() -> T> EpochReloadableLazy<T, F> {
+        /// Returns a guard for the value, initializing it if necessary.
+        pub fn load(&self) -> EpochGuard<'_, T> {
+            if let Some(guard) = self.try_load() {
+                return guard;
+            }
+            {
+                let _writer = self.writer.lock().unwrap_or_else(|e| e.into_inner());
+                if !self.is_initialized() {
+                    self.swap((self.init)());
+                }
+            }
+            self.try_load().unwrap()
+        }
+
+        /// Replaces the value with the result of calling the initialization
+        /// function again.
+        ///
+        /// If the function panics, the previous value is kept.
+        pub fn reload(&self) {
+            let _writer = self.writer.lock().unwrap_or_else(|e| e.into_inner());
+            self.swap((self.init)());
+        }
+
+        /// Replaces the value with the result of calling `f` with the
+        /// current one, initializing it first if necessary.
+        ///
+        /// Writers are serialized, so no other value is stored between
+        /// reading the current value and storing the new one.
+        pub fn update<G>(&self, f: G)
+            where G: FnOnce(&T) -> T
+        {
+            let _writer = self.writer.lock().unwrap_or_else(|e| e.into_inner());
+            if !self.is_initialized() {
+                self.swap((self.init)());
+            }
+            // the current value is only retired by writers
+            let value = f(unsafe { &*self.value.load(Ordering::Acquire) });
+            self.swap(value);
+        }
+    }
+
+    impl<T, F> Drop for EpochReloadableLazy<T, F> {
+        fn drop(&mut self) {
+            let value = *self.value.get_mut();
+            if !value.is_null() {
+                drop(unsafe { Box::from_raw(value) });
+            }
+        }
+    }
+
+    /// A guard for the value of an
+    /// [`EpochReloadableLazy`](struct.EpochReloadableLazy.html), which keeps
+    /// it from being dropped while it is alive.
+    ///
+    /// The guard cannot be sent to other threads.
+    pub struct EpochGuard<'a, T> {
+        value: &'a T,
+        _guard: epoch::Guard,
+    }
+
+    impl<'a, T> Deref for EpochGuard<'a, T> {
+        type Target = T;
+
+        fn deref(&self) -> &T {
+            self.value
+        }
+    }
+}
@@ -0,0 +1,194 @@
+// Copyright 2016 lazy-static.rs Developers
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+/*!
+Lazily initialized values for a single thread.
+
+These are the counterparts of [`sync::Lazy`](../sync/struct.Lazy.html) and
+[`sync::OnceCell`](../sync/struct.OnceCell.html) without any synchronization.
+They are not `Sync`, so they cannot be stored in a `static`, but they can be
+used inside of `thread_local!`, in single-threaded event loops, or on
+`wasm32`, where the atomics of the `sync` types would only add overhead.
+
+```rust
+extern crate lazy_static;
+
+use lazy_static::unsync::Lazy;
+
+thread_local! {
+    static SQUARES: Lazy<Vec<u64>> = Lazy::new(|| (0..100).map(|n| n * n).collect());
+}
+
+fn main() {
+    assert_eq!(SQUARES.with(|squares| squares[9]), 81);
+}
+```
+*/
+
+use core::cell::{Cell, UnsafeCell};
+use core::ops::Deref;
+
+/// A value that is initialized on its first access, on a single thread.
+///
+/// The initialized value is reached through `Deref`.
+pub struct Lazy<T, F = fn() -> T> {
+    cell: OnceCell<T>,
+    init: Cell<Option<F>>,
+    // set once `take` has taken out the value that `init` returned
+    taken: bool,
+}
+
+impl<T, F> Lazy<T, F> {
+    /// Creates a new lazy value that is initialized by calling `init`.
+    pub const fn new(init: F) -> Self {
+        Lazy {
+            cell: OnceCell::new(),
+            init: Cell::new(Some(init)),
+            taken: false,
+        }
+    }
+
+    /// Returns a reference to the value if it has already been initialized.
+    pub fn try_get(this: &Self) -> Option<&T> {
+        this.cell.get()
+    }
+
+    /// Returns whether the value has already been initialized, without
+    /// initializing it.
+    pub fn is_initialized(this: &Self) -> bool {
+        this.cell.get().is_some()
+    }
+
+    /// Returns a mutable reference to the value if it has already been
+    /// initialized.
+    pub fn get_mut(this: &mut Self) -> Option<&mut T> {
+        this.cell.get_mut()
+    }
+
+    /// Consumes the lazy value, returning the value if it has been
+    /// initialized.
+    pub fn into_inner(this: Self) -> Option<T> {
+        this.cell.into_inner()
+    }
+
+    /// Takes the value out if it has been initialized.
+    ///
+    /// The initialization function has already been used up at that
+    /// point, so dereferencing `this` afterwards panics with a message
+    /// saying that the value was taken.
+    pub fn take(this: &mut Self) -> Option<T> {
+        let value = this.cell.take();
+        this.taken |= value.is_some();
+        value
+    }
+}
+
+impl<T, F: FnOnce() -> T> Lazy<T, F> {
+    /// Forces the initialization of the value and returns a reference to it.
+    ///
+    /// This is equivalent to dereferencing `this`, but more explicit.
+    pub fn force(this: &Self) -> &T {
+        this.cell.get_or_init(|| match this.init.take() {
+            Some(init) => init(),
+            None if this.taken => panic!("Lazy instance has had its value taken"),
+            None => panic!("Lazy instance has previously been poisoned"),
+        })
+    }
+}
+
+impl<T, F: FnOnce() -> T> Deref for Lazy<T, F> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        Lazy::force(self)
+    }
+}
+
+/// A cell that can be written to only once, on a single thread.
+///
+/// ```rust
+/// extern crate lazy_static;
+///
+/// use lazy_static::unsync::OnceCell;
+///
+/// fn main() {
+///     let cell = OnceCell::new();
+///     assert!(cell.get().is_none());
+///     assert_eq!(cell.get_or_init(|| 1), &1);
+///     assert!(cell.set(2).is_err());
+/// }
+/// ```
+pub struct OnceCell<T> {
+    value: UnsafeCell<Option<T>>,
+}
+
+impl<T> OnceCell<T> {
+    /// Creates a new empty cell.
+    pub const fn new() -> Self {
+        OnceCell {
+            value: UnsafeCell::new(None),
+        }
+    }
+
+    /// Returns a reference to the value, or `None` if the cell is empty.
+    pub fn get(&self) -> Option<&T> {
+        // the value is never changed again through a shared reference once
+        // it is set
+        unsafe { (*self.value.get()).as_ref() }
+    }
+
+    /// Returns a mutable reference to the value, or `None` if the cell is
+    /// empty.
+    pub fn get_mut(&mut self) -> Option<&mut T> {
+        self.value.get_mut().as_mut()
+    }
+
+    /// Consumes the cell, returning its value if it has one.
+    pub fn into_inner(self) -> Option<T> {
+        self.value.into_inner()
+    }
+
+    /// Takes the value out of the cell, leaving it empty.
+    pub fn take(&mut self) -> Option<T> {
+        self.value.get_mut().take()
+    }
+
+    /// Sets the value of the cell.
+    ///
+    /// Returns `Err(value)` if the cell already had a value.
+    pub fn set(&self, value: T) -> Result<(), T> {
+        if self.get().is_some() {
+            return Err(value);
+        }
+        // there are no references to the empty value, and nothing else can
+        // access the cell while this runs
+        unsafe { *self.value.get() = Some(value) };
+        Ok(())
+    }
+
+    /// Returns a reference to the value, initializing it with `f` if the
+    /// cell is empty.
+    ///
+    /// Panics if `f` initializes the cell itself.
+    pub fn get_or_init<F>(&self, f: F) -> &T
+        where F: FnOnce() -> T
+    {
+        if let Some(value) = self.get() {
+            return value;
+        }
+        if self.set(f()).is_err() {
+            panic!("OnceCell initialized by its own initialization function");
+        }
+        self.get().unwrap()
+    }
+}
+
+impl<T> Default for OnceCell<T> {
+    fn default() -> Self {
+        OnceCell::new()
+    }
+}
@@ -0,0 +1,34 @@
+#![cfg(all(feature="backtrace", not(feature="spin_no_std"), not(feature="strict")))]
+
+#[macro_use]
+extern crate lazy_static;
+
+use std::thread;
+use std::time::Instant;
+
+lazy_static! {
+    static ref CONFIG: u32 = 1;
+    static ref UNTOUCHED: u32 = 2;
+}
+
+fn info(name: &str) -> &'static lazy_static::registry::StaticInfo {
+    lazy_static::registry().find(|s| s.name() == name).unwrap()
+}
+
+#[inline(never)]
+fn load_config() -> u32 {
+    *CONFIG
+}
+
+#[test]
+fn first_init() {
+    assert!(info("backtrace::CONFIG").first_init().is_none());
+    let before = Instant::now();
+    thread::Builder::new().name("early".into()).spawn(load_config).unwrap().join().unwrap();
+    let _ = *CONFIG;
+    let first = info("backtrace::CONFIG").first_init().unwrap();
+    assert_eq!(first.thread, "early");
+    assert!(first.at >= before);
+    assert!(first.backtrace.to_string().contains("load_config"));
+    assert!(info("backtrace::UNTOUCHED").first_init().is_none());
+}
@@ -0,0 +1,24 @@
+#![cfg(all(feature="critical-section", not(feature="strict")))]
+
+#[macro_use]
+extern crate lazy_static;
+
+use lazy_static::backend::{CriticalSectionOnce, GlobalCriticalSection};
+
+lazy_static! {
+    #[lazy(backend = CriticalSectionOnce<GlobalCriticalSection>)]
+    static ref GUARDED: Vec<u32> = vec![1, 2, 3];
+
+    #[lazy(backend = "critical_section")]
+    static ref NAMED: u32 = 4;
+}
+
+#[test]
+fn global_critical_section() {
+    let threads: Vec<_> = (0..4).map(|_| std::thread::spawn(|| GUARDED.len())).collect();
+    for thread in threads {
+        assert_eq!(thread.join().unwrap(), 3);
+    }
+    assert!(lazy_static::is_initialized(&GUARDED));
+    assert_eq!(*NAMED, 4);
+}
@@ -0,0 +1,37 @@
+#![cfg(all(feature="ctor", not(feature="strict")))]
+
+#[macro_use]
+extern crate lazy_static;
+
+lazy_static! {
+    #[lazy(eager)]
+    static ref EAGER: Vec<u32> = vec![1, 2, 3];
+    static ref LAZY: Vec<u32> = vec![4];
+    #[lazy(eager)]
+    static ref mut EAGER_MUT: u32 = 5;
+}
+
+#[test]
+fn initialized_before_main() {
+    assert!(lazy_static::is_initialized(&EAGER));
+    assert!(lazy_static::is_initialized(&EAGER_MUT));
+    assert_eq!(lazy_static::is_initialized(&LAZY), cfg!(feature="eager-all"));
+    assert_eq!(*EAGER, [1, 2, 3]);
+    assert_eq!(*EAGER_MUT.lock(), 5);
+}
+
+#[cfg(not(feature="spin_no_std"))]
+lazy_static! {
+    #[lazy(background)]
+    static ref BACKGROUND: String = std::thread::current().name().unwrap_or_default().to_string();
+}
+
+#[cfg(not(feature="spin_no_std"))]
+#[test]
+fn initialized_in_background() {
+    let start = std::time::Instant::now();
+    while !lazy_static::is_initialized(&BACKGROUND) && start.elapsed() < std::time::Duration::from_secs(10) {
+        std::thread::sleep(std::time::Duration::from_millis(1));
+    }
+    assert_eq!(*BACKGROUND, "ctor::BACKGROUND");
+}
@@ -0,0 +1,43 @@
+#![cfg(all(feature="eager-all", not(feature="strict")))]
+
+#[macro_use]
+extern crate lazy_static;
+
+use std::sync::atomic::{AtomicUsize, Ordering::SeqCst};
+
+static INITS: AtomicUsize = AtomicUsize::new(0);
+
+lazy_static! {
+    static ref FIRST: usize = INITS.fetch_add(1, SeqCst);
+    pub static ref SECOND: Vec<u8> = {
+        INITS.fetch_add(1, SeqCst);
+        vec![2]
+    };
+    static ref NAME: str = String::from("eager").into_boxed_str();
+    static ref mut LOCKED: u32 = 3;
+}
+
+#[test]
+fn every_static_initialized_before_main() {
+    assert!(lazy_static::is_initialized(&FIRST));
+    assert!(lazy_static::is_initialized(&SECOND));
+    assert!(lazy_static::is_initialized(&NAME));
+    assert!(lazy_static::is_initialized(&LOCKED));
+    assert_eq!(INITS.load(SeqCst), 2);
+    assert!(*FIRST < 2);
+    assert_eq!(*SECOND, [2]);
+}
+
+lazy_static! {
+    #[lazy(retry_on_panic)]
+    static ref RETRIED: u32 = 4;
+    static ref PLUGIN: u32;
+}
+
+#[test]
+fn statics_that_may_not_be_ready_left_lazy() {
+    assert!(!lazy_static::is_initialized(&RETRIED));
+    assert_eq!(*RETRIED, 4);
+    PLUGIN.init_with(|| 5);
+    assert_eq!(*PLUGIN, 5);
+}
@@ -0,0 +1,51 @@
+#![cfg(all(feature="log", not(feature="spin_no_std"), not(feature="strict")))]
+
+#[macro_use]
+extern crate lazy_static;
+extern crate log;
+
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+use log::{Log, Metadata, Record};
+
+// Records the warnings logged by lazy_static.
+struct Warnings(Mutex<Vec<String>>);
+
+impl Log for Warnings {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.target() == "lazy_static"
+    }
+
+    fn log(&self, record: &Record) {
+        if self.enabled(record.metadata()) {
+            assert_eq!(record.level(), log::Level::Warn);
+            self.0.lock().unwrap().push(record.args().to_string());
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+static WARNINGS: Warnings = Warnings(Mutex::new(Vec::new()));
+
+lazy_static! {
+    static ref SLOW: u32 = {
+        thread::sleep(Duration::from_millis(30));
+        1
+    };
+    static ref FAST: u32 = 2;
+}
+
+#[test]
+fn slow_initialization() {
+    log::set_logger(&WARNINGS).unwrap();
+    log::set_max_level(log::LevelFilter::Warn);
+    lazy_static::set_slow_init_threshold(Duration::from_millis(20));
+    assert_eq!(*FAST, 2);
+    assert_eq!(*SLOW, 1);
+    let warnings = WARNINGS.0.lock().unwrap();
+    assert_eq!(warnings.len(), 1);
+    assert!(warnings[0].starts_with("initializer of lazy static `log::SLOW` took "));
+    assert!(warnings[0].ends_with(", longer than 20ms"));
+}
@@ -0,0 +1,60 @@
+#![cfg(loom)]
+
+// Run with `RUSTFLAGS="--cfg loom" cargo test --test loom --release`.
+
+extern crate lazy_static;
+extern crate loom;
+
+use lazy_static::sync::{Lazy, OnceCell};
+use loom::sync::Arc;
+use loom::sync::atomic::{AtomicUsize, Ordering};
+use loom::thread;
+
+#[test]
+fn lazy_initialized_once() {
+    loom::model(|| {
+        let runs = Arc::new(AtomicUsize::new(0));
+        let lazy = {
+            let runs = runs.clone();
+            Arc::new(Lazy::new(move || {
+                runs.fetch_add(1, Ordering::Relaxed);
+                vec![1, 2, 3]
+            }))
+        };
+        let other = {
+            let lazy = lazy.clone();
+            thread::spawn(move || lazy.len())
+        };
+        assert_eq!(lazy.len(), 3);
+        assert_eq!(other.join().unwrap(), 3);
+        assert_eq!(runs.load(Ordering::Relaxed), 1);
+    });
+}
+
+#[test]
+fn try_get_sees_initialized_value() {
+    loom::model(|| {
+        let lazy: Arc<Lazy<String>> = Arc::new(Lazy::new(|| String::from("value")));
+        let other = {
+            let lazy = lazy.clone();
+            thread::spawn(move || Lazy::try_get(&lazy).map(|value| value.len()))
+        };
+        Lazy::force(&lazy);
+        assert!(matches!(other.join().unwrap(), None | Some(5)));
+    });
+}
+
+#[test]
+fn once_cell_set_race() {
+    loom::model(|| {
+        let cell = Arc::new(OnceCell::new());
+        let other = {
+            let cell = cell.clone();
+            thread::spawn(move || cell.set(1).is_ok())
+        };
+        let here = cell.set(2).is_ok();
+        let there = other.join().unwrap();
+        assert!(here != there);
+        assert_eq!(*cell.get().unwrap(), if here { 2 } else { 1 });
+    });
+}
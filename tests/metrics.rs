@@ -0,0 +1,25 @@
+#![cfg(all(feature="metrics", not(feature="strict")))]
+
+#[macro_use]
+extern crate lazy_static;
+
+use std::thread;
+use std::time::Duration;
+
+lazy_static! {
+    static ref SLOW: u32 = {
+        thread::sleep(Duration::from_millis(20));
+        1
+    };
+    static ref try FALLIBLE: Result<u32, ()> = Ok(2);
+}
+
+#[test]
+fn records_timing() {
+    assert_eq!(*SLOW, 1);
+    assert_eq!(FALLIBLE.try_deref(), Ok(&2));
+    let timings = lazy_static::metrics();
+    let slow = timings.iter().find(|t| t.name == "metrics::SLOW").unwrap();
+    assert!(slow.duration >= Duration::from_millis(20));
+    assert!(timings.iter().any(|t| t.name == "metrics::FALLIBLE"));
+}
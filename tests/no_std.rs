@@ -1,5 +1,4 @@
-#![cfg(feature="spin_no_std")]
-#![feature(const_fn)]
+#![cfg(all(feature="spin_no_std", not(feature="strict")))]
 
 #![no_std]
 
@@ -9,6 +8,13 @@ extern crate lazy_static;
 lazy_static! {
     /// Documentation!
     pub static ref NUMBER: u32 = times_two(3);
+
+    static ref mut MUTABLE: u32 = 1;
+
+    #[lazy(backend = "spin")]
+    static ref SPINNING: u32 = 8;
+
+    static ref LATE: u32;
 }
 
 fn times_two(n: u32) -> u32 {
@@ -19,3 +25,20 @@ fn times_two(n: u32) -> u32 {
 fn test_basic() {
     assert_eq!(*NUMBER, 6);
 }
+
+#[test]
+fn test_mutable() {
+    *MUTABLE.lock() += 1;
+    assert_eq!(*MUTABLE.lock(), 2);
+}
+
+#[test]
+fn test_named_backend() {
+    assert_eq!(*SPINNING, 8);
+}
+
+#[test]
+fn test_init_with() {
+    LATE.init_with(|| 5);
+    assert_eq!(*LATE, 5);
+}
@@ -0,0 +1,35 @@
+#![cfg(all(feature="parking_lot", not(feature="spin_no_std"), not(feature="strict")))]
+
+#[macro_use]
+extern crate lazy_static;
+extern crate parking_lot;
+
+use std::any::TypeId;
+use lazy_static::backend::OnceBackend;
+
+lazy_static! {
+    static ref DEFAULT: Vec<u32> = vec![1, 2, 3];
+
+    #[lazy(backend = parking_lot::Once)]
+    static ref EXPLICIT: u32 = 4;
+
+    #[lazy(backend = "parking_lot")]
+    static ref NAMED: u32 = 5;
+}
+
+fn backend<T, B: OnceBackend + 'static>(_: &lazy_static::lazy::Lazy<T, B>) -> TypeId {
+    TypeId::of::<B>()
+}
+
+#[test]
+fn parking_lot_once() {
+    let threads: Vec<_> = (0..4).map(|_| std::thread::spawn(|| DEFAULT.len())).collect();
+    for thread in threads {
+        assert_eq!(thread.join().unwrap(), 3);
+    }
+    assert!(lazy_static::is_initialized(&DEFAULT));
+    assert_eq!(*EXPLICIT, 4);
+    assert_eq!(*NAMED, 5);
+    assert_eq!(backend(NAMED::__static_ref_lazy()), TypeId::of::<parking_lot::Once>());
+    assert_eq!(backend(&lazy_static::lazy::Lazy::<u32>::new()), TypeId::of::<parking_lot::Once>());
+}
@@ -0,0 +1,112 @@
+#![cfg(all(feature="process-global", not(feature="strict")))]
+
+#[macro_use]
+extern crate lazy_static;
+
+use std::os::raw::c_void;
+use std::panic;
+use std::sync::atomic::{AtomicUsize, Ordering::SeqCst};
+use std::sync::Barrier;
+use std::thread;
+use std::time::Duration;
+
+static INITS: AtomicUsize = AtomicUsize::new(0);
+
+mod first {
+    lazy_static! {
+        #[lazy(process_global = "process_global::SHARED")]
+        pub static ref SHARED: Vec<u32> = {
+            super::INITS.fetch_add(1, super::SeqCst);
+            vec![1, 2, 3]
+        };
+    }
+}
+
+mod second {
+    lazy_static! {
+        #[lazy(process_global = "process_global::SHARED")]
+        pub static ref SHARED: Vec<u32> = {
+            super::INITS.fetch_add(1, super::SeqCst);
+            vec![4, 5, 6]
+        };
+    }
+}
+
+#[test]
+fn initialized_once_per_key() {
+    assert_eq!(*first::SHARED, [1, 2, 3]);
+    assert_eq!(*second::SHARED, [1, 2, 3]);
+    assert!(std::ptr::eq(&*first::SHARED, &*second::SHARED));
+    assert_eq!(INITS.load(SeqCst), 1);
+}
+
+static RACED_INITS: AtomicUsize = AtomicUsize::new(0);
+
+lazy_static! {
+    #[lazy(process_global = "process_global::RACED")]
+    static ref RACED_FIRST: u32 = {
+        RACED_INITS.fetch_add(1, SeqCst);
+        thread::sleep(Duration::from_millis(50));
+        1
+    };
+    #[lazy(process_global = "process_global::RACED")]
+    static ref RACED_SECOND: u32 = {
+        RACED_INITS.fetch_add(1, SeqCst);
+        thread::sleep(Duration::from_millis(50));
+        2
+    };
+}
+
+#[test]
+fn initialized_once_when_raced() {
+    let barrier = Barrier::new(2);
+    let (first, second) = thread::scope(|s| {
+        let first = s.spawn(|| {
+            barrier.wait();
+            *RACED_FIRST
+        });
+        let second = s.spawn(|| {
+            barrier.wait();
+            *RACED_SECOND
+        });
+        (first.join().unwrap(), second.join().unwrap())
+    });
+    assert_eq!(first, second);
+    assert_eq!(RACED_INITS.load(SeqCst), 1);
+}
+
+lazy_static! {
+    #[lazy(process_global = "process_global::FLAKY")]
+    static ref FAILING: u32 = panic!("the first copy fails");
+    #[lazy(process_global = "process_global::FLAKY")]
+    static ref RETRYING: u32 = 7;
+}
+
+// the static that panicked is poisoned like any other, but the other copies
+// can still create the value
+#[test]
+fn panic_is_retried_by_other_copies() {
+    assert!(panic::catch_unwind(|| *FAILING).is_err());
+    assert_eq!(*RETRYING, 7);
+}
+
+lazy_static! {
+    #[lazy(process_global = "process_global::FOREIGN")]
+    static ref FOREIGN: String = "local".to_string();
+}
+
+// stands in for another copy of the crate, which created the value first
+unsafe extern "C-unwind" fn foreign_init(_: *mut c_void) -> *const c_void {
+    Box::into_raw(Box::new("foreign".to_string())) as *const c_void
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn value_of_another_copy() {
+    let key = "process_global::FOREIGN";
+    let value = unsafe {
+        lazy_static::global::lazy_static_process_global_v1(key.as_ptr(), key.len(), foreign_init, std::ptr::null_mut())
+    };
+    assert!(!value.is_null());
+    assert_eq!(*FOREIGN, "foreign");
+}
@@ -0,0 +1,172 @@
+#![cfg(all(feature="registry", not(feature="strict")))]
+
+#[macro_use]
+extern crate lazy_static;
+
+lazy_static! {
+    static ref FIRST: u32 = 1;
+    pub static ref SECOND: Vec<u8> = vec![2];
+    static ref mut LOCKED: u32 = 3;
+}
+
+mod warm {
+    lazy_static! {
+        pub static ref CACHE: Vec<u32> = vec![1, 2, 3];
+    }
+
+    pub mod nested {
+        lazy_static! {
+            pub static ref INNER: u32 = 4;
+        }
+    }
+}
+
+mod warmer {
+    lazy_static! {
+        pub static ref COLD: u32 = 5;
+    }
+}
+
+fn info(name: &str) -> &'static lazy_static::registry::StaticInfo {
+    lazy_static::registry().find(|s| s.name() == name).unwrap()
+}
+
+#[test]
+fn registered() {
+    assert_eq!(info("registry::FIRST").type_name(), "u32");
+    assert_eq!(info("registry::SECOND").type_name(), "Vec<u8>");
+    assert!(lazy_static::registry().any(|s| s.name() == "registry::LOCKED"));
+}
+
+lazy_static! {
+    static ref const UNREGISTERED_CONST: u32 = 10;
+    static ref thread_local UNREGISTERED_LOCAL: u32 = 11;
+    static ref try UNREGISTERED_TRY: Result<u32, String> = Ok(12);
+    #[lazy(cache_error)]
+    static ref try UNREGISTERED_CACHED: Result<u32, String> = Ok(13);
+    static ref async UNREGISTERED_ASYNC: u32 = std::future::ready(14);
+    #[lazy(fallback = 0)]
+    static ref try REGISTERED_FALLBACK: Result<u32, String> = Ok(15);
+}
+
+#[test]
+fn unregistered_kinds() {
+    for name in ["CONST", "LOCAL", "TRY", "CACHED", "ASYNC"] {
+        let name = format!("registry::UNREGISTERED_{}", name);
+        assert!(!lazy_static::registry().any(|s| s.name() == name), "{}", name);
+    }
+    assert_eq!(*UNREGISTERED_CONST + UNREGISTERED_LOCAL.with(|l| *l), 21);
+    assert_eq!(UNREGISTERED_TRY.try_deref(), Ok(&12));
+    assert_eq!(UNREGISTERED_CACHED.try_deref(), Ok(&13));
+    assert_eq!(*UNREGISTERED_ASYNC.blocking_get(), 14);
+    assert_eq!(info("registry::REGISTERED_FALLBACK").type_name(), "u32");
+}
+
+#[test]
+fn initialize_from_registry() {
+    let second = info("registry::SECOND");
+    assert!(!second.is_initialized());
+    second.initialize();
+    assert!(second.is_initialized());
+    assert!(lazy_static::is_initialized(&SECOND));
+    assert_eq!(*FIRST, 1);
+    assert!(info("registry::FIRST").is_initialized());
+}
+
+#[test]
+fn initialize_module() {
+    assert!(!lazy_static::is_initialized(&warm::CACHE));
+    lazy_static::initialize_module("registry::warm");
+    assert!(lazy_static::is_initialized(&warm::CACHE));
+    assert!(lazy_static::is_initialized(&warm::nested::INNER));
+    assert!(!lazy_static::is_initialized(&warmer::COLD));
+}
+
+lazy_static! {
+    static ref COUNTED: u32 = 6;
+    static ref UNUSED: u32 = 7;
+}
+
+#[test]
+fn accesses() {
+    lazy_static::initialize(&UNUSED);
+    let _ = *COUNTED + *COUNTED;
+    if cfg!(feature="access-counts") {
+        assert_eq!(info("registry::UNUSED").accesses(), Some(0));
+        assert_eq!(info("registry::COUNTED").accesses(), Some(2));
+    } else {
+        assert_eq!(info("registry::COUNTED").accesses(), None);
+    }
+}
+
+#[test]
+#[cfg(not(feature="spin_no_std"))]
+fn leaked() {
+    let before = lazy_static::registry().filter(|s| s.is_leaked()).count();
+    let value = lazy_static::leak(vec![1u16, 2]);
+    assert_eq!(*value, [1, 2]);
+    let leaked: Vec<_> = lazy_static::registry().filter(|s| s.is_leaked()).collect();
+    assert_eq!(leaked.len(), before + 1);
+    assert!(leaked.iter().any(|s| s.type_name() == "alloc::vec::Vec<u16>" && s.is_initialized()));
+    assert!(!info("registry::FIRST").is_leaked());
+}
+
+#[cfg(not(feature="spin_no_std"))]
+mod dump {
+    use std::thread;
+    use std::time::Duration;
+    use lazy_static::registry::InitState;
+
+    lazy_static! {
+        static ref DUMPED: u32 = {
+            thread::sleep(Duration::from_millis(10));
+            8
+        };
+        static ref NOT_DUMPED: u32 = 9;
+    }
+
+    #[test]
+    fn dump() {
+        thread::Builder::new().name("warm-up".into()).spawn(|| *DUMPED).unwrap().join().unwrap();
+        let dump = lazy_static::dump();
+        let dumped = dump.statics.iter().find(|s| s.name == "registry::dump::DUMPED").unwrap();
+        assert_eq!(dumped.type_name, "u32");
+        assert_eq!(dumped.state, InitState::Initialized);
+        assert!(dumped.duration.unwrap() >= Duration::from_millis(10));
+        assert_eq!(dumped.thread.as_ref().unwrap(), "warm-up");
+        let not_dumped = dump.statics.iter().find(|s| s.name == "registry::dump::NOT_DUMPED").unwrap();
+        assert_eq!(not_dumped.state, InitState::Uninitialized);
+        assert_eq!(not_dumped.duration, None);
+
+        let report = dump.to_string();
+        assert!(report.starts_with("lazy statics: "));
+        assert!(report.contains("\n  registry::dump::DUMPED: u32, initialized in "));
+        assert!(report.contains(" on thread `warm-up`\n"));
+        assert!(report.contains("\n  registry::dump::NOT_DUMPED: u32, uninitialized\n"));
+        lazy_static_dump!();
+    }
+
+    lazy_static! {
+        static ref SCRAPED: u32 = 10;
+        static ref NOT_SCRAPED: Option<&'static str> = None;
+    }
+
+    #[test]
+    fn prometheus() {
+        let _ = *SCRAPED + *SCRAPED;
+        let metrics = lazy_static::dump().to_prometheus();
+        assert!(metrics.starts_with("# HELP lazy_static_initialized "));
+        assert!(metrics.contains("\n# TYPE lazy_static_initialized gauge\n"));
+        assert!(metrics.contains("\nlazy_static_initialized{name=\"registry::dump::SCRAPED\",type=\"u32\"} 1\n"));
+        assert!(metrics.contains("\nlazy_static_initialized{name=\"registry::dump::NOT_SCRAPED\",type=\"Option<&'static str>\"} 0\n"));
+        assert!(metrics.contains("\nlazy_static_init_duration_seconds{name=\"registry::dump::SCRAPED\",type=\"u32\"} "));
+        assert!(!metrics.contains("lazy_static_init_duration_seconds{name=\"registry::dump::NOT_SCRAPED\""));
+        assert!(!metrics.contains("<leaked>"));
+        if cfg!(feature="access-counts") {
+            assert!(metrics.contains("\n# TYPE lazy_static_accesses_total counter\n"));
+            assert!(metrics.contains("\nlazy_static_accesses_total{name=\"registry::dump::SCRAPED\",type=\"u32\"} 2\n"));
+        } else {
+            assert!(!metrics.contains("lazy_static_accesses_total"));
+        }
+    }
+}
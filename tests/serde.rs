@@ -0,0 +1,29 @@
+#![cfg(all(feature="serde", not(feature="strict")))]
+
+#[macro_use]
+extern crate lazy_static;
+extern crate serde_json;
+
+use std::collections::BTreeMap;
+use lazy_static::sync::Lazy;
+
+lazy_static! {
+    static ref CONFIG: BTreeMap<&'static str, u32> = {
+        let mut m = BTreeMap::new();
+        m.insert("port", 8080);
+        m.insert("workers", 4);
+        m
+    };
+}
+
+static NAMES: Lazy<Vec<&'static str>> = Lazy::new(|| vec!["foo", "bar"]);
+
+#[test]
+fn serialize_static() {
+    assert_eq!(serde_json::to_string(&CONFIG).unwrap(), r#"{"port":8080,"workers":4}"#);
+}
+
+#[test]
+fn serialize_lazy_type() {
+    assert_eq!(serde_json::to_string(&NAMES).unwrap(), r#"["foo","bar"]"#);
+}
@@ -0,0 +1,49 @@
+// eager-all would initialize `INJECTED` before it is set
+#![cfg(not(any(feature="spin_no_std", feature="strict", feature="eager-all")))]
+
+#[macro_use]
+extern crate lazy_static;
+
+use std::sync::Mutex;
+
+// every static of this test binary is dropped, so it only has one test
+
+static DROPS: Mutex<Vec<&'static str>> = Mutex::new(Vec::new());
+
+struct Logged(&'static str);
+
+impl Drop for Logged {
+    fn drop(&mut self) {
+        DROPS.lock().unwrap().push(self.0);
+        if self.0 == "late" {
+            let _ = &*REVIVED;
+        }
+    }
+}
+
+lazy_static! {
+    static ref BASE: Logged = Logged("base");
+    #[lazy(depends_on = [BASE])]
+    static ref SERVICE: Logged = Logged("service");
+    static ref LATE: Logged = Logged("late");
+    static ref REVIVED: Logged = Logged("revived");
+    static ref INJECTED: Logged = Logged("computed");
+    static ref const CONSTANT: u32 = 1;
+}
+
+#[test]
+fn shutdown() {
+    assert!(lazy_static::set(&INJECTED, Logged("injected")).is_ok());
+    let _ = &*SERVICE;
+    let _ = &*LATE;
+    unsafe { lazy_static::shutdown() };
+    assert_eq!(*DROPS.lock().unwrap(), ["late", "revived", "service", "base", "injected"]);
+    assert!(!lazy_static::is_initialized(&BASE));
+    assert!(!lazy_static::is_initialized(&INJECTED));
+    assert!(!lazy_static::is_initialized(&REVIVED));
+    assert_eq!(*CONSTANT, 1);
+
+    assert_eq!(SERVICE.0, "service");
+    unsafe { lazy_static::shutdown() };
+    assert_eq!(DROPS.lock().unwrap()[5..], ["service", "base"]);
+}
@@ -0,0 +1,24 @@
+#![cfg(feature="strict")]
+
+#[macro_use]
+extern crate lazy_static;
+
+use std::panic;
+
+lazy_static! {
+    static ref CONFIG: Vec<u32> = vec![1, 2];
+    static ref try PORT: Result<u16, std::num::ParseIntError> = "80".parse();
+}
+
+#[test]
+fn access_before_ready_panics() {
+    let error = panic::catch_unwind(|| CONFIG.len()).unwrap_err();
+    let message = error.downcast_ref::<String>().unwrap();
+    assert!(message.contains("lazy static `strict::CONFIG` was accessed before"), "{}", message);
+    assert!(panic::catch_unwind(|| PORT.try_deref().is_ok()).is_err());
+    assert!(!lazy_static::is_initialized(&CONFIG));
+
+    lazy_static::runtime_ready();
+    assert_eq!(*CONFIG, [1, 2]);
+    assert_eq!(PORT.try_deref(), Ok(&80));
+}
@@ -1,4 +1,14 @@
-#![cfg_attr(feature="nightly", feature(const_fn))]
+// the statics are accessed without `lazy_static::runtime_ready()`, and are
+// expected to stay uninitialized until then, which eager-all does not do
+#![cfg(not(any(feature="strict", feature="eager-all")))]
+
+// the expansion must keep building when references to a `static mut` are
+// an error, as in the 2024 edition
+#![deny(static_mut_refs)]
+
+// the attributes of a static are not applied to its initializer, which is
+// where `UNSAFE` and `PRE_INIT` keep the forms they were written with
+#![allow(unknown_lints, unnecessary_transmutes, clippy::unused_unit)]
 
 #[macro_use]
 extern crate lazy_static;
@@ -12,7 +22,7 @@ lazy_static! {
 
     /// More documentation!
     #[allow(unused_variables)]
-    #[derive(Copy, Clone, Debug)]
+    #[derive(Copy, Clone)]
     pub static ref STRING: String = "hello".to_string();
 
     static ref HASHMAP: HashMap<u32, &'static str> = {
@@ -33,6 +43,32 @@ lazy_static! {
 
 }
 
+lazy_static! {
+    #[doc = "Documentation through an explicit attribute"]
+    #[cfg(not(any()))]
+    static ref CFG_ENABLED: u32 = 1;
+
+    // This would not compile if the `#[cfg]` was only applied to the type.
+    #[cfg(any())]
+    static ref CFG_ENABLED: u32 = 2;
+
+    /// Doc comment before a `#[cfg]` attribute.
+    #[cfg(any())]
+    #[allow(dead_code)]
+    static ref CFG_DISABLED: NonExistentType = unreachable!();
+}
+
+#[test]
+fn fmt_forwarding() {
+    assert_eq!(format!("{}", STRING), "hello");
+    assert_eq!(format!("{:?}", ARRAY_BOXES), "[1, 2, 3]");
+}
+
+#[test]
+fn test_cfg() {
+    assert_eq!(*CFG_ENABLED, 1);
+}
+
 lazy_static! {
     static ref S1: &'static str = "a";
     static ref S2: &'static str = "b";
@@ -51,6 +87,7 @@ fn times_two(n: u32) -> u32 {
 }
 
 #[test]
+#[allow(clippy::legacy_numeric_constants)]
 fn test_basic() {
     assert_eq!(&**STRING, "hello");
     assert_eq!(*NUMBER, 6);
@@ -68,14 +105,15 @@ fn test_repeat() {
 }
 
 #[test]
+#[allow(clippy::ptr_eq)]
 fn test_meta() {
     // this would not compile if STRING were not marked #[derive(Copy, Clone)]
     let copy_of_string = STRING;
     // just to make sure it was copied
     assert!(&STRING as *const _ != &copy_of_string as *const _);
 
-    // this would not compile if STRING were not marked #[derive(Debug)]
-    assert_eq!(format!("{:?}", STRING), "STRING { __private_field: () }".to_string());
+    // `Debug` is forwarded to the value instead of being derived
+    assert_eq!(format!("{:?}", STRING), "\"hello\"".to_string());
 }
 
 mod visibility {
@@ -84,16 +122,36 @@ mod visibility {
         static ref BAR: Box<u32> = Box::new(98);
     }
 
+    pub mod inner {
+        lazy_static! {
+            pub(in visibility) static ref BAZ: Box<u32> = Box::new(42);
+            pub(crate) static ref QUX: Box<u32> = Box::new(27);
+        }
+    }
+
     #[test]
     fn sub_test() {
         assert_eq!(**FOO, 0);
         assert_eq!(**BAR, 98);
+        assert_eq!(**inner::BAZ, 42);
+        assert_eq!(**inner::QUX, 27);
     }
 }
 
 #[test]
 fn test_visibility() {
     assert_eq!(*visibility::FOO, Box::new(0));
+    assert_eq!(*visibility::inner::QUX, Box::new(27));
+}
+
+// The generated wrapper type of a `pub` static is public as well.
+fn deref_foo(lazy: &'static visibility::FOO) -> &'static u32 {
+    lazy
+}
+
+#[test]
+fn test_visibility_type() {
+    assert_eq!(*deref_foo(&visibility::FOO), 0);
 }
 
 // This should not cause a warning about a missing Copy implementation
@@ -129,9 +187,12 @@ fn item_name_shadowing() {
 }
 
 use std::sync::atomic::AtomicBool;
+use std::sync::atomic::AtomicUsize;
+#[allow(deprecated)]
 use std::sync::atomic::ATOMIC_BOOL_INIT;
 use std::sync::atomic::Ordering::SeqCst;
 
+#[allow(deprecated)]
 static PRE_INIT_FLAG: AtomicBool = ATOMIC_BOOL_INIT;
 
 lazy_static! {
@@ -142,12 +203,66 @@ lazy_static! {
 }
 
 #[test]
+#[allow(clippy::bool_assert_comparison)]
 fn pre_init() {
     assert_eq!(PRE_INIT_FLAG.load(SeqCst), false);
     lazy_static::initialize(&PRE_INIT);
     assert_eq!(PRE_INIT_FLAG.load(SeqCst), true);
 }
 
+static INIT_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+lazy_static! {
+    static ref COUNTED: usize = INIT_COUNT.fetch_add(1, SeqCst);
+}
+
+#[test]
+fn is_initialized() {
+    lazy_static! {
+        static ref NOT_YET: u32 = 1;
+    }
+
+    assert!(!lazy_static::is_initialized(&NOT_YET));
+    assert_eq!(*NOT_YET, 1);
+    assert!(lazy_static::is_initialized(&NOT_YET));
+}
+
+#[test]
+fn get_without_initializing() {
+    lazy_static! {
+        static ref PEEKED: String = "peeked".to_string();
+    }
+
+    assert_eq!(lazy_static::get(&PEEKED), None);
+    assert!(!lazy_static::is_initialized(&PEEKED));
+    assert_eq!(&*PEEKED, "peeked");
+    assert_eq!(lazy_static::get(&PEEKED).map(|s| &s[..]), Some("peeked"));
+}
+
+fn force_all<T: lazy_static::LazyStatic>(statics: &[&T]) -> usize {
+    statics.iter().filter(|lazy| !lazy_static::is_initialized(**lazy))
+        .map(|lazy| lazy_static::initialize(*lazy))
+        .count()
+}
+
+#[test]
+fn generic_over_lazy_statics() {
+    lazy_static! {
+        static ref GENERIC: u32 = 3;
+    }
+
+    assert_eq!(force_all(&[&GENERIC]), 1);
+    assert_eq!(force_all(&[&GENERIC]), 0);
+}
+
+#[test]
+fn initialize_once() {
+    lazy_static::initialize(&COUNTED);
+    lazy_static::initialize(&COUNTED);
+    assert_eq!(*COUNTED, 0);
+    assert_eq!(INIT_COUNT.load(SeqCst), 1);
+}
+
 lazy_static! {
     static ref LIFETIME_NAME: for<'a> fn(&'a u8) = { fn f(_: &u8) {} f };
 }
@@ -156,3 +271,1774 @@ lazy_static! {
 fn lifetime_name() {
     let _ = LIFETIME_NAME;
 }
+
+static FALLIBLE_ATTEMPTS: AtomicUsize = AtomicUsize::new(0);
+
+fn fallible() -> Result<u32, String> {
+    match FALLIBLE_ATTEMPTS.fetch_add(1, SeqCst) {
+        0 => Err("first attempt".to_string()),
+        n => Ok(n as u32),
+    }
+}
+
+lazy_static! {
+    static ref try FALLIBLE: Result<u32, String> = fallible();
+
+    pub static ref try NESTED_ERROR: Result<Vec<u8>, Box<dyn std::error::Error + Send + Sync>> = {
+        let input = "not a number";
+        Ok(vec![input.parse::<u8>()?])
+    };
+}
+
+#[test]
+fn try_deref() {
+    assert_eq!(FALLIBLE.try_deref(), Err("first attempt".to_string()));
+    assert_eq!(FALLIBLE.try_deref(), Ok(&1));
+    assert_eq!(FALLIBLE.try_deref(), Ok(&1));
+    assert_eq!(FALLIBLE_ATTEMPTS.load(SeqCst), 2);
+
+    assert!(NESTED_ERROR.try_deref().is_err());
+}
+
+trait Shape {
+    fn area(&self) -> u32;
+}
+
+struct Square(u32);
+
+impl Shape for Square {
+    fn area(&self) -> u32 {
+        self.0 * self.0
+    }
+}
+
+lazy_static! {
+    static ref BYTES: [u8] = vec![1, 2, 3].into_boxed_slice();
+    pub static ref GREETING: str = format!("hello {}", "world").into_boxed_str();
+    #[lazy(retry_on_panic)]
+    static ref SHAPE: dyn Shape + Send + Sync = Box::new(Square(3));
+    static ref CALLBACK: dyn Fn(u32) -> u32 + Send + Sync = Box::new(|x| x + 1);
+    static ref SIZED_ARRAY: [u8; 2] = [4, 5];
+}
+
+#[test]
+fn unsized_values() {
+    assert_eq!(lazy_static::get(&BYTES), None);
+    let bytes: &'static [u8] = &BYTES;
+    assert_eq!(bytes, [1, 2, 3]);
+    assert_eq!(lazy_static::get(&BYTES), Some(&[1u8, 2, 3][..]));
+    let greeting: &'static str = &GREETING;
+    assert_eq!(greeting, "hello world");
+    assert_eq!(format!("{}", GREETING), "hello world");
+    assert_eq!(SHAPE.area(), 9);
+    assert_eq!(CALLBACK(1), 2);
+    assert_eq!(*SIZED_ARRAY, [4, 5]);
+}
+
+lazy_static! {
+    static ref NAME: String = "lazy".to_string();
+    static ref ROW: [u8] = vec![4, 5, 6].into_boxed_slice();
+    static ref CIRCLE: dyn Shape + Send + Sync = Box::new(Square(2));
+    static ref const WIDTH: u32 = 80;
+}
+
+#[test]
+fn as_ref() {
+    use std::borrow::Borrow;
+
+    fn len<S: AsRef<String>>(s: S) -> usize {
+        s.as_ref().len()
+    }
+    fn sum<B: Borrow<[u8]>>(bytes: &B) -> u32 {
+        bytes.borrow().iter().map(|&b| u32::from(b)).sum()
+    }
+
+    assert_eq!(len(&NAME), 4);
+    assert_eq!(sum(&ROW), 15);
+    assert_eq!(Borrow::<dyn Shape + Send + Sync>::borrow(&CIRCLE).area(), 4);
+    assert_eq!(*AsRef::<u32>::as_ref(&WIDTH), 80);
+}
+
+static INJECTED_INITS: AtomicUsize = AtomicUsize::new(0);
+
+lazy_static! {
+    static ref INJECTED: Vec<String> = {
+        INJECTED_INITS.fetch_add(1, SeqCst);
+        vec!["default".to_string()]
+    };
+    static ref INJECTED_STR: str = "default".to_string().into_boxed_str();
+    static ref const INJECTED_CONST: u8 = 1;
+}
+
+#[test]
+fn set() {
+    assert_eq!(lazy_static::set(&INJECTED, vec!["flag".to_string()]), Ok(()));
+    assert_eq!(*INJECTED, ["flag"]);
+    assert_eq!(lazy_static::set(&INJECTED, vec![]), Err(vec![]));
+    assert_eq!(INJECTED_INITS.load(SeqCst), 0);
+
+    assert_eq!(&*INJECTED_STR, "default");
+    assert_eq!(lazy_static::set(&INJECTED_STR, "late".into()), Err("late".into()));
+    assert_eq!(lazy_static::set(&INJECTED_CONST, 2), Err(2));
+}
+
+lazy_static! {
+    static ref PLUGIN: Vec<String>;
+    pub static ref NEVER_SET: u32;
+}
+
+#[test]
+fn init_with() {
+    assert!(std::panic::catch_unwind(|| PLUGIN.len()).is_err());
+    let suffix = String::from("loaded");
+    PLUGIN.init_with(move || vec![suffix]);
+    assert!(std::panic::catch_unwind(|| PLUGIN.init_with(Vec::new)).is_err());
+    assert_eq!(*PLUGIN, ["loaded"]);
+    assert!(std::panic::catch_unwind(|| PLUGIN.init_with(Vec::new)).is_err());
+    assert_eq!(lazy_static::get(&NEVER_SET), None);
+}
+
+lazy_static! {
+    static ref INITIALIZING: u32;
+}
+
+#[test]
+fn init_with_while_initializing() {
+    INITIALIZING.init_with(|| {
+        // the initializer has been taken out, but the value is not set yet
+        assert!(std::panic::catch_unwind(|| INITIALIZING.init_with(|| 2)).is_err());
+        1
+    });
+    assert_eq!(*INITIALIZING, 1);
+}
+
+const BASE: u32 = 40;
+
+lazy_static! {
+    static ref const CONSTANT: u32 = BASE + 2;
+    pub static ref const CONSTANT_STR: &'static str = "constant";
+}
+
+#[test]
+fn const_static() {
+    assert_eq!(*CONSTANT, 42);
+    assert_eq!(CONSTANT_STR.len(), 8);
+    assert!(lazy_static::is_initialized(&CONSTANT));
+    assert_eq!(lazy_static::get(&CONSTANT), Some(&42));
+}
+
+static ELEMENT_INITS: AtomicUsize = AtomicUsize::new(0);
+
+static SQUARES: lazy_static::sync::LazyArray<usize, 8> = lazy_static::sync::LazyArray::new(|i| {
+    ELEMENT_INITS.fetch_add(1, SeqCst);
+    i * i
+});
+
+#[test]
+fn lazy_array() {
+    use lazy_static::sync::LazyArray;
+
+    assert_eq!(LazyArray::try_get(&SQUARES, 3), None);
+    assert_eq!(SQUARES[3], 9);
+    assert_eq!(SQUARES[3], 9);
+    assert_eq!(LazyArray::get(&SQUARES, 7), Some(&49));
+    assert_eq!(LazyArray::get(&SQUARES, 8), None);
+    assert_eq!(LazyArray::try_get(&SQUARES, 0), None);
+    assert_eq!(ELEMENT_INITS.load(SeqCst), 2);
+}
+
+fn words(text: &str) -> Vec<&str> {
+    lazy_static! {
+        static ref SEPARATORS: Vec<char> = vec![' ', ',', ';'];
+    }
+    text.split(&SEPARATORS[..]).filter(|w| !w.is_empty()).collect()
+}
+
+#[test]
+fn function_scope() {
+    assert_eq!(words("a, b;c"), ["a", "b", "c"]);
+
+    lazy_static! {
+        #[cfg(not(any()))]
+        static ref try PARSED: Result<u8, std::num::ParseIntError> = "7".parse();
+        static ref mut LOCKED: Vec<u8> = Vec::new();
+        static ref rwlock SHARED: u8 = 1;
+        static ref const CONSTANT: u8 = 2;
+        static ref thread_local LOCAL: u8 = 3;
+        #[lazy(retry_on_panic)]
+        static ref RETRIED: u8 = 4;
+    }
+    LOCKED.lock().push(*PARSED.try_deref().unwrap());
+    assert_eq!(*LOCKED.lock(), [7]);
+    assert_eq!(*SHARED.read() + *CONSTANT + LOCAL.with(|l| *l) + *RETRIED, 10);
+}
+
+static CACHED_ATTEMPTS: AtomicUsize = AtomicUsize::new(0);
+
+lazy_static! {
+    #[lazy(cache_error)]
+    static ref try CACHED_ERROR: Result<u32, String> = {
+        CACHED_ATTEMPTS.fetch_add(1, SeqCst);
+        Err("no config".to_string())
+    };
+}
+
+#[test]
+fn try_deref_cache_error() {
+    assert_eq!(CACHED_ERROR.try_deref(), Err(&"no config".to_string()));
+    assert!(std::ptr::eq(CACHED_ERROR.try_deref().unwrap_err(), CACHED_ERROR.try_deref().unwrap_err()));
+    assert_eq!(CACHED_ATTEMPTS.load(SeqCst), 1);
+}
+
+lazy_static! {
+    #[lazy(fallback = 10)]
+    static ref try WORKERS: Result<u32, std::num::ParseIntError> = "many".parse();
+
+    #[lazy(fallback = 1)]
+    pub static ref try THREADS: Result<u32, String> = Ok(4);
+
+    #[lazy(fallback = Vec::new())]
+    static ref try PANICKING: Result<Vec<u8>, String> = panic!("unreachable backend");
+}
+
+mod fallback_options {
+    lazy_static! {
+        #[lazy(cache_padded)]
+        #[lazy(fallback = 2)]
+        #[lazy(accessor = padded_workers)]
+        pub static ref try PADDED: Result<u32, std::num::ParseIntError> = "many".parse();
+    }
+}
+
+#[test]
+fn fallback_with_options() {
+    assert_eq!(*fallback_options::PADDED, 2);
+    assert!(fallback_options::PADDED.used_fallback());
+    assert_eq!(*fallback_options::padded_workers(), 2);
+}
+
+#[test]
+fn fallback() {
+    assert_eq!(*WORKERS, 10);
+    assert!(WORKERS.used_fallback());
+    assert!(!THREADS.used_fallback());
+    assert_eq!(*THREADS, 4);
+    assert_eq!(lazy_static::get(&THREADS), Some(&4));
+}
+
+#[test]
+#[cfg(not(feature="spin_no_std"))]
+fn fallback_on_panic() {
+    assert!(PANICKING.is_empty());
+    assert!(PANICKING.used_fallback());
+}
+
+#[cfg(not(feature="spin_no_std"))]
+mod deadline {
+    use std::sync::atomic::AtomicBool;
+    use std::sync::atomic::Ordering::SeqCst;
+    use std::thread;
+    use std::time::Duration;
+
+    static RELEASED: AtomicBool = AtomicBool::new(false);
+
+    lazy_static! {
+        #[lazy(deadline = Duration::from_millis(20), fallback = String::from("fallback"))]
+        static ref BLOCKED: String = {
+            while !RELEASED.load(SeqCst) {
+                thread::sleep(Duration::from_millis(1));
+            }
+            String::from("real")
+        };
+
+        #[lazy(deadline = Duration::from_secs(60), fallback = 0)]
+        static ref QUICK: u32 = 1;
+
+        #[lazy(deadline = Duration::from_secs(60), fallback = 2)]
+        static ref FAILING: u32 = connect();
+
+        #[lazy(deadline = Duration::from_secs(60), fallback = 3)]
+        pub(crate) static ref SHARED: u32 = 4;
+    }
+
+    fn connect() -> u32 {
+        panic!("unreachable backend")
+    }
+
+    #[test]
+    fn deadline() {
+        let fallback: &'static String = &BLOCKED;
+        assert_eq!(fallback, "fallback");
+        assert!(BLOCKED.is_fallback());
+        assert!(!lazy_static::is_initialized(&BLOCKED));
+        RELEASED.store(true, SeqCst);
+        lazy_static::initialize(&BLOCKED);
+        assert_eq!(*BLOCKED, "real");
+        assert!(!BLOCKED.is_fallback());
+        assert_eq!(fallback, "fallback");
+    }
+
+    #[test]
+    fn deadline_met() {
+        assert!(!QUICK.is_fallback());
+        assert_eq!(*QUICK, 1);
+        assert!(!QUICK.is_fallback());
+    }
+
+    #[test]
+    fn deadline_panic() {
+        assert_eq!(*FAILING, 2);
+        assert!(FAILING.is_fallback());
+        assert_eq!(*FAILING, 2);
+    }
+}
+
+#[test]
+#[cfg(not(feature="spin_no_std"))]
+fn deadline_visibility() {
+    assert_eq!(*deadline::SHARED, 4);
+    assert!(!deadline::SHARED.is_fallback());
+}
+
+static FLAKY_ATTEMPTS: AtomicUsize = AtomicUsize::new(0);
+
+lazy_static! {
+    #[lazy(retry_on_panic)]
+    static ref FLAKY: usize = match FLAKY_ATTEMPTS.fetch_add(1, SeqCst) {
+        0 => panic!("first attempt"),
+        n => n,
+    };
+}
+
+#[test]
+fn retry_on_panic() {
+    assert!(std::panic::catch_unwind(|| *FLAKY).is_err());
+    assert!(!lazy_static::is_initialized(&FLAKY));
+    assert_eq!(*FLAKY, 1);
+    assert_eq!(*FLAKY, 1);
+    assert_eq!(FLAKY_ATTEMPTS.load(SeqCst), 2);
+}
+
+#[cfg(not(feature="spin_no_std"))]
+lazy_static! {
+    static ref BROKEN: u32 = "broken".parse().unwrap_or_else(|_| panic!("no config for {}", "broken"));
+}
+
+#[test]
+#[cfg(not(feature="spin_no_std"))]
+fn poisoned_message() {
+    fn message(payload: Box<dyn std::any::Any + Send>) -> String {
+        match payload.downcast::<String>() {
+            Ok(message) => *message,
+            Err(payload) => String::from(*payload.downcast::<&'static str>().unwrap()),
+        }
+    }
+
+    let first = message(std::panic::catch_unwind(|| *BROKEN).unwrap_err());
+    assert_eq!(first, "no config for broken");
+    for _ in 0..2 {
+        let later = message(std::panic::catch_unwind(|| *BROKEN).unwrap_err());
+        assert_eq!(later, "lazy static `test::BROKEN` is poisoned, as its initializer panicked: no config for broken");
+    }
+}
+
+static DEPENDENCY_ORDER: AtomicUsize = AtomicUsize::new(0);
+
+mod dependency {
+    lazy_static! {
+        pub static ref FIRST: usize = ::DEPENDENCY_ORDER.fetch_add(1, ::SeqCst);
+    }
+}
+
+lazy_static! {
+    static ref SECOND: usize = DEPENDENCY_ORDER.fetch_add(1, SeqCst);
+
+    #[lazy(depends_on = [dependency::FIRST, SECOND])]
+    static ref DEPENDENT: usize = DEPENDENCY_ORDER.fetch_add(1, SeqCst);
+}
+
+#[test]
+fn depends_on() {
+    assert_eq!(*DEPENDENT, 2);
+    assert_eq!(*dependency::FIRST, 0);
+    assert_eq!(*SECOND, 1);
+}
+
+static BACKEND_RUNS: AtomicUsize = AtomicUsize::new(0);
+static BACKEND_CALLS: AtomicUsize = AtomicUsize::new(0);
+
+struct CountingOnce(std::sync::Once);
+
+unsafe impl lazy_static::backend::OnceBackend for CountingOnce {
+    const INIT: Self = CountingOnce(std::sync::Once::new());
+
+    fn call_once<F: FnOnce()>(&self, f: F) {
+        BACKEND_CALLS.fetch_add(1, SeqCst);
+        self.0.call_once(|| {
+            BACKEND_RUNS.fetch_add(1, SeqCst);
+            f()
+        })
+    }
+
+    fn is_completed(&self) -> bool {
+        self.0.is_completed()
+    }
+}
+
+lazy_static! {
+    #[lazy(backend = CountingOnce)]
+    /// Documentation after a backend attribute.
+    static ref CUSTOM_BACKEND: u32 = 5;
+}
+
+#[test]
+fn custom_backend() {
+    assert_eq!(lazy_static::get(&CUSTOM_BACKEND), None);
+    assert_eq!(*CUSTOM_BACKEND, 5);
+    assert_eq!(*CUSTOM_BACKEND, 5);
+    assert!(lazy_static::is_initialized(&CUSTOM_BACKEND));
+    assert_eq!(BACKEND_RUNS.load(SeqCst), 1);
+    // initialized statics do not enter the once guard again
+    assert_eq!(BACKEND_CALLS.load(SeqCst), 1);
+}
+
+#[cfg(not(feature="spin_no_std"))]
+lazy_static! {
+    #[lazy(backend = "std")]
+    static ref NAMED_BACKEND: u32 = 6;
+}
+
+#[cfg(not(feature="spin_no_std"))]
+#[test]
+fn named_backend() {
+    fn backend<T, B: lazy_static::backend::OnceBackend + 'static>(_: &lazy_static::lazy::Lazy<T, B>) -> std::any::TypeId {
+        std::any::TypeId::of::<B>()
+    }
+
+    assert_eq!(*NAMED_BACKEND, 6);
+    assert_eq!(backend(NAMED_BACKEND::__static_ref_lazy()), std::any::TypeId::of::<std::sync::Once>());
+}
+
+static SECTIONS: AtomicUsize = AtomicUsize::new(0);
+
+struct CountingSection;
+
+unsafe impl lazy_static::backend::CriticalSection for CountingSection {
+    fn with<R, F: FnOnce() -> R>(f: F) -> R {
+        static LOCK: std::sync::Mutex<()> = std::sync::Mutex::new(());
+        let _lock = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        SECTIONS.fetch_add(1, SeqCst);
+        f()
+    }
+}
+
+lazy_static! {
+    #[lazy(backend = lazy_static::backend::CriticalSectionOnce<CountingSection>)]
+    static ref CRITICAL_SECTION: Vec<u32> = vec![1, 2];
+}
+
+#[test]
+fn critical_section_backend() {
+    let threads: Vec<_> = (0..4).map(|_| std::thread::spawn(|| CRITICAL_SECTION.len())).collect();
+    for thread in threads {
+        assert_eq!(thread.join().unwrap(), 2);
+    }
+    let sections = SECTIONS.load(SeqCst);
+    assert!((1..=4).contains(&sections));
+    assert_eq!(*CRITICAL_SECTION, vec![1, 2]);
+    assert_eq!(SECTIONS.load(SeqCst), sections);
+}
+
+lazy_static! {
+    #[lazy(cache_padded)]
+    static ref CACHE_PADDED: u8 = 7;
+
+    #[lazy(retry_on_panic)]
+    #[lazy(cache_padded)]
+    static ref CACHE_PADDED_RETRY: u8 = 8;
+}
+
+#[test]
+fn cache_padded() {
+    let align = std::mem::align_of::<lazy_static::sync::CachePadded<()>>();
+    assert!(align >= 64);
+    assert_eq!(*CACHE_PADDED, 7);
+    assert_eq!(CACHE_PADDED::__static_ref_lazy() as *const _ as usize % align, 0);
+    assert_eq!(*CACHE_PADDED_RETRY, 8);
+    assert_eq!(CACHE_PADDED_RETRY::__static_ref_lazy() as *const _ as usize % align, 0);
+}
+
+#[cfg(target_os = "linux")]
+mod placement {
+    // the linker defines the bounds of sections that are named like
+    // identifiers
+    extern "C" {
+        static __start_lazy_static_placement: u8;
+        static __stop_lazy_static_placement: u8;
+    }
+
+    lazy_static! {
+        #[lazy(link_section = "lazy_static_placement")]
+        #[lazy(used)]
+        static ref PLACED: u32 = 7;
+
+        #[lazy(storage(#[link_section = "lazy_static_placement"] #[used]))]
+        #[lazy(cache_padded)]
+        static ref PLACED_PADDED: u32 = 8;
+    }
+
+    fn in_section<T>(value: *const T) -> bool {
+        let value = value as usize;
+        let (start, stop) = unsafe {
+            (&__start_lazy_static_placement as *const u8 as usize,
+             &__stop_lazy_static_placement as *const u8 as usize)
+        };
+        start <= value && value < stop
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn storage_placement() {
+        assert_eq!(*PLACED, 7);
+        assert_eq!(*PLACED_PADDED, 8);
+        assert!(in_section(PLACED::__static_ref_lazy()));
+        assert!(in_section(PLACED_PADDED::__static_ref_lazy()));
+        assert!(in_section(&*PLACED));
+    }
+}
+
+lazy_static! {
+    #[lazy(ffi)]
+    static ref EXPORTED: [u32; 2] = [3, 4];
+
+    #[lazy(ffi = "lazy_static_test_exported_name")]
+    #[lazy(cache_padded)]
+    pub static ref EXPORTED_NAMED: u64 = 5;
+}
+
+extern "C" {
+    fn EXPORTED_get() -> *const [u32; 2];
+    fn lazy_static_test_exported_name() -> *const u64;
+}
+
+#[test]
+fn ffi() {
+    unsafe {
+        let exported = EXPORTED_get();
+        assert_eq!(*exported, [3, 4]);
+        assert!(std::ptr::eq(exported, &*EXPORTED));
+        assert_eq!(*lazy_static_test_exported_name(), 5);
+        assert!(std::ptr::eq(lazy_static_test_exported_name(), &*EXPORTED_NAMED));
+    }
+}
+
+mod accessors {
+    lazy_static! {
+        #[lazy(accessor = greeting)]
+        #[lazy(cache_padded)]
+        pub static ref GREETING: str = String::from("hello").into_boxed_str();
+
+        #[lazy(accessor_only = answer)]
+        pub static ref ANSWER: u32 = 42;
+    }
+
+    pub fn answer_ref() -> &'static u32 {
+        &ANSWER
+    }
+}
+
+#[test]
+fn accessors() {
+    assert_eq!(accessors::greeting(), "hello");
+    assert!(std::ptr::eq(accessors::greeting(), &*accessors::GREETING));
+    assert_eq!(*accessors::answer(), 42);
+    assert!(std::ptr::eq(accessors::answer(), accessors::answer_ref()));
+}
+
+mod initializers {
+    pub fn squares() -> Vec<u64> {
+        (0..10).map(|n| n * n).collect()
+    }
+}
+
+static FN_INIT_CALLS: AtomicUsize = AtomicUsize::new(0);
+
+fn fail_once() -> u32 {
+    if FN_INIT_CALLS.fetch_add(1, SeqCst) == 0 {
+        panic!("first call");
+    }
+    9
+}
+
+lazy_static! {
+    static ref FN_SQUARES: Vec<u64> = fn initializers::squares;
+
+    #[lazy(retry_on_panic)]
+    static ref FN_RETRIED: u32 = fn fail_once;
+}
+
+#[test]
+fn fn_initializer() {
+    assert_eq!(FN_SQUARES[9], 81);
+    assert!(std::panic::catch_unwind(|| *FN_RETRIED).is_err());
+    assert_eq!(*FN_RETRIED, 9);
+    assert_eq!(FN_INIT_CALLS.load(SeqCst), 2);
+}
+
+static THREAD_LOCAL_INITS: AtomicUsize = AtomicUsize::new(0);
+
+lazy_static! {
+    static ref thread_local PER_THREAD: std::cell::Cell<usize> =
+        std::cell::Cell::new(THREAD_LOCAL_INITS.fetch_add(1, SeqCst));
+}
+
+#[test]
+fn thread_local() {
+    PER_THREAD.with(|value| value.set(value.get() + 100));
+    let here = PER_THREAD.with(|value| value.get());
+    let there = std::thread::spawn(|| PER_THREAD.with(|value| value.get())).join().unwrap();
+    assert!(here >= 100);
+    assert!(there < 100);
+    assert_ne!(here - 100, there);
+}
+
+static LAZY_TYPE: lazy_static::sync::Lazy<Vec<u32>> = lazy_static::sync::Lazy::new(|| vec![1, 2, 3]);
+
+#[test]
+fn lazy_type() {
+    assert_eq!(LAZY_TYPE.len(), 3);
+    assert_eq!(*lazy_static::sync::Lazy::force(&LAZY_TYPE), vec![1, 2, 3]);
+
+    let calls = std::cell::Cell::new(0);
+    let local = lazy_static::sync::Lazy::new(|| {
+        calls.set(calls.get() + 1);
+        String::from("local")
+    });
+    assert_eq!(calls.get(), 0);
+    assert!(!lazy_static::sync::Lazy::is_initialized(&local));
+    assert_eq!(&*local, "local");
+    assert_eq!(&*local, "local");
+    assert_eq!(calls.get(), 1);
+    assert!(lazy_static::sync::Lazy::is_initialized(&local));
+}
+
+#[test]
+fn unsync() {
+    use lazy_static::unsync::{Lazy, OnceCell};
+
+    let calls = std::cell::Cell::new(0);
+    let mut lazy = Lazy::new(|| {
+        calls.set(calls.get() + 1);
+        vec![1, 2]
+    });
+    assert!(!Lazy::is_initialized(&lazy));
+    assert_eq!(lazy.len(), 2);
+    assert_eq!(*lazy, vec![1, 2]);
+    assert_eq!(calls.get(), 1);
+    Lazy::get_mut(&mut lazy).unwrap().push(3);
+    assert_eq!(Lazy::take(&mut lazy), Some(vec![1, 2, 3]));
+    let taken = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| lazy.len())).unwrap_err();
+    assert_eq!(taken.downcast_ref::<&str>(), Some(&"Lazy instance has had its value taken"));
+    assert_eq!(Lazy::into_inner(lazy), None);
+
+    let cell = OnceCell::new();
+    assert_eq!(cell.get(), None);
+    assert_eq!(cell.set(1), Ok(()));
+    assert_eq!(cell.set(2), Err(2));
+    assert_eq!(cell.get_or_init(|| 3), &1);
+
+    let reentrant: OnceCell<u32> = OnceCell::new();
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        reentrant.get_or_init(|| {
+            reentrant.set(1).unwrap();
+            2
+        });
+    }));
+    assert!(result.is_err());
+    assert_eq!(reentrant.get(), Some(&1));
+}
+
+#[test]
+fn get_mut() {
+    use lazy_static::sync::{Lazy, LazyArray, OnceCell};
+
+    let mut lazy: Lazy<Vec<u32>> = Lazy::new(|| vec![1]);
+    assert_eq!(Lazy::get_mut(&mut lazy), None);
+    Lazy::force(&lazy);
+    Lazy::get_mut(&mut lazy).unwrap().push(2);
+    assert_eq!(*lazy, vec![1, 2]);
+
+    let mut cell = OnceCell::new();
+    assert_eq!(cell.get_mut(), None);
+    cell.set(1).unwrap();
+    *cell.get_mut().unwrap() += 1;
+    assert_eq!(cell.get(), Some(&2));
+
+    let mut array: LazyArray<usize, 2> = LazyArray::new(|i| i);
+    assert_eq!(LazyArray::get_mut(&mut array, 1), None);
+    assert_eq!(array[1], 1);
+    *LazyArray::get_mut(&mut array, 1).unwrap() = 5;
+    assert_eq!(array[1], 5);
+    assert_eq!(LazyArray::get_mut(&mut array, 2), None);
+}
+
+#[test]
+fn into_inner_take() {
+    use lazy_static::sync::{Lazy, OnceCell};
+
+    let lazy: Lazy<String> = Lazy::new(|| String::from("value"));
+    assert_eq!(Lazy::into_inner(lazy), None);
+    let lazy: Lazy<String> = Lazy::new(|| String::from("value"));
+    Lazy::force(&lazy);
+    assert_eq!(Lazy::into_inner(lazy), Some(String::from("value")));
+
+    let mut lazy: Lazy<String> = Lazy::new(|| String::from("value"));
+    Lazy::force(&lazy);
+    assert_eq!(Lazy::take(&mut lazy), Some(String::from("value")));
+    assert_eq!(Lazy::try_get(&lazy), None);
+    assert_eq!(Lazy::take(&mut lazy), None);
+    let taken = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| lazy.len())).unwrap_err();
+    assert_eq!(taken.downcast_ref::<&str>(), Some(&"Lazy instance has had its value taken"));
+
+    let mut cell = OnceCell::new();
+    cell.set(1).unwrap();
+    assert_eq!(cell.take(), Some(1));
+    assert_eq!(cell.get(), None);
+    cell.set(2).unwrap();
+    assert_eq!(cell.into_inner(), Some(2));
+}
+
+#[test]
+fn drop_value() {
+    use lazy_static::sync::Lazy;
+    use std::rc::Rc;
+
+    let value = Rc::new(());
+    let lazy: Lazy<Rc<()>, _> = Lazy::new(|| value.clone());
+    drop(lazy);
+    assert_eq!(Rc::strong_count(&value), 1);
+
+    let lazy = Lazy::new(|| value.clone());
+    Lazy::force(&lazy);
+    assert_eq!(Rc::strong_count(&value), 2);
+    drop(lazy);
+    assert_eq!(Rc::strong_count(&value), 1);
+}
+
+static ONCE_CELL: lazy_static::sync::OnceCell<u32> = lazy_static::sync::OnceCell::new();
+
+#[test]
+fn once_cell() {
+    assert_eq!(ONCE_CELL.get(), None);
+    assert_eq!(ONCE_CELL.set(1), Ok(()));
+    assert_eq!(ONCE_CELL.set(2), Err(2));
+    assert_eq!(ONCE_CELL.get_or_init(|| 3), &1);
+    assert_eq!(ONCE_CELL.get(), Some(&1));
+
+    let cell = lazy_static::sync::OnceCell::new();
+    assert_eq!(cell.get_or_init(|| "init"), &"init");
+    assert_eq!(cell.set("set"), Err("set"));
+}
+
+lazy_static! {
+    /// A global counter behind a mutex.
+    pub static ref mut COUNTER: u32 = 10;
+}
+
+#[test]
+fn mutable_static() {
+    let threads: Vec<_> = (0..4).map(|_| std::thread::spawn(|| {
+        *COUNTER.lock() += 1;
+    })).collect();
+    for thread in threads {
+        thread.join().unwrap();
+    }
+    assert_eq!(*COUNTER.lock(), 14);
+}
+
+lazy_static! {
+    static ref rwlock FLAGS: Vec<&'static str> = vec!["a"];
+}
+
+#[test]
+fn rwlock_static() {
+    {
+        let first = FLAGS.read();
+        let second = FLAGS.read();
+        assert_eq!(first.len(), second.len());
+    }
+    FLAGS.write().push("b");
+    assert_eq!(&*FLAGS.read(), &["a", "b"]);
+}
+
+mod async_init {
+    use std::future::Future;
+    use std::pin::Pin;
+    use std::sync::{mpsc, Arc, Mutex};
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering::SeqCst};
+    use std::task::{Context, Poll, Wake, Waker};
+    use std::thread::{self, Thread};
+    use lazy_static::future::AsyncLazy;
+
+    struct ThreadWaker(Thread);
+
+    impl Wake for ThreadWaker {
+        fn wake(self: Arc<Self>) {
+            self.0.unpark();
+        }
+    }
+
+    fn block_on<F: Future>(future: F) -> F::Output {
+        let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+        let mut cx = Context::from_waker(&waker);
+        let mut future = Box::pin(future);
+        loop {
+            match future.as_mut().poll(&mut cx) {
+                Poll::Ready(output) => return output,
+                Poll::Pending => thread::park(),
+            }
+        }
+    }
+
+    /// A future that is pending on its first poll.
+    struct YieldOnce(bool, u32);
+
+    impl Future for YieldOnce {
+        type Output = u32;
+
+        fn poll(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<u32> {
+            if self.0 {
+                Poll::Ready(self.1)
+            } else {
+                self.0 = true;
+                cx.waker().wake_by_ref();
+                Poll::Pending
+            }
+        }
+    }
+
+    static STARTED: AtomicUsize = AtomicUsize::new(0);
+
+    fn start() -> YieldOnce {
+        STARTED.fetch_add(1, SeqCst);
+        YieldOnce(false, 7)
+    }
+
+    lazy_static! {
+        static ref async READY: &'static str = std::future::ready("ready");
+        static ref async YIELDING: u32 = start();
+    }
+
+    #[test]
+    fn async_static() {
+        assert_eq!(*block_on(READY.get()), "ready");
+        assert_eq!(*READY.blocking_get(), "ready");
+        assert_eq!(*block_on(YIELDING.get()), 7);
+        assert_eq!(*block_on(YIELDING.get()), 7);
+        assert_eq!(STARTED.load(SeqCst), 1);
+    }
+
+    /// A future that is pending until it is opened, keeping the waker it
+    /// was last polled with.
+    struct Gate(Arc<Mutex<(bool, Option<Waker>)>>);
+
+    impl Future for Gate {
+        type Output = u32;
+
+        fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<u32> {
+            let mut gate = self.0.lock().unwrap();
+            if gate.0 {
+                Poll::Ready(3)
+            } else {
+                gate.1 = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+
+    struct Flag(AtomicBool);
+
+    impl Wake for Flag {
+        fn wake(self: Arc<Self>) {
+            self.0.store(true, SeqCst);
+        }
+    }
+
+    #[test]
+    fn async_cancelled() {
+        let gate = Arc::new(Mutex::new((false, None)));
+        let future = gate.clone();
+        let lazy = AsyncLazy::new(move || Gate(future));
+
+        let waiting = Arc::new(Flag(AtomicBool::new(false)));
+        let waiting_waker = Waker::from(waiting.clone());
+        let mut waiting_get = Box::pin(lazy.get());
+        assert!(waiting_get.as_mut().poll(&mut Context::from_waker(&waiting_waker)).is_pending());
+
+        // the task that polled the future last is cancelled
+        let cancelled_waker = Waker::from(Arc::new(Flag(AtomicBool::new(false))));
+        let mut cancelled_get = Box::pin(lazy.get());
+        assert!(cancelled_get.as_mut().poll(&mut Context::from_waker(&cancelled_waker)).is_pending());
+        drop(cancelled_get);
+
+        let waker = {
+            let mut gate = gate.lock().unwrap();
+            gate.0 = true;
+            gate.1.take().unwrap()
+        };
+        waker.wake();
+        assert!(waiting.0.load(SeqCst));
+        match waiting_get.as_mut().poll(&mut Context::from_waker(&waiting_waker)) {
+            Poll::Ready(value) => assert_eq!(*value, 3),
+            Poll::Pending => panic!("the initialization was not continued"),
+        }
+    }
+
+    #[test]
+    fn async_poll_does_not_block() {
+        let (entered_tx, entered_rx) = mpsc::channel();
+        let (release_tx, release_rx) = mpsc::channel();
+        let lazy = AsyncLazy::new(move || std::future::poll_fn(move |_| {
+            entered_tx.send(()).unwrap();
+            release_rx.recv().unwrap();
+            Poll::Ready(5)
+        }));
+
+        thread::scope(|s| {
+            let polling = s.spawn(|| *block_on(lazy.get()));
+            entered_rx.recv().unwrap();
+            // the future is being polled by the other thread
+            let waiting = Arc::new(Flag(AtomicBool::new(false)));
+            let waker = Waker::from(waiting.clone());
+            let mut get = Box::pin(lazy.get());
+            assert!(get.as_mut().poll(&mut Context::from_waker(&waker)).is_pending());
+            release_tx.send(()).unwrap();
+            assert_eq!(polling.join().unwrap(), 5);
+            assert!(waiting.0.load(SeqCst));
+            assert_eq!(get.as_mut().poll(&mut Context::from_waker(&waker)), Poll::Ready(&5));
+        });
+    }
+
+    #[test]
+    fn async_woken_during_poll() {
+        let (entered_tx, entered_rx) = mpsc::channel();
+        let (release_tx, release_rx) = mpsc::channel();
+        let mut polled = false;
+        let lazy = AsyncLazy::new(move || std::future::poll_fn(move |cx| {
+            if polled {
+                return Poll::Ready(9);
+            }
+            polled = true;
+            // the waiters are woken before the other task registers
+            cx.waker().wake_by_ref();
+            entered_tx.send(()).unwrap();
+            release_rx.recv().unwrap();
+            Poll::Pending
+        }));
+
+        thread::scope(|s| {
+            let polling = s.spawn(|| {
+                let waker = Waker::from(Arc::new(Flag(AtomicBool::new(false))));
+                let mut get = Box::pin(lazy.get());
+                // the task is cancelled after this poll
+                get.as_mut().poll(&mut Context::from_waker(&waker)).is_pending()
+            });
+            entered_rx.recv().unwrap();
+            let waiting = Arc::new(Flag(AtomicBool::new(false)));
+            let waker = Waker::from(waiting.clone());
+            let mut get = Box::pin(lazy.get());
+            assert!(get.as_mut().poll(&mut Context::from_waker(&waker)).is_pending());
+            release_tx.send(()).unwrap();
+            assert!(polling.join().unwrap());
+            assert!(waiting.0.load(SeqCst), "the waiting task was not woken");
+            assert_eq!(get.as_mut().poll(&mut Context::from_waker(&waker)), Poll::Ready(&9));
+        });
+    }
+
+    #[test]
+    fn async_resumed_after_cancel() {
+        let gate = Arc::new(Mutex::new((false, None)));
+        let future = gate.clone();
+        let lazy = AsyncLazy::new(move || Gate(future));
+
+        let waker = Waker::from(Arc::new(Flag(AtomicBool::new(false))));
+        let mut cancelled_get = Box::pin(lazy.get());
+        assert!(cancelled_get.as_mut().poll(&mut Context::from_waker(&waker)).is_pending());
+        drop(cancelled_get);
+
+        gate.lock().unwrap().0 = true;
+        assert_eq!(*block_on(lazy.get()), 3);
+        assert_eq!(lazy.try_get(), Some(&3));
+    }
+}
+
+// Miri reports the leaked values as memory leaks
+#[test]
+#[cfg(not(feature="spin_no_std"))]
+#[cfg_attr(miri, ignore)]
+fn leak() {
+    fn greeting(name: &str) -> &'static str {
+        lazy_static::leak(format!("hello {}", name))
+    }
+
+    let first = greeting("first");
+    assert_eq!(first, "hello first");
+    assert_eq!(greeting("second"), "hello second");
+    assert_eq!(first, "hello first");
+}
+
+#[cfg(all(unix, not(feature="spin_no_std")))]
+mod fork {
+    use std::sync::atomic::{AtomicU32, AtomicUsize, Ordering::SeqCst};
+
+    extern "C" {
+        fn fork() -> i32;
+        fn waitpid(pid: i32, status: *mut i32, options: i32) -> i32;
+        fn _exit(status: i32) -> !;
+    }
+
+    static ORDER: AtomicUsize = AtomicUsize::new(0);
+
+    struct Resource {
+        pid: AtomicU32,
+        reopened_as: AtomicUsize,
+    }
+
+    impl Resource {
+        fn new() -> Self {
+            Resource {
+                pid: AtomicU32::new(std::process::id()),
+                reopened_as: AtomicUsize::new(0),
+            }
+        }
+
+        fn reopen(&self) {
+            self.pid.store(std::process::id(), SeqCst);
+            self.reopened_as.store(ORDER.fetch_add(1, SeqCst) + 1, SeqCst);
+        }
+    }
+
+    lazy_static! {
+        #[lazy(on_fork_child = Resource::reopen)]
+        static ref FIRST: Resource = Resource::new();
+        #[lazy(on_fork_child = |r: &Resource| r.reopen())]
+        static ref SECOND: Resource = Resource::new();
+        #[lazy(on_fork_child = |_: &Resource| unsafe { _exit(3) })]
+        static ref UNUSED: Resource = Resource::new();
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn hooks_run_in_child() {
+        let parent = std::process::id();
+        let _ = &*FIRST;
+        let _ = &*SECOND;
+        let pid = unsafe { fork() };
+        assert!(pid >= 0);
+        if pid == 0 {
+            // only async-signal-safe calls are made in the child
+            let ok = FIRST.pid.load(SeqCst) != parent
+                && FIRST.reopened_as.load(SeqCst) == 1
+                && SECOND.reopened_as.load(SeqCst) == 2;
+            unsafe { _exit(if ok { 0 } else { 1 }) };
+        }
+        let mut status = 0;
+        assert_eq!(unsafe { waitpid(pid, &mut status, 0) }, pid);
+        assert_eq!(status, 0);
+        assert_eq!(FIRST.pid.load(SeqCst), parent);
+        assert_eq!(FIRST.reopened_as.load(SeqCst), 0);
+        assert!(!lazy_static::is_initialized(&UNUSED));
+    }
+
+    #[cfg(feature="test-support")]
+    static REOPENED: AtomicUsize = AtomicUsize::new(0);
+
+    #[cfg(feature="test-support")]
+    lazy_static! {
+        #[lazy(on_fork_child = |_: &Resource| { REOPENED.fetch_add(1, SeqCst); })]
+        static ref RESET: Resource = Resource::new();
+    }
+
+    #[test]
+    #[cfg(feature="test-support")]
+    #[cfg_attr(miri, ignore)]
+    fn hook_runs_once_after_reset() {
+        for _ in 0..3 {
+            let _ = &*RESET;
+            unsafe { lazy_static::reset(&RESET) };
+        }
+        let _ = &*RESET;
+        let pid = unsafe { fork() };
+        assert!(pid >= 0);
+        if pid == 0 {
+            unsafe { _exit(if REOPENED.load(SeqCst) == 1 { 0 } else { 1 }) };
+        }
+        let mut status = 0;
+        assert_eq!(unsafe { waitpid(pid, &mut status, 0) }, pid);
+        assert_eq!(status, 0);
+    }
+}
+
+#[cfg(not(feature="spin_no_std"))]
+mod drop_at_exit {
+    use std::env;
+    use std::process::Command;
+
+    struct PrintOnDrop(&'static str);
+
+    impl Drop for PrintOnDrop {
+        fn drop(&mut self) {
+            println!("dropped {}", self.0);
+        }
+    }
+
+    lazy_static! {
+        #[lazy(unsafe(drop_at_exit))]
+        static ref FIRST: PrintOnDrop = PrintOnDrop("first");
+        #[lazy(unsafe(drop_at_exit))]
+        static ref SECOND: PrintOnDrop = PrintOnDrop("second");
+    }
+
+    #[test]
+    fn drop_at_exit_child() {
+        if env::var_os("LAZY_STATIC_DROP_AT_EXIT").is_none() {
+            return;
+        }
+        let _ = &*FIRST;
+        let _ = unsafe { lazy_static::leak_dropped_at_exit(PrintOnDrop("leaked")) };
+        let _ = &*SECOND;
+        ::std::process::exit(0);
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn drop_at_exit() {
+        let output = Command::new(env::current_exe().unwrap())
+            .args(["--exact", "drop_at_exit::drop_at_exit_child", "--nocapture", "--test-threads=1"])
+            .env("LAZY_STATIC_DROP_AT_EXIT", "1")
+            .output()
+            .unwrap();
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        assert!(stdout.ends_with("dropped second\ndropped leaked\ndropped first\n"), "{}", stdout);
+    }
+}
+
+#[cfg(not(feature="spin_no_std"))]
+mod cycle {
+    lazy_static! {
+        static ref CYCLE_A: u32 = *CYCLE_B + 1;
+        static ref CYCLE_B: u32 = *CYCLE_A + 1;
+    }
+
+    #[test]
+    fn reentrant_initialization_panics() {
+        let err = std::panic::catch_unwind(|| *CYCLE_A).unwrap_err();
+        let message = err.downcast::<String>().unwrap();
+        assert!(message.ends_with("cycle::CYCLE_A -> test::cycle::CYCLE_B -> test::cycle::CYCLE_A"), "{}", message);
+    }
+
+    static BARRIER: std::sync::Barrier = std::sync::Barrier::new(2);
+
+    lazy_static! {
+        static ref CROSS_A: u32 = {
+            BARRIER.wait();
+            *CROSS_B + 1
+        };
+        static ref CROSS_B: u32 = {
+            BARRIER.wait();
+            *CROSS_A + 1
+        };
+    }
+
+    #[test]
+    fn cross_thread_cycle_panics() {
+        let a = std::thread::spawn(|| *CROSS_A);
+        let b = std::thread::spawn(|| *CROSS_B);
+        let messages: Vec<String> = vec![a.join().unwrap_err(), b.join().unwrap_err()]
+            .into_iter()
+            .map(|err| match err.downcast::<String>() {
+                Ok(message) => *message,
+                Err(err) => err.downcast::<&str>().map(|message| message.to_string()).unwrap_or_default(),
+            })
+            .collect();
+        assert!(messages.iter().any(|message| message.contains("another thread waiting for this one")), "{:?}", messages);
+    }
+}
+
+#[cfg(not(feature="spin_no_std"))]
+mod timeout {
+    use std::thread;
+    use std::time::Duration;
+    use lazy_static::sync::Lazy;
+
+    lazy_static! {
+        static ref SLOW: u32 = {
+            thread::sleep(Duration::from_millis(200));
+            1
+        };
+    }
+
+    static SLOW_LAZY: Lazy<u32> = Lazy::new(|| {
+        thread::sleep(Duration::from_millis(200));
+        2
+    });
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn get_timeout() {
+        let handle = thread::spawn(|| *SLOW);
+        thread::sleep(Duration::from_millis(50));
+        assert_eq!(lazy_static::get(&SLOW), None);
+        let err = lazy_static::get_timeout(&SLOW, Duration::from_millis(10)).unwrap_err();
+        assert_eq!(err.to_string(), "timed out waiting for the initialization of a lazy static");
+        assert_eq!(lazy_static::get_timeout(&SLOW, Duration::from_secs(10)), Ok(&1));
+        assert_eq!(handle.join().unwrap(), 1);
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn get_timeout_lazy_type() {
+        let handle = thread::spawn(|| *SLOW_LAZY);
+        thread::sleep(Duration::from_millis(50));
+        assert_eq!(Lazy::try_get(&SLOW_LAZY), None);
+        assert!(Lazy::get_timeout(&SLOW_LAZY, Duration::from_millis(10)).is_err());
+        assert_eq!(Lazy::get_timeout(&SLOW_LAZY, Duration::from_secs(10)), Ok(&2));
+        assert_eq!(Lazy::try_get(&SLOW_LAZY), Some(&2));
+        assert_eq!(handle.join().unwrap(), 2);
+    }
+
+    #[test]
+    fn get_timeout_uninitialized() {
+        lazy_static! {
+            static ref FAST: u32 = 3;
+        }
+        assert_eq!(lazy_static::get_timeout(&FAST, Duration::from_millis(0)), Ok(&3));
+    }
+}
+
+#[cfg(not(feature="spin_no_std"))]
+mod lazy_map {
+    use std::sync::atomic::{AtomicUsize, Ordering::SeqCst};
+    use std::thread;
+    use lazy_static::sync::LazyMap;
+
+    static LENGTHS: LazyMap<String, usize> = LazyMap::new();
+    static CALLS: AtomicUsize = AtomicUsize::new(0);
+
+    fn length(s: &str) -> usize {
+        *LENGTHS.get_or_init(s.to_string(), || {
+            CALLS.fetch_add(1, SeqCst);
+            s.len()
+        })
+    }
+
+    #[test]
+    fn once_per_key() {
+        let handles: Vec<_> = (0..8).map(|_| thread::spawn(|| length("hello") + length("hi"))).collect();
+        for handle in handles {
+            assert_eq!(handle.join().unwrap(), 7);
+        }
+        assert_eq!(CALLS.load(SeqCst), 2);
+        assert_eq!(LENGTHS.get("hello"), Some(&5));
+        assert_eq!(LENGTHS.get("missing"), None);
+    }
+
+    #[derive(Debug, PartialEq)]
+    struct Port(u16);
+
+    #[test]
+    fn one_value_per_type() {
+        use lazy_static::sync::LazyTypeMap;
+
+        static TYPES: LazyTypeMap = LazyTypeMap::new();
+        assert_eq!(TYPES.get::<Port>(), None);
+        let port: &'static Port = TYPES.get_or_init(|| Port(80));
+        assert_eq!(TYPES.get_or_init(|| Port(8080)), port);
+        assert_eq!(*TYPES.get_or_init(|| String::from("name")), "name");
+        assert_eq!(TYPES.get::<Port>(), Some(&Port(80)));
+        assert_eq!(LazyTypeMap::global().get::<Port>(), None);
+    }
+}
+
+#[cfg(not(feature="spin_no_std"))]
+mod race {
+    use std::alloc::{GlobalAlloc, Layout, System};
+    use std::sync::atomic::{AtomicUsize, Ordering::SeqCst};
+    use std::thread;
+    use lazy_static::sync::RaceLazy;
+
+    static INITS: AtomicUsize = AtomicUsize::new(0);
+    static DROPS: AtomicUsize = AtomicUsize::new(0);
+
+    struct Counted(usize);
+
+    impl Drop for Counted {
+        fn drop(&mut self) {
+            DROPS.fetch_add(1, SeqCst);
+        }
+    }
+
+    #[test]
+    fn first_value_wins() {
+        let lazy = RaceLazy::new(|| Counted(INITS.fetch_add(1, SeqCst)));
+        assert!(!RaceLazy::is_initialized(&lazy));
+        let values: Vec<usize> = thread::scope(|scope| {
+            let handles: Vec<_> = (0..8).map(|_| scope.spawn(|| &*lazy as *const Counted as usize)).collect();
+            handles.into_iter().map(|handle| handle.join().unwrap()).collect()
+        });
+        assert!(values.iter().all(|&value| value == values[0]));
+        let inits = INITS.load(SeqCst);
+        assert!((1..=8).contains(&inits));
+        assert!(lazy.0 < inits);
+        // the values of the threads that lost the race are dropped
+        assert_eq!(DROPS.load(SeqCst), inits - 1);
+        assert!(RaceLazy::is_initialized(&lazy));
+        drop(lazy);
+        assert_eq!(DROPS.load(SeqCst), inits);
+    }
+
+    #[test]
+    fn into_inner() {
+        let mut lazy: RaceLazy<Vec<u32>> = RaceLazy::new(|| vec![1, 2]);
+        assert_eq!(RaceLazy::get_mut(&mut lazy), None);
+        assert_eq!(lazy.len(), 2);
+        RaceLazy::get_mut(&mut lazy).unwrap().push(3);
+        assert_eq!(RaceLazy::into_inner(lazy), Some(vec![1, 2, 3]));
+    }
+
+    struct Live<'a>(&'a AtomicUsize);
+
+    unsafe impl<'a> GlobalAlloc for Live<'a> {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            self.0.fetch_add(1, SeqCst);
+            System.alloc(layout)
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+            self.0.fetch_sub(1, SeqCst);
+            System.dealloc(ptr, layout)
+        }
+    }
+
+    #[test]
+    fn custom_allocator() {
+        let live = AtomicUsize::new(0);
+        let lazy = RaceLazy::new_in(|| [7u64; 4], Live(&live));
+        assert_eq!(live.load(SeqCst), 0);
+        assert_eq!(lazy[3], 7);
+        assert_eq!(live.load(SeqCst), 1);
+        assert_eq!(RaceLazy::into_inner(lazy), Some([7; 4]));
+        assert_eq!(live.load(SeqCst), 0);
+
+        let lazy = RaceLazy::new_in(|| (), Live(&live));
+        assert_eq!(*lazy, ());
+        assert_eq!(live.load(SeqCst), 0);
+    }
+}
+
+#[cfg(not(feature="spin_no_std"))]
+mod lazy_arc {
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering::SeqCst};
+    use std::thread;
+    use lazy_static::sync::LazyArc;
+
+    static CALLS: AtomicUsize = AtomicUsize::new(0);
+    static SHARED: LazyArc<Vec<u32>> = LazyArc::new(|| {
+        CALLS.fetch_add(1, SeqCst);
+        vec![1, 2, 3]
+    });
+
+    #[test]
+    fn hands_out_clones() {
+        assert!(!LazyArc::is_initialized(&SHARED));
+        assert_eq!(LazyArc::try_get(&SHARED), None);
+        let handles: Vec<_> = (0..4).map(|_| thread::spawn(|| LazyArc::get(&SHARED))).collect();
+        let arcs: Vec<Arc<Vec<u32>>> = handles.into_iter().map(|handle| handle.join().unwrap()).collect();
+        assert!(arcs.iter().all(|arc| Arc::ptr_eq(arc, &arcs[0])));
+        assert_eq!(*arcs[0], vec![1, 2, 3]);
+        assert_eq!(SHARED.len(), 3);
+        assert!(Arc::ptr_eq(&LazyArc::try_get(&SHARED).unwrap(), &arcs[0]));
+        assert_eq!(CALLS.load(SeqCst), 1);
+    }
+
+    static BUILDS: AtomicUsize = AtomicUsize::new(0);
+    static RELEASABLE: lazy_static::sync::ReleasableLazy<Vec<u32>> = lazy_static::sync::ReleasableLazy::new(|| {
+        BUILDS.fetch_add(1, SeqCst);
+        vec![4, 5]
+    });
+
+    #[test]
+    fn releasable() {
+        assert!(RELEASABLE.try_get().is_none());
+        let first = RELEASABLE.get();
+        let second = thread::spawn(|| RELEASABLE.get()).join().unwrap();
+        assert!(Arc::ptr_eq(&first, &second));
+        assert_eq!(BUILDS.load(SeqCst), 1);
+        drop(first);
+        assert!(RELEASABLE.is_initialized());
+        drop(second);
+        assert!(!RELEASABLE.is_initialized());
+        assert_eq!(*RELEASABLE.get(), vec![4, 5]);
+        assert_eq!(BUILDS.load(SeqCst), 2);
+    }
+}
+
+#[cfg(not(feature="spin_no_std"))]
+mod expiring {
+    use std::sync::atomic::{AtomicUsize, Ordering::SeqCst};
+    use std::thread;
+    use std::time::Duration;
+    use lazy_static::sync::ExpiringLazy;
+
+    static REFRESHES: AtomicUsize = AtomicUsize::new(0);
+    static TOKEN: ExpiringLazy<usize> = ExpiringLazy::new(Duration::from_millis(100), || REFRESHES.fetch_add(1, SeqCst));
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn refreshes_after_ttl() {
+        assert_eq!(TOKEN.try_get(), None);
+        assert_eq!(*TOKEN.get(), 0);
+        assert_eq!(*TOKEN.get(), 0);
+        thread::sleep(Duration::from_millis(150));
+        assert_eq!(TOKEN.try_get(), None);
+        let token = TOKEN.get();
+        assert_eq!(*token, 1);
+        TOKEN.invalidate();
+        assert_eq!(*TOKEN.get(), 2);
+        // a value that was handed out stays alive after being replaced
+        assert_eq!(*token, 1);
+    }
+}
+
+#[cfg(not(feature="spin_no_std"))]
+mod reload {
+    use std::sync::atomic::{AtomicUsize, Ordering::SeqCst};
+    use std::thread;
+    use lazy_static::sync::{EpochReloadableLazy, ReloadableLazy};
+
+    static INITS: AtomicUsize = AtomicUsize::new(0);
+    static FLAGS: ReloadableLazy<Vec<&'static str>> = ReloadableLazy::new(|| {
+        INITS.fetch_add(1, SeqCst);
+        vec!["a"]
+    });
+    static PRESET: ReloadableLazy<u32> = ReloadableLazy::new(|| panic!("not used"));
+    static COUNTER: ReloadableLazy<usize> = ReloadableLazy::new(|| 0);
+
+    #[test]
+    fn replace_keeps_snapshots() {
+        assert!(FLAGS.try_get().is_none());
+        let first = FLAGS.get();
+        assert_eq!(*first, ["a"]);
+        assert_eq!(FLAGS.replace(vec!["b"]).as_deref(), Some(&vec!["a"]));
+        assert_eq!(*FLAGS.get(), ["b"]);
+        assert_eq!(*FLAGS.update(|flags| flags.iter().chain(&["c"]).cloned().collect()), ["b", "c"]);
+        assert_eq!(*first, ["a"]);
+        assert_eq!(INITS.load(SeqCst), 1);
+
+        assert_eq!(PRESET.replace(3), None);
+        assert_eq!(*PRESET.get(), 3);
+    }
+
+    static SETTINGS: ReloadableLazy<u32> = ReloadableLazy::new(|| 1);
+
+    #[test]
+    fn generations() {
+        assert_eq!(SETTINGS.generation(), 0);
+        assert!(SETTINGS.try_get_with_generation().is_none());
+        let (value, generation) = SETTINGS.get_with_generation();
+        assert_eq!((*value, generation), (1, 1));
+        assert!(!SETTINGS.has_changed(generation));
+        SETTINGS.replace(2);
+        SETTINGS.update(|n| n + 1);
+        assert!(SETTINGS.has_changed(generation));
+        let (value, generation) = SETTINGS.try_get_with_generation().unwrap();
+        assert_eq!((*value, generation), (3, 3));
+        assert_eq!(SETTINGS.generation(), 3);
+    }
+
+    static HUP_LOADS: AtomicUsize = AtomicUsize::new(0);
+    static ON_HUP: ReloadableLazy<usize> = ReloadableLazy::new(|| HUP_LOADS.fetch_add(1, SeqCst));
+
+    #[test]
+    #[cfg(unix)]
+    #[cfg_attr(miri, ignore)]
+    fn reload_on_sighup() {
+        extern "C" {
+            fn raise(signum: i32) -> i32;
+        }
+
+        ON_HUP.reload_on_sighup();
+        assert_eq!(*ON_HUP.get(), 0);
+        assert_eq!(*ON_HUP.reload(), 1);
+        assert_eq!(unsafe { raise(1) }, 0);
+        for _ in 0..500 {
+            if ON_HUP.generation() == 3 {
+                break;
+            }
+            thread::sleep(std::time::Duration::from_millis(10));
+        }
+        assert_eq!(*ON_HUP.get(), 2);
+    }
+
+    static ON_USR2: ReloadableLazy<usize> = ReloadableLazy::new(|| 0);
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    #[cfg_attr(miri, ignore)]
+    fn reload_pipe_is_not_inherited() {
+        extern "C" {
+            fn fcntl(fd: i32, cmd: i32, ...) -> i32;
+        }
+        const F_GETFD: i32 = 1;
+        const FD_CLOEXEC: i32 = 1;
+
+        // `SIGUSR2` is never raised, so the reloading thread keeps waiting
+        ON_USR2.reload_on_signal(12);
+        let mut pipes = 0;
+        for entry in std::fs::read_dir("/proc/self/fd").unwrap() {
+            let entry = entry.unwrap();
+            let fd: i32 = entry.file_name().to_str().unwrap().parse().unwrap();
+            // the standard streams may be pipes of whoever runs the tests
+            if fd <= 2 {
+                continue;
+            }
+            let is_pipe = std::fs::read_link(entry.path())
+                .map(|target| target.to_string_lossy().starts_with("pipe:"))
+                .unwrap_or(false);
+            if is_pipe {
+                assert_ne!(unsafe { fcntl(fd, F_GETFD) } & FD_CLOEXEC, 0, "fd {} is inherited", fd);
+                pipes += 1;
+            }
+        }
+        assert!(pipes >= 2);
+    }
+
+    static ON_KILL: ReloadableLazy<usize> = ReloadableLazy::new(|| 0);
+
+    #[test]
+    #[cfg(unix)]
+    #[cfg_attr(miri, ignore)]
+    fn reload_on_uncatchable_signal() {
+        // `SIGKILL` cannot be handled
+        assert!(std::panic::catch_unwind(|| ON_KILL.reload_on_signal(9)).is_err());
+    }
+
+    fn watched_path() -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("lazy_static_watch_{}", std::process::id()))
+    }
+
+    static WATCHED: ReloadableLazy<String> = ReloadableLazy::new(|| {
+        std::fs::read_to_string(watched_path()).unwrap_or_default()
+    });
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn reload_on_change() {
+        let path = watched_path();
+        std::fs::write(&path, "one").unwrap();
+        assert_eq!(*WATCHED.get(), "one");
+        WATCHED.reload_on_change(&path, std::time::Duration::from_millis(10));
+        std::fs::write(&path, "second").unwrap();
+        for _ in 0..500 {
+            if *WATCHED.get() == "second" {
+                break;
+            }
+            thread::sleep(std::time::Duration::from_millis(10));
+        }
+        let generation = WATCHED.generation();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(generation, 2);
+    }
+
+    static EPOCH_DROPS: AtomicUsize = AtomicUsize::new(0);
+
+    struct Counted(usize);
+
+    impl Drop for Counted {
+        fn drop(&mut self) {
+            EPOCH_DROPS.fetch_add(1, SeqCst);
+        }
+    }
+
+    static EPOCH_VALUE: EpochReloadableLazy<Counted> = EpochReloadableLazy::new(|| Counted(0));
+
+    #[test]
+    fn epoch_readers_and_writers() {
+        assert!(EPOCH_VALUE.try_load().is_none());
+        let first = EPOCH_VALUE.load();
+        assert_eq!(first.0, 0);
+        let readers: Vec<_> = (0..3)
+            .map(|_| thread::spawn(|| {
+                let mut last = 0;
+                for _ in 0..200 {
+                    let value = EPOCH_VALUE.load();
+                    assert!(value.0 >= last);
+                    last = value.0;
+                }
+            }))
+            .collect();
+        for _ in 0..50 {
+            EPOCH_VALUE.update(|value| Counted(value.0 + 1));
+        }
+        for reader in readers {
+            reader.join().unwrap();
+        }
+        // the first value is still loaded on this thread
+        assert_eq!(first.0, 0);
+        drop(first);
+        EPOCH_VALUE.replace(Counted(100));
+        EPOCH_VALUE.reload();
+        assert_eq!(EPOCH_VALUE.load().0, 0);
+        assert_eq!(EPOCH_VALUE.generation(), 53);
+        assert!(EPOCH_DROPS.load(SeqCst) >= 49);
+    }
+
+    // every value is equal to its generation
+    static PAIRED: ReloadableLazy<usize> = ReloadableLazy::new(|| 1);
+
+    #[test]
+    fn readers_and_writers() {
+        let readers: Vec<_> = (0..3)
+            .map(|_| thread::spawn(|| {
+                let mut last = 0;
+                for _ in 0..200 {
+                    let (value, generation) = PAIRED.get_with_generation();
+                    assert_eq!(*value, generation);
+                    assert!(generation >= last);
+                    last = generation;
+                }
+            }))
+            .collect();
+        for _ in 0..50 {
+            PAIRED.update(|n| n + 1);
+        }
+        for reader in readers {
+            reader.join().unwrap();
+        }
+        assert_eq!(PAIRED.replace(52).as_deref(), Some(&51));
+        assert_eq!((*PAIRED.get(), PAIRED.generation()), (52, 52));
+    }
+
+    #[test]
+    fn updates_are_serialized() {
+        let threads: Vec<_> = (0..4)
+            .map(|_| thread::spawn(|| for _ in 0..25 { COUNTER.update(|n| n + 1); }))
+            .collect();
+        for thread in threads {
+            thread.join().unwrap();
+        }
+        assert_eq!(*COUNTER.get(), 100);
+    }
+}
+
+#[cfg(unix)]
+mod signal {
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    extern "C" {
+        fn signal(signum: i32, handler: extern "C" fn(i32)) -> usize;
+        fn raise(signum: i32) -> i32;
+    }
+
+    const SIGUSR1: i32 = if cfg!(any(target_os = "macos", target_os = "ios", target_os = "freebsd")) { 30 } else { 10 };
+
+    lazy_static! {
+        static ref CONFIG: u32 = 7;
+    }
+
+    static SEEN: AtomicU32 = AtomicU32::new(u32::MAX);
+
+    extern "C" fn handler(_: i32) {
+        SEEN.store(lazy_static::get(&CONFIG).copied().unwrap_or(0), Ordering::SeqCst);
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn get_from_signal_handler() {
+        unsafe {
+            signal(SIGUSR1, handler);
+            raise(SIGUSR1);
+        }
+        assert_eq!(SEEN.load(Ordering::SeqCst), 0);
+        lazy_static::initialize(&CONFIG);
+        unsafe {
+            signal(SIGUSR1, handler);
+            raise(SIGUSR1);
+        }
+        assert_eq!(SEEN.load(Ordering::SeqCst), 7);
+    }
+}
+
+#[cfg(not(feature="spin_no_std"))]
+mod dispatch {
+    fn scalar() -> &'static str {
+        "scalar"
+    }
+
+    lazy_dispatch! {
+        static ref KERNEL: fn() -> &'static str = {
+            #[cfg(target_arch = "x86_64")]
+            "sse2" => || "sse2",
+            #[cfg(target_arch = "aarch64")]
+            "neon" => || "neon",
+            _ => scalar,
+        };
+
+        pub(crate) static ref BOXED: Box<dyn Fn(u32) -> u32 + Send + Sync> = {
+            #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+            "avx2", "fma" => Box::new(|x| x * 2),
+            _ => Box::new(|x| x + x),
+        };
+    }
+
+    #[test]
+    fn picks_detected_implementation() {
+        let expected = if cfg!(target_arch = "x86_64") {
+            "sse2"
+        } else if cfg!(target_arch = "aarch64") {
+            "neon"
+        } else {
+            "scalar"
+        };
+        assert_eq!(KERNEL(), expected);
+        assert!(lazy_static::is_initialized(&KERNEL));
+        assert_eq!(BOXED(21), 42);
+    }
+}
+
+#[cfg(not(feature="spin_no_std"))]
+mod env {
+    use std::env;
+    use std::panic;
+
+    lazy_env! {
+        static DEFAULTED: u16 = "LAZY_STATIC_TEST_UNSET", default 8080;
+        pub static NAMES: String = "LAZY_STATIC_TEST_NAMES", default "a,b".to_string();
+        static THREADS: usize = "LAZY_STATIC_TEST_THREADS";
+        static INVALID: u16 = "LAZY_STATIC_TEST_INVALID", default 1;
+        static MISSING: String = "LAZY_STATIC_TEST_MISSING";
+    }
+
+    fn panic_message<F: FnOnce() + panic::UnwindSafe>(f: F) -> String {
+        *panic::catch_unwind(f).unwrap_err().downcast::<String>().unwrap()
+    }
+
+    #[test]
+    fn parse_variables() {
+        env::set_var("LAZY_STATIC_TEST_THREADS", "4");
+        env::set_var("LAZY_STATIC_TEST_INVALID", "lots");
+        assert_eq!(*DEFAULTED, 8080);
+        assert_eq!(*THREADS, 4);
+        assert_eq!(NAMES.split(',').count(), 2);
+        assert_eq!(
+            panic_message(|| { let _ = *INVALID; }),
+            "environment variable `LAZY_STATIC_TEST_INVALID` has the value \"lots\", \
+             which is not a valid `u16`: invalid digit found in string",
+        );
+        assert_eq!(
+            panic_message(|| { let _ = MISSING.len(); }),
+            "environment variable `LAZY_STATIC_TEST_MISSING` is not set",
+        );
+    }
+}
@@ -0,0 +1,74 @@
+#![cfg(all(feature="test-support", not(feature="strict")))]
+
+#[macro_use]
+extern crate lazy_static;
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use lazy_static::sync::Lazy;
+
+static RUNS: AtomicUsize = AtomicUsize::new(0);
+
+lazy_static! {
+    static ref COUNTED: usize = RUNS.fetch_add(1, Ordering::SeqCst) + 1;
+}
+
+#[test]
+fn reset_reinitializes() {
+    assert_eq!(*COUNTED, 1);
+    unsafe { lazy_static::reset(&COUNTED) };
+    assert!(!lazy_static::is_initialized(&COUNTED));
+    assert_eq!(*COUNTED, 2);
+}
+
+static VALUE: Lazy<Vec<u32>> = Lazy::new(|| vec![1, 2, 3]);
+
+#[test]
+fn reset_lazy_type() {
+    assert_eq!(*VALUE, [1, 2, 3]);
+    unsafe { Lazy::reset(&VALUE, || vec![4]) };
+    assert_eq!(*VALUE, [4]);
+}
+
+#[cfg(not(feature="spin_no_std"))]
+mod overrides {
+    use std::thread;
+
+    lazy_static! {
+        static ref ENDPOINT: String = "production".to_string();
+        static ref UNTOUCHED: Vec<u8> = vec![1];
+        static ref LABEL: str = "label".to_string().into_boxed_str();
+    }
+
+    #[test]
+    fn restores_previous_value() {
+        assert_eq!(*ENDPOINT, "production");
+        {
+            let _guard = unsafe { lazy_static::override_scoped(&ENDPOINT, "mock".to_string()) };
+            assert_eq!(*ENDPOINT, "mock");
+        }
+        assert_eq!(*ENDPOINT, "production");
+    }
+
+    #[test]
+    fn restores_uninitialized_state() {
+        {
+            let _guard = unsafe { lazy_static::override_scoped(&UNTOUCHED, vec![2]) };
+            assert_eq!(*UNTOUCHED, [2]);
+        }
+        assert!(!lazy_static::is_initialized(&UNTOUCHED));
+        assert_eq!(*UNTOUCHED, [1]);
+    }
+
+    #[test]
+    fn overrides_are_serialized() {
+        let guard = unsafe { lazy_static::override_scoped(&LABEL, "first".into()) };
+        let waiter = thread::spawn(|| {
+            let _guard = unsafe { lazy_static::override_scoped(&LABEL, "second".into()) };
+            LABEL.to_string()
+        });
+        assert_eq!(&*LABEL, "first");
+        drop(guard);
+        assert_eq!(waiter.join().unwrap(), "second");
+        assert_eq!(&*LABEL, "label");
+    }
+}
@@ -0,0 +1,72 @@
+#![cfg(all(feature="tokio", not(feature="spin_no_std"), not(feature="strict")))]
+
+#[macro_use]
+extern crate lazy_static;
+extern crate tokio;
+
+use std::future::{poll_fn, Future};
+use std::panic;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
+use tokio::runtime::{Builder, Runtime};
+use tokio::time::Sleep;
+
+/// A future resolving to a value once a tokio timer has elapsed, which
+/// only works inside of a runtime.
+struct Delayed(Pin<Box<Sleep>>, u32);
+
+impl Future for Delayed {
+    type Output = u32;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<u32> {
+        match self.0.as_mut().poll(cx) {
+            Poll::Ready(()) => Poll::Ready(self.1),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+fn delayed(millis: u64, value: u32) -> Delayed {
+    Delayed(Box::pin(tokio::time::sleep(Duration::from_millis(millis))), value)
+}
+
+lazy_static! {
+    static ref async SPAWNED: u32 = delayed(5, 1);
+    static ref async TIMED: u32 = delayed(1, 2);
+    static ref async REFUSED: u32 = std::future::ready(3);
+    static ref async PLAIN: u32 = std::future::ready(4);
+}
+
+fn runtime() -> Runtime {
+    Builder::new_current_thread().enable_time().build().unwrap()
+}
+
+#[test]
+fn spawn() {
+    let runtime = runtime();
+    let handle = runtime.block_on(poll_fn(|_| Poll::Ready(SPAWNED.spawn())));
+    assert_eq!(*runtime.block_on(handle).unwrap(), 1);
+    assert_eq!(*runtime.block_on(SPAWNED.get()), 1);
+}
+
+#[test]
+fn blocking_get_in_runtime() {
+    let runtime = runtime();
+    let handle = runtime.spawn_blocking(|| *TIMED.blocking_get());
+    assert_eq!(runtime.block_on(handle).unwrap(), 2);
+}
+
+#[test]
+fn blocking_get_on_worker() {
+    let result = runtime().block_on(poll_fn(|_| {
+        Poll::Ready(panic::catch_unwind(|| *REFUSED.blocking_get()))
+    }));
+    assert!(result.is_err());
+    assert_eq!(*REFUSED.blocking_get(), 3);
+}
+
+#[test]
+fn blocking_get_without_runtime() {
+    assert_eq!(*PLAIN.blocking_get(), 4);
+}
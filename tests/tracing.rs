@@ -0,0 +1,57 @@
+#![cfg(all(feature="tracing", not(feature="strict")))]
+
+#[macro_use]
+extern crate lazy_static;
+extern crate tracing;
+
+use std::fmt;
+use std::sync::Mutex;
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Metadata, Subscriber};
+
+// Records the `name` field of every span that is created.
+struct Names(Mutex<Vec<String>>);
+
+impl Visit for &Names {
+    fn record_debug(&mut self, _: &Field, _: &dyn fmt::Debug) {}
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "name" {
+            self.0.lock().unwrap().push(value.to_string());
+        }
+    }
+}
+
+impl Subscriber for &'static Names {
+    fn enabled(&self, _: &Metadata) -> bool {
+        true
+    }
+
+    fn new_span(&self, span: &Attributes) -> Id {
+        assert_eq!(span.metadata().name(), "lazy_static::initialize");
+        span.record(&mut &**self);
+        Id::from_u64(1)
+    }
+
+    fn record(&self, _: &Id, _: &Record) {}
+    fn record_follows_from(&self, _: &Id, _: &Id) {}
+    fn event(&self, _: &Event) {}
+    fn enter(&self, _: &Id) {}
+    fn exit(&self, _: &Id) {}
+}
+
+lazy_static! {
+    static ref NAMES: Names = Names(Mutex::new(Vec::new()));
+    static ref OUTER: u32 = *INNER + 1;
+    static ref INNER: u32 = 1;
+}
+
+#[test]
+fn span_per_initialization() {
+    let names: &'static Names = &NAMES;
+    tracing::subscriber::set_global_default(names).unwrap();
+    assert_eq!(*OUTER, 2);
+    assert_eq!(*OUTER, 2);
+    assert_eq!(*NAMES.0.lock().unwrap(), ["tracing::OUTER", "tracing::INNER"]);
+}